@@ -0,0 +1,126 @@
+//! Command-line arguments.
+
+use {
+  clap::{
+    Args,
+    Parser,
+    Subcommand,
+    ValueEnum,
+  },
+  env::prelude::{
+    ColorChoice,
+    ProjectConfig,
+  },
+};
+
+/// Advent of Code workspace manager.
+#[derive(Debug, Parser)]
+#[command(name = "aoc", version, about)]
+pub struct Cli {
+  /// Configuration overrides, taking precedence over every other layer.
+  #[command(flatten)]
+  pub overrides: Overrides,
+
+  /// What to do.
+  #[command(subcommand)]
+  pub command: Command,
+}
+
+/// Configuration values set on the command line.
+#[derive(Debug, Default, Args)]
+pub struct Overrides {
+  /// Database URL or path.
+  #[arg(long, global = true, value_name = "URL")]
+  pub db: Option<String>,
+
+  /// Server bind address.
+  #[arg(long, global = true)]
+  pub ip: Option<String>,
+
+  /// Server port.
+  #[arg(long, global = true)]
+  pub port: Option<u16>,
+
+  /// Tracing filter directives, in `RUST_LOG` syntax.
+  #[arg(long = "log", global = true, value_name = "FILTER")]
+  pub rust_log: Option<String>,
+
+  /// When to color output: auto, always or never.
+  #[arg(long, global = true, value_name = "WHEN")]
+  pub color: Option<ColorChoice>,
+}
+
+impl Overrides {
+  /// Applies the values given on the command line as the override layer.
+  pub fn apply(&self, mut config: ProjectConfig) -> ProjectConfig {
+    if let Some(db) = &self.db {
+      config = config.with_db(db);
+    }
+    if let Some(ip) = &self.ip {
+      config = config.with_ip(ip);
+    }
+    if let Some(port) = self.port {
+      config = config.with_port(port);
+    }
+    if let Some(rust_log) = &self.rust_log {
+      config = config.with_rust_log(rust_log);
+    }
+    if let Some(color) = self.color {
+      config = config.with_color(color);
+    }
+    config
+  }
+}
+
+/// Top-level subcommands.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+  /// Inspect the layered configuration.
+  #[command(subcommand)]
+  Config(ConfigCommand),
+
+  /// Check the environment, toolchain, session key and puzzle data.
+  Doctor {
+    /// Print the snapshot as TOML, for bug reports, instead of a checklist.
+    #[arg(long)]
+    toml: bool,
+  },
+
+  /// List the members that depend on a crate, directly or not, to know
+  /// which days to re-test after changing it.
+  #[cfg(feature = "metadata")]
+  Dependents {
+    /// Package name, such as `grid`.
+    package: String,
+
+    /// Only list solution crates.
+    #[arg(long)]
+    solutions: bool,
+  },
+}
+
+/// `aoc config` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+  /// Print every configuration value.
+  Show {
+    /// Also print the layer each value came from.
+    #[arg(long)]
+    origin: bool,
+
+    /// Output format; `json` always includes the origins.
+    #[cfg(feature = "serde")]
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+  },
+}
+
+/// How `aoc config show` prints the configuration.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  /// `key = "value"` lines.
+  #[default]
+  Text,
+  /// A JSON object, for scripts.
+  Json,
+}
@@ -1,17 +1,27 @@
-fn main() -> miette::Result<()> {
-  //{ Initialize logging }
-  logline::init();
+mod cli;
+mod workspace;
 
+use {
+  clap::Parser,
+  cli::Cli,
+  env::prelude::*,
+  workspace::Workspace,
+};
+
+fn main() -> miette::Result<()> {
   //{ Parse command line arguments }
   let cli = Cli::parse();
-  debug!("{:#?}", cli);
 
   //{ Identify the current configuration }
-  let bin = Workspace::define()?;
+  let bin = Workspace::define(&cli.overrides)?;
+
+  //{ Initialize logging }
+  logline::init();
+  debug!("{:#?}", cli);
   debug!("{:#?}", bin);
 
   //{ Execute the parsed command }
-  bin.execute_command(&cli)?; // No change here, as the term_size is now handled within execute_command.
+  bin.execute_command(&cli)?;
 
   //{ Finish }
   Ok(())
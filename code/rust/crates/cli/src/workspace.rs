@@ -0,0 +1,166 @@
+//! The workspace the CLI operates on.
+
+use {
+  crate::cli::{
+    Cli,
+    Command,
+    ConfigCommand,
+    Format,
+    Overrides,
+  },
+  env::prelude::*,
+  miette::{
+    IntoDiagnostic,
+    Result,
+  },
+  std::io::{
+    Write,
+    stdout,
+  },
+};
+
+/// The current workspace, as described by the global environment.
+#[derive(Debug)]
+pub struct Workspace {
+  env: &'static Environment,
+}
+
+impl Workspace {
+  /// Identifies the workspace and initializes the global environment with
+  /// this binary's own package metadata and the command-line overrides, so
+  /// the override layer wins everywhere, logging included.
+  ///
+  /// # Errors
+  /// If the workspace files or configuration are invalid; the diagnostic
+  /// points at the offending line.
+  pub fn define(overrides: &Overrides) -> Result<Self> {
+    let mut env = Environment::try_new()?.with_pkg_manifest(env!("CARGO_MANIFEST_DIR"));
+    env.project.configuration = overrides.apply(env.project.configuration);
+    Ok(Self { env: init_env(env) })
+  }
+
+  /// Runs the parsed command.
+  ///
+  /// # Errors
+  /// If the command fails, e.g. when stdout is closed early.
+  pub fn execute_command(&self, cli: &Cli) -> Result<()> {
+    match &cli.command {
+      #[cfg(feature = "serde")]
+      Command::Config(ConfigCommand::Show { origin, format }) => {
+        self.show_config(*origin, *format)
+      }
+      #[cfg(not(feature = "serde"))]
+      Command::Config(ConfigCommand::Show { origin }) => {
+        self.show_config(*origin, Format::Text)
+      }
+      Command::Doctor { toml } => self.doctor(*toml),
+      #[cfg(feature = "metadata")]
+      Command::Dependents { package, solutions } => {
+        self.dependents(package, *solutions)
+      }
+    }
+  }
+
+  /// Prints a diagnostic snapshot, as a checklist or as TOML.
+  ///
+  /// The checklist fails if any check does, so scripts can gate on it.
+  fn doctor(&self, toml: bool) -> Result<()> {
+    let theme = self.env.project.configuration.theme();
+    let snapshot = Snapshot::capture(self.env);
+    let mut out = stdout().lock();
+    if toml {
+      return write!(out, "{}", snapshot.to_toml()).into_diagnostic();
+    }
+
+    let checks = snapshot.checks();
+    for check in &checks {
+      let role = match check.status {
+        CheckStatus::Pass => Role::Success,
+        CheckStatus::Warn => Role::Warning,
+        CheckStatus::Fail => Role::Failure,
+      };
+      writeln!(
+        out,
+        "{}  {}: {}",
+        theme.paint(role, format_args!("{:>4}", check.status)),
+        check.label,
+        check.detail
+      )
+      .into_diagnostic()?;
+      if let Some(hint) = &check.hint {
+        writeln!(
+          out,
+          "      {}",
+          theme.paint(Role::Dim, format_args!("hint: {hint}"))
+        )
+        .into_diagnostic()?;
+      }
+    }
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+    let failed = count(CheckStatus::Fail);
+    let summary = format!(
+      "{} ok, {} warnings, {failed} failures",
+      count(CheckStatus::Pass),
+      count(CheckStatus::Warn)
+    );
+    writeln!(out, "\n{}", theme.paint(Role::Heading, summary)).into_diagnostic()?;
+    if failed > 0 {
+      miette::bail!("{failed} doctor checks failed");
+    }
+    Ok(())
+  }
+
+  /// Prints the workspace members that depend on `package`, with the
+  /// puzzle of each solution crate.
+  #[cfg(feature = "metadata")]
+  fn dependents(&self, package: &str, solutions: bool) -> Result<()> {
+    let theme = self.env.project.configuration.theme();
+    let graph = WorkspaceGraph::cached()?;
+    if graph.package(package).is_none() {
+      miette::bail!("no package named `{package}` in the workspace");
+    }
+    let mut out = stdout().lock();
+    for dependent in graph
+      .reverse_deps(package)
+      .into_iter()
+      .filter(|dependent| dependent.member)
+    {
+      match dependent.solution {
+        Some(solution) => writeln!(
+          out,
+          "{}  {}",
+          theme.paint(Role::Star, solution),
+          dependent.name
+        ),
+        None if solutions => continue,
+        None => writeln!(out, "{}", dependent.name),
+      }
+      .into_diagnostic()?;
+    }
+    Ok(())
+  }
+
+  /// Prints every configuration value, with command-line overrides applied
+  /// and registered secrets, such as a database password, redacted.
+  fn show_config(&self, origin: bool, format: Format) -> Result<()> {
+    let config = &self.env.project.configuration;
+    let theme = config.theme();
+    let mut out = stdout().lock();
+    #[cfg(feature = "serde")]
+    if format == Format::Json {
+      let json = serde_json::to_string_pretty(&config).into_diagnostic()?;
+      return writeln!(out, "{}", redact(&json)).into_diagnostic();
+    }
+    for (key, value, source) in config.entries() {
+      let value = redact(&value);
+      if origin {
+        let source = theme.paint(Role::Dim, format_args!("# {source}"));
+        writeln!(out, "{key} = {value:?}  {source}")
+      } else {
+        writeln!(out, "{key} = {value:?}")
+      }
+      .into_diagnostic()?;
+    }
+    Ok(())
+  }
+}
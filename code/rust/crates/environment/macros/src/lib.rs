@@ -0,0 +1,179 @@
+//! Procedural macros for the `env` crate.
+//!
+//! Use them through `env`'s `macros` feature rather than depending on this
+//! crate directly: the generated code refers to `::env` for its runtime
+//! support.
+
+use {
+  proc_macro::TokenStream,
+  proc_macro2::TokenStream as TokenStream2,
+  quote::{
+    format_ident,
+    quote,
+  },
+  syn::{
+    Error,
+    Expr,
+    FnArg,
+    GenericParam,
+    Ident,
+    ItemFn,
+    Pat,
+    Result,
+    ReturnType,
+    Type,
+    meta::ParseNestedMeta,
+    parse_macro_input,
+  },
+};
+
+/// Caches the results of a free function.
+///
+/// See `env::memo` for the options and examples.
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+  let mut options = Options::default();
+  let parser = syn::meta::parser(|meta| options.parse(&meta));
+  parse_macro_input!(attr with parser);
+  let function = parse_macro_input!(item as ItemFn);
+
+  expand(&options, function)
+    .unwrap_or_else(Error::into_compile_error)
+    .into()
+}
+
+/// Where the cache lives.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+  /// One cache per thread, kept until cleared.
+  #[default]
+  Thread,
+  /// One cache per outermost call, dropped when it returns.
+  Call,
+}
+
+#[derive(Default)]
+struct Options {
+  key: Option<Expr>,
+  scope: Scope,
+}
+
+impl Options {
+  fn parse(&mut self, meta: &ParseNestedMeta<'_>) -> Result<()> {
+    if meta.path.is_ident("key") {
+      self.key = Some(meta.value()?.parse()?);
+    } else if meta.path.is_ident("scope") {
+      let scope: Ident = meta.value()?.parse()?;
+      self.scope = match scope.to_string().as_str() {
+        "thread" => Scope::Thread,
+        "call" => Scope::Call,
+        _ => return Err(Error::new(scope.span(), "expected `thread` or `call`")),
+      };
+    } else {
+      return Err(meta.error("expected `key = ...` or `scope = thread | call`"));
+    }
+    Ok(())
+  }
+}
+
+fn expand(options: &Options, function: ItemFn) -> Result<TokenStream2> {
+  let ItemFn {
+    attrs,
+    vis,
+    sig,
+    block,
+  } = function;
+
+  if let Some(asyncness) = sig.asyncness {
+    return Err(Error::new_spanned(
+      asyncness,
+      "cannot memoize an async function",
+    ));
+  }
+  if let Some(param) = sig
+    .generics
+    .params
+    .iter()
+    .find(|param| !matches!(param, GenericParam::Lifetime(_)))
+  {
+    return Err(Error::new_spanned(
+      param,
+      "cannot memoize a generic function: every instance would share one cache",
+    ));
+  }
+  if matches!(sig.output, ReturnType::Default) {
+    return Err(Error::new_spanned(
+      &sig,
+      "a memoized function must return a value",
+    ));
+  }
+
+  let mut names = Vec::new();
+  let mut owned = Vec::new();
+  for input in &sig.inputs {
+    let FnArg::Typed(input) = input else {
+      return Err(Error::new_spanned(input, "cannot memoize a method"));
+    };
+    let Pat::Ident(pattern) = &*input.pat else {
+      return Err(Error::new_spanned(
+        &input.pat,
+        "memoized arguments must be plain names",
+      ));
+    };
+    let name = &pattern.ident;
+    owned.push(if let Type::Reference(_) = &*input.ty {
+      quote!(::std::borrow::ToOwned::to_owned(#name))
+    } else {
+      quote!(::std::clone::Clone::clone(&#name))
+    });
+    names.push(name.clone());
+  }
+
+  let key = options
+    .key
+    .as_ref()
+    .map_or_else(|| quote!((#(#owned,)*)), |key| quote!(#key));
+  let cache = format_ident!("__MEMOIZE_{}", sig.ident.to_string().to_uppercase());
+  let clear = format_ident!("clear_{}", sig.ident);
+  let scope =
+    (options.scope == Scope::Call).then(|| quote!(let _scope = ::env::memo::Memo::enter(&#cache);));
+
+  let mut outer = sig.clone();
+  for input in &mut outer.inputs {
+    if let FnArg::Typed(input) = input
+      && let Pat::Ident(pattern) = &mut *input.pat
+    {
+      pattern.mutability = None;
+    }
+  }
+  let mut inner = sig;
+  inner.ident = format_ident!("__memoize_{}_inner", inner.ident);
+  let compute = &inner.ident;
+
+  Ok(quote! {
+    ::std::thread_local! {
+      #[doc(hidden)]
+      #[allow(non_upper_case_globals)]
+      static #cache: ::env::memo::Memo = const { ::env::memo::Memo::new() };
+    }
+
+    #(#attrs)*
+    #vis #outer {
+      #inner #block
+
+      #scope
+      let key = #key;
+      if let ::std::option::Option::Some(value) = #cache.with(|memo| memo.get(&key)) {
+        return value;
+      }
+      let value = #compute(#(#names),*);
+      #cache.with(|memo| memo.insert(key, ::std::clone::Clone::clone(&value)));
+      value
+    }
+
+    /// Forgets every result cached on this thread.
+    #vis fn #clear() {
+      #cache.with(::env::memo::Memo::clear);
+    }
+  })
+}
@@ -21,7 +21,7 @@
 //! ## Option 2: Custom Initialization
 //! ```no_run
 //! use env::prelude::*;
-//! let env = set_env(
+//! let env = init_env(
 //!   Environment::new()
 //!     .with_pkg_name(env!("CARGO_PKG_NAME"))
 //!     .with_pkg_version(env!("CARGO_PKG_VERSION"))
@@ -48,14 +48,20 @@
 //!
 //! # Lifetime
 //!
-//! Returns `&'static Environment` - valid for the entire program lifetime. Perfect for use
-//! in macros like `getenv!()` which need static references.
+//! Returns `&'static Environment` - valid for the entire program lifetime, so callers can
+//! hold on to it freely.
+//!
+//! # Overrides and Reloading
+//!
+//! `get_env()` always returns the global instance. Tests can layer another environment over
+//! it on one thread with `with_env()`, which `current_env()` and `getenv!()` respect, and
+//! long-running commands can follow configuration edits with `Reloadable` (`watch` feature).
 
 use super::_prelude::*;
 pub use {
   get as get_env,
   init as init_env,
-  // try_get as try_get_env,
+  try_get as try_get_env,
 };
 
 /// Global static environment instance, protected by `OnceLock` for safe one-time initialization
@@ -82,9 +88,7 @@ pub static ENV: OnceLock<Environment> = OnceLock::new();
 /// println!("Package: {}", env.package.metadata.name);
 /// ```
 pub fn get() -> &'static Environment {
-  // Run only once in the top-level environment.
-  dotenv().ok();
-  (ENV.get_or_init(Environment::new)) as _
+  ENV.get_or_init(Environment::new)
 }
 
 /// Initialize the global environment with a custom `Environment` instance.
@@ -108,7 +112,7 @@ pub fn get() -> &'static Environment {
 /// ```no_run
 /// use env::prelude::*;
 /// // Configure before any other code uses get_env()
-/// set_env(
+/// init_env(
 ///   Environment::new()
 ///     .with_pkg_name(env!("CARGO_PKG_NAME"))
 ///     .with_pkg_version(env!("CARGO_PKG_VERSION"))
@@ -119,13 +123,41 @@ pub fn get() -> &'static Environment {
 /// let pkg_name = getenv!(pkg_name);
 /// ```
 pub fn init(env: Environment) -> &'static Environment {
-  dotenv().ok();
   ENV.get_or_init(|| env)
 }
 
-// pub fn try_get() -> Option<&'static Environment> {
-//   ENV.get()
-// }
+/// Retrieve the cached global environment, reporting why it could not be
+/// created.
+///
+/// Like `get()`, but instead of printing unreadable workspace metadata and
+/// panicking on invalid configuration, the first call returns the
+/// diagnostic, which points at the offending line of `Cargo.toml`, `.env` or
+/// `aoc.toml`. Failures are not cached; once initialized, this is as cheap
+/// as `get()`.
+///
+/// # Errors
+/// See `Environment::try_new()`.
+///
+/// # Examples
+/// ```no_run
+/// use env::prelude::*;
+///
+/// fn main() -> MietteResult<()> {
+///   let env = try_get_env()?;
+///   println!(
+///     "Serving on {}:{}",
+///     env.project.configuration.ip, env.project.configuration.port
+///   );
+///   Ok(())
+/// }
+/// ```
+pub fn try_get() -> Result<&'static Environment> {
+  if let Some(env) = ENV.get() {
+    return Ok(env);
+  }
+  let env = Environment::try_new()?;
+  Ok(ENV.get_or_init(|| env))
+}
 
 /// Top-level application environment container.
 ///
@@ -146,6 +178,7 @@ pub fn init(env: Environment) -> &'static Environment {
 ///   .with_port(8080);
 /// ```
 #[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Environment {
   pub project: Project,
   pub package: Package,
@@ -160,6 +193,120 @@ impl Environment {
     Self::default()
   }
 
+  /// Creates a new environment, reporting invalid workspace files and
+  /// configuration values as diagnostics.
+  ///
+  /// # Errors
+  /// - `Error::Io` if the workspace `Cargo.toml` or a layer file cannot be read
+  /// - `Error::Toml` if `Cargo.toml` or `aoc.toml` is not valid TOML
+  /// - `Error::Dotenv` if a `.env` line is not a `KEY=value` assignment
+  /// - `Error::Value` if a configuration value is invalid, such as a `port`
+  ///   that is not a u16
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::prelude::*;
+  ///
+  /// let env = Environment::try_new()?.with_pkg_name(env!("CARGO_PKG_NAME"));
+  /// # Ok::<(), EnvError>(())
+  /// ```
+  pub fn try_new() -> Result<Self> {
+    Ok(Self {
+      project: Project::try_new()?,
+      package: Package::new(),
+    })
+  }
+
+  /// Re-reads the project configuration files, as `Reloadable` does when
+  /// they change.
+  ///
+  /// Metadata, paths, the package and any values set by environment
+  /// variables or `with_*` overrides are kept.
+  ///
+  /// # Errors
+  /// See `Environment::try_new()`.
+  pub fn try_reload(&self) -> Result<Self> {
+    Ok(Self {
+      project: self.project.try_reload()?,
+      package: self.package.clone(),
+    })
+  }
+
+  /// This environment as a TOML document, readable by `from_file()`.
+  ///
+  /// Configuration origins are included for inspection but are not read
+  /// back.
+  ///
+  /// # Errors
+  /// `Error::Serialize` if a value has no TOML representation.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::prelude::*;
+  ///
+  /// std::fs::write("fixture.toml", Environment::try_new()?.to_toml()?)?;
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  #[cfg(feature = "serde")]
+  pub fn to_toml(&self) -> Result<String> {
+    to_toml_string_pretty(self).map_err(|error| Error::Serialize {
+      format: "TOML",
+      message: error.to_string(),
+    })
+  }
+
+  /// This environment as pretty-printed JSON, readable by `from_file()`.
+  ///
+  /// # Errors
+  /// `Error::Serialize` if a value has no JSON representation.
+  #[cfg(feature = "serde")]
+  pub fn to_json(&self) -> Result<String> {
+    serde_json::to_string_pretty(self).map_err(|error| Error::Serialize {
+      format: "JSON",
+      message: error.to_string(),
+    })
+  }
+
+  /// Loads an environment written by `to_toml()` or `to_json()`, such as a
+  /// test fixture; `.json` files are read as JSON, anything else as TOML.
+  ///
+  /// Nothing is discovered or read from the process environment, and every
+  /// configuration origin is `Source::Default`.
+  ///
+  /// # Errors
+  /// - `Error::Io` if the file cannot be read
+  /// - `Error::Toml` or `Error::Json` if it is malformed or a field is
+  ///   missing, pointing at the position
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// let env = Environment::new()
+  ///   .with_pkg_name("fixture")
+  ///   .with_port(4000_u16);
+  /// let path = std::env::temp_dir().join(format!("env-fixture-{}.json", std::process::id()));
+  /// std::fs::write(&path, env.to_json()?)?;
+  ///
+  /// let loaded = Environment::from_file(&path)?;
+  /// assert_eq!(loaded.package.metadata.name, "fixture");
+  /// assert_eq!(loaded.project.configuration.port, 4000);
+  /// assert_eq!(loaded.project.paths.project, env.project.paths.project);
+  /// # std::fs::remove_file(&path)?;
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  #[cfg(feature = "serde")]
+  pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+    let path = path.as_ref();
+    let contents = read_to_string(path).map_err(|error| Error::io(path, error))?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("json") => {
+        serde_json::from_str(&contents).map_err(|error| Error::json(path, &contents, &error))
+      }
+      _ => from_toml_str(&contents).map_err(|error| Error::toml(path, &contents, &error)),
+    }
+  }
+
   /// Sets the project name (stored in package metadata).
   ///
   /// **Note:** This sets package name, not project name. For the actual project/workspace name,
@@ -211,6 +358,26 @@ impl Environment {
     self
   }
 
+  /// Sets the tracing filter directives.
+  ///
+  /// Overrides the `RUST_LOG` environment variable (default: empty).
+  #[must_use]
+  pub fn with_rust_log(mut self, rust_log: impl Into<String>) -> Self {
+    self.project = self.project.with_rust_log(rust_log);
+    self
+  }
+
+  /// Reads the package metadata from the crate manifest in `manifest_dir`,
+  /// resolving fields inherited from the workspace.
+  ///
+  /// Pass the calling crate's `env!("CARGO_MANIFEST_DIR")`. Left unchanged if
+  /// the manifest cannot be read, e.g. for an installed binary.
+  #[must_use]
+  pub fn with_pkg_manifest(mut self, manifest_dir: impl AsRef<Path>) -> Self {
+    self.package = self.package.with_manifest(manifest_dir);
+    self
+  }
+
   /// Sets the package name (current running crate).
   ///
   /// This is the name of the binary/library being executed, distinct from workspace name.
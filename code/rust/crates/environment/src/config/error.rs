@@ -1,4 +1,11 @@
-use super::_prelude::*;
+use {
+  super::_prelude::*,
+  miette::{
+    NamedSource,
+    SourceSpan,
+  },
+  toml::de::DeTable,
+};
 
 pub type Result<T> = StdResult<T, Error>;
 
@@ -10,4 +17,256 @@ pub enum Error {
     help("Consider converting this to a more descriptive error message.")
   )]
   Generic(String),
+
+  #[error("could not set up logging: {0}")]
+  #[diagnostic(
+    code(env::logging),
+    help("Check the `RUST_LOG` directives and that no other logger was installed first.")
+  )]
+  Logging(String),
+
+  #[error("could not read {}", path.display())]
+  #[diagnostic(code(env::io), help("Check that the file exists and is readable."))]
+  Io {
+    path: PathBuf,
+    #[source]
+    source: IOError,
+  },
+
+  #[error("could not write {}", path.display())]
+  #[diagnostic(
+    code(env::write),
+    help("Check that the directory exists and the file is writable.")
+  )]
+  Write {
+    path: PathBuf,
+    #[source]
+    source: IOError,
+  },
+
+  #[error("cannot edit {}: {reason}", path.display())]
+  #[diagnostic(
+    code(env::manifest),
+    help("Declare what the edit refers to first, or edit the manifest by hand.")
+  )]
+  Manifest { path: PathBuf, reason: String },
+
+  #[error("could not watch the project for changes: {0}")]
+  #[diagnostic(
+    code(env::watch),
+    help(
+      "Check that the project root exists and that the system's file watch limit is not exhausted."
+    )
+  )]
+  Watch(String),
+
+  #[error("could not read the workspace graph: {0}")]
+  #[diagnostic(
+    code(env::metadata),
+    help("Check that `cargo metadata` succeeds in the workspace; it may need network access.")
+  )]
+  Metadata(String),
+
+  #[error("could not write the environment as {format}: {message}")]
+  #[diagnostic(
+    code(env::serialize),
+    help("Every environment field should be representable; please report this as a bug.")
+  )]
+  Serialize {
+    format: &'static str,
+    message: String,
+  },
+
+  #[error(transparent)]
+  #[diagnostic(transparent)]
+  Toml(Box<TomlError>),
+
+  #[error(transparent)]
+  #[diagnostic(transparent)]
+  Json(Box<JsonError>),
+
+  #[error(transparent)]
+  #[diagnostic(transparent)]
+  Dotenv(Box<DotenvError>),
+
+  #[error(transparent)]
+  #[diagnostic(transparent)]
+  Value(Box<ValueError>),
+
+  #[error(transparent)]
+  #[diagnostic(transparent)]
+  Inherit(Box<InheritError>),
+}
+
+/// A configuration file that is not valid TOML.
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("invalid TOML in {}: {message}", path.display())]
+#[diagnostic(
+  code(env::toml),
+  help("Fix the syntax at the marked position; see https://toml.io for the format.")
+)]
+pub struct TomlError {
+  pub path: PathBuf,
+  pub message: String,
+  #[source_code]
+  pub src: NamedSource<String>,
+  #[label("here")]
+  pub span: Option<SourceSpan>,
+}
+
+/// A JSON file that is not valid JSON, or not the expected shape.
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("invalid JSON in {}: {message}", path.display())]
+#[diagnostic(
+  code(env::json),
+  help("Fix the value at the marked position; `Environment::to_json()` shows the expected shape.")
+)]
+pub struct JsonError {
+  pub path: PathBuf,
+  pub message: String,
+  #[source_code]
+  pub src: NamedSource<String>,
+  #[label("here")]
+  pub span: Option<SourceSpan>,
+}
+
+/// A `.env` line that is not a `KEY=value` assignment.
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("invalid line in {}", path.display())]
+#[diagnostic(
+  code(env::dotenv),
+  help("Each line must be `KEY=value`, optionally prefixed with `export `, or a `#` comment.")
+)]
+pub struct DotenvError {
+  pub path: PathBuf,
+  #[source_code]
+  pub src: NamedSource<String>,
+  #[label("expected `KEY=value`")]
+  pub span: SourceSpan,
+}
+
+/// A configuration value that cannot be used, with the place it was set.
+///
+/// `src` and `span` are absent when the value came from the process
+/// environment rather than a file.
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("invalid `{key}` value {value:?} from {origin}")]
+#[diagnostic(code(env::config::value))]
+pub struct ValueError {
+  pub key: &'static str,
+  pub value: String,
+  pub origin: String,
+  #[help]
+  pub help: String,
+  #[source_code]
+  pub src: Option<NamedSource<String>>,
+  #[label("set here")]
+  pub span: Option<SourceSpan>,
+}
+
+/// A `{ workspace = true }` manifest field the workspace cannot provide.
+#[derive(Debug, ThisError, Diagnostic)]
+#[error("cannot inherit `{key}` in {}: {reason}", path.display())]
+#[diagnostic(
+  code(env::package::inherit),
+  help("Set `{key}` in `[workspace.package]` of the workspace Cargo.toml, or in this manifest.")
+)]
+pub struct InheritError {
+  pub key: String,
+  pub path: PathBuf,
+  pub reason: &'static str,
+  #[source_code]
+  pub src: NamedSource<String>,
+  #[label("inherited here")]
+  pub span: Option<SourceSpan>,
+}
+
+impl Error {
+  /// An unreadable file.
+  #[must_use]
+  pub fn io(path: impl Into<PathBuf>, source: IOError) -> Self {
+    Self::Io {
+      path: path.into(),
+      source,
+    }
+  }
+
+  /// A TOML syntax error in the file at `path` with `contents`.
+  #[must_use]
+  pub fn toml(path: &Path, contents: &str, error: &toml::de::Error) -> Self {
+    Self::Toml(Box::new(TomlError {
+      path: path.to_path_buf(),
+      message: error.message().trim().to_owned(),
+      src: named_source(path, contents),
+      span: error.span().map(SourceSpan::from),
+    }))
+  }
+
+  /// A TOML syntax error found while parsing the file at `path` for editing.
+  #[must_use]
+  pub fn toml_edit(path: &Path, contents: &str, error: &toml_edit::TomlError) -> Self {
+    Self::Toml(Box::new(TomlError {
+      path: path.to_path_buf(),
+      message: error.message().trim().to_owned(),
+      src: named_source(path, contents),
+      span: error.span().map(SourceSpan::from),
+    }))
+  }
+
+  /// A JSON syntax or shape error in the file at `path` with `contents`.
+  #[cfg(feature = "serde")]
+  #[must_use]
+  pub fn json(path: &Path, contents: &str, error: &serde_json::Error) -> Self {
+    let message = error.to_string();
+    let message = message
+      .rsplit_once(" at line ")
+      .map_or(message.as_str(), |(message, _)| message);
+    let span = (error.line() > 0).then(|| {
+      let line: usize = contents
+        .split_inclusive('\n')
+        .take(error.line() - 1)
+        .map(str::len)
+        .sum();
+      SourceSpan::from((line + error.column().saturating_sub(1)).min(contents.len()))
+    });
+    Self::Json(Box::new(JsonError {
+      path: path.to_path_buf(),
+      message: message.to_owned(),
+      src: named_source(path, contents),
+      span,
+    }))
+  }
+
+  /// A malformed line of the `.env` file at `path` with `contents`.
+  #[must_use]
+  pub fn dotenv(path: &Path, contents: &str, span: impl Into<SourceSpan>) -> Self {
+    Self::Dotenv(Box::new(DotenvError {
+      path: path.to_path_buf(),
+      src: named_source(path, contents),
+      span: span.into(),
+    }))
+  }
+}
+
+/// A file's contents, labelled with its path for diagnostics.
+#[must_use]
+pub fn named_source(path: &Path, contents: &str) -> NamedSource<String> {
+  let language = match path.extension().and_then(|extension| extension.to_str()) {
+    Some("toml") => "TOML",
+    Some("json") => "JSON",
+    _ => "Shell",
+  };
+  NamedSource::new(path.display().to_string(), contents.to_owned()).with_language(language)
+}
+
+/// The span of the value at `keys` in a TOML document.
+#[must_use]
+pub fn toml_span(contents: &str, keys: &[&str]) -> Option<SourceSpan> {
+  let root = DeTable::parse(contents).ok()?;
+  let (last, parents) = keys.split_last()?;
+  let mut table = root.get_ref();
+  for key in parents {
+    table = table.get(*key)?.get_ref().as_table()?;
+  }
+  Some(table.get(*last)?.span().into())
 }
@@ -1,14 +1,39 @@
+#[cfg(feature = "watch")]
+pub use super::internal::{
+  Reload,
+  Reloadable,
+};
 pub use super::{
   super::{
     package::prelude::*,
     project::prelude::*,
   },
   internal::{
+    Assets,
+    Check,
+    CurrentEnv,
+    DotenvError as EnvDotenvError,
     ENV,
     Environment,
     Error as EnvError,
+    Features,
+    InheritError as EnvInheritError,
+    JsonError as EnvJsonError,
+    PathStatus,
+    Provenance,
     Result as EnvResult,
+    SESSION_VARIABLE,
+    Session,
+    Snapshot,
+    Status as CheckStatus,
+    TomlError as EnvTomlError,
+    Toolchain,
+    ValueError as EnvValueError,
+    YearAssets,
+    current_env,
     get_env,
     init_env,
+    try_get_env,
+    with_env,
   },
 };
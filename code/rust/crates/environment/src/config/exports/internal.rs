@@ -1,9 +1,13 @@
+#[cfg(feature = "watch")]
+pub use super::super::reload::*;
 pub use {
   super::super::{
     core::*,
     error::*,
     package::prelude::*,
     project::prelude::*,
+    scope::*,
+    snapshot::*,
   },
   crate::prelude::*,
   dotenv::dotenv,
@@ -0,0 +1,354 @@
+//! Shorthand macros for the global environment.
+//!
+//! - `setenv!()` initializes the global `Environment` with the calling
+//!   crate's own manifest and `CARGO_PKG_*` values, which `env!` can only
+//!   read from inside that crate - hence a macro rather than a function.
+//! - `getenv!(key)` reads one field of the current environment by a short
+//!   key: the innermost `with_env()` scope on this thread, or else the
+//!   global environment, initialized with defaults if `setenv!()` was
+//!   never called.
+//!
+//! # Keys
+//!
+//! | Key | Field | Type |
+//! |-----|-------|------|
+//! | *(none)* | the whole environment | `CurrentEnv` |
+//! | `project` | `project` | `Project` |
+//! | `package` | `package` | `Package` |
+//! | `prj_name` | `project.metadata.name` | `String` |
+//! | `prj_version` | `project.metadata.version` | `String` |
+//! | `prj_description` | `project.metadata.description` | `String` |
+//! | `pkg_name` | `package.metadata.name` | `String` |
+//! | `pkg_version` | `package.metadata.version` | `String` |
+//! | `pkg_description` | `package.metadata.description` | `String` |
+//! | `pkg_authors` | `package.metadata.authors` | `Vec<String>` |
+//! | `pkg_edition` | `package.metadata.edition` | `String` |
+//! | `pkg_license` | `package.metadata.license` | `String` |
+//! | `pkg_homepage` | `package.metadata.homepage` | `String` |
+//! | `pkg_repository` | `package.metadata.repository` | `String` |
+//! | `pkg_keywords` | `package.metadata.keywords` | `Vec<String>` |
+//! | `pkg_categories` | `package.metadata.categories` | `Vec<String>` |
+//! | `pkg_bins` | `package.metadata.bins` | `Vec<String>` |
+//! | `pkg_features` | `package.metadata.features` | `BTreeMap<String, Vec<String>>` |
+//! | `pkg_manifest` | `package.metadata.manifest` | `Option<PathBuf>` |
+//! | `prj_path` | `project.paths.project` | `PathBuf` |
+//! | `pkg_path` | `project.paths.package` | `PathBuf` |
+//! | `assets_path` | `project.paths.assets` | `PathBuf` |
+//! | `db_path` | `project.paths.database` | `PathBuf` |
+//! | `repo_path` | `project.paths.repository` | `PathBuf` |
+//! | `data_path` | `project.paths.data` | `PathBuf` |
+//! | `templates_path` | `project.paths.templates` | `PathBuf` |
+//! | `cache_path` | `project.paths.cache` | `PathBuf` |
+//! | `docs_path` | `project.paths.documentation` | `PathBuf` |
+//! | `code_path` | `project.paths.code` | `PathBuf` |
+//! | `db` | `project.configuration.db` | `String` |
+//! | `ip` | `project.configuration.ip` | `String` |
+//! | `port` | `project.configuration.port` | `u16` |
+//! | `rust_log` | `project.configuration.rust_log` | `String` |
+//! | `color` | `project.configuration.color` | `ColorChoice` |
+//! | `palette` | `project.configuration.palette` | `Option<Palette>` |
+//!
+//! Values are copied out of the environment, so they stay valid after a
+//! `with_env()` scope ends; the key-less form returns a `CurrentEnv`
+//! handle instead. Any other key fails to compile.
+//!
+//! # Examples
+//! ```no_run
+//! use env::prelude::*;
+//!
+//! setenv!();
+//! assert_eq!(getenv!(pkg_name), env!("CARGO_PKG_NAME"));
+//! println!("{} on {}:{}", getenv!(prj_name), getenv!(ip), getenv!(port));
+//! println!("Assets at {}", getenv!(assets_path).display());
+//! ```
+
+/// Initializes the global environment with the calling crate's package
+/// metadata.
+///
+/// Without arguments it starts from `Environment::new()`; pass a
+/// pre-configured `Environment` to keep other settings. Returns the global
+/// `&'static Environment`; like `init_env`, only the first initialization
+/// takes effect.
+///
+/// # Examples
+/// ```no_run
+/// use env::prelude::*;
+///
+/// let env = setenv!(Environment::new().with_port(8080_u16));
+/// assert_eq!(env.package.metadata.version, env!("CARGO_PKG_VERSION"));
+/// ```
+#[macro_export]
+macro_rules! setenv {
+  () => {
+    $crate::setenv!($crate::prelude::Environment::new())
+  };
+  ($env:expr $(,)?) => {
+    $crate::prelude::init_env(
+      $env
+        .with_pkg_manifest(::core::env!("CARGO_MANIFEST_DIR"))
+        .with_pkg_name(::core::env!("CARGO_PKG_NAME"))
+        .with_pkg_version(::core::env!("CARGO_PKG_VERSION"))
+        .with_pkg_description(::core::env!("CARGO_PKG_DESCRIPTION")),
+    )
+  };
+}
+
+/// Reads a field of the current environment by key.
+///
+/// Inside `with_env()` this is the scoped environment; elsewhere it is the
+/// global one.
+///
+/// See the `config::macros` module for the keys and their types.
+///
+/// # Examples
+/// ```no_run
+/// use env::prelude::*;
+///
+/// let name: String = getenv!(pkg_name);
+/// let port: u16 = getenv!(port);
+/// let root: PathBuf = getenv!(prj_path);
+/// let authors: Vec<String> = getenv!(pkg_authors);
+/// ```
+///
+/// Unknown keys are rejected at compile time:
+/// ```compile_fail
+/// use env::prelude::*;
+///
+/// let version = getenv!(pkg_verison);
+/// ```
+#[macro_export]
+macro_rules! getenv {
+  () => {
+    $crate::prelude::current_env()
+  };
+  (project) => {
+    $crate::prelude::current_env().project.clone()
+  };
+  (package) => {
+    $crate::prelude::current_env().package.clone()
+  };
+  (prj_name) => {
+    $crate::prelude::current_env()
+      .project
+      .metadata
+      .name
+      .clone()
+  };
+  (prj_version) => {
+    $crate::prelude::current_env()
+      .project
+      .metadata
+      .version
+      .clone()
+  };
+  (prj_description) => {
+    $crate::prelude::current_env()
+      .project
+      .metadata
+      .description
+      .clone()
+  };
+  (pkg_name) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .name
+      .clone()
+  };
+  (pkg_version) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .version
+      .clone()
+  };
+  (pkg_description) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .description
+      .clone()
+  };
+  (pkg_authors) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .authors
+      .clone()
+  };
+  (pkg_edition) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .edition
+      .clone()
+  };
+  (pkg_license) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .license
+      .clone()
+  };
+  (pkg_homepage) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .homepage
+      .clone()
+  };
+  (pkg_repository) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .repository
+      .clone()
+  };
+  (pkg_keywords) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .keywords
+      .clone()
+  };
+  (pkg_categories) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .categories
+      .clone()
+  };
+  (pkg_bins) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .bins
+      .clone()
+  };
+  (pkg_features) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .features
+      .clone()
+  };
+  (pkg_manifest) => {
+    $crate::prelude::current_env()
+      .package
+      .metadata
+      .manifest
+      .clone()
+  };
+  (prj_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .project
+      .clone()
+  };
+  (pkg_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .package
+      .clone()
+  };
+  (assets_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .assets
+      .clone()
+  };
+  (db_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .database
+      .clone()
+  };
+  (repo_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .repository
+      .clone()
+  };
+  (data_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .data
+      .clone()
+  };
+  (templates_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .templates
+      .clone()
+  };
+  (cache_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .cache
+      .clone()
+  };
+  (docs_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .documentation
+      .clone()
+  };
+  (code_path) => {
+    $crate::prelude::current_env()
+      .project
+      .paths
+      .code
+      .clone()
+  };
+  (db) => {
+    $crate::prelude::current_env()
+      .project
+      .configuration
+      .db
+      .clone()
+  };
+  (ip) => {
+    $crate::prelude::current_env()
+      .project
+      .configuration
+      .ip
+      .clone()
+  };
+  (port) => {
+    $crate::prelude::current_env().project.configuration.port
+  };
+  (rust_log) => {
+    $crate::prelude::current_env()
+      .project
+      .configuration
+      .rust_log
+      .clone()
+  };
+  (color) => {
+    $crate::prelude::current_env().project.configuration.color
+  };
+  (palette) => {
+    $crate::prelude::current_env().project.configuration.palette
+  };
+  ($key:ident) => {
+    ::core::compile_error!(::core::concat!(
+      "unknown environment key `",
+      ::core::stringify!($key),
+      "`; expected one of: project, package, prj_name, prj_version, prj_description, ",
+      "pkg_name, pkg_version, pkg_description, pkg_authors, pkg_edition, pkg_license, ",
+      "pkg_homepage, pkg_repository, pkg_keywords, pkg_categories, pkg_bins, pkg_features, ",
+      "pkg_manifest, prj_path, pkg_path, assets_path, db_path, ",
+      "repo_path, data_path, templates_path, cache_path, docs_path, code_path, ",
+      "db, ip, port, rust_log, color, palette"
+    ))
+  };
+}
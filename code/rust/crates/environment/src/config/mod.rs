@@ -1,7 +1,13 @@
 mod core;
 mod error;
+#[cfg(feature = "macros")]
+pub mod macros;
 pub mod package;
 pub mod project;
+#[cfg(feature = "watch")]
+mod reload;
+mod scope;
+mod snapshot;
 
 mod exports;
 pub mod _prelude {
@@ -45,7 +45,10 @@
 //! println!("{} v{}", pkg_name, pkg_version);
 //! ```
 
-use super::Metadata;
+use {
+  super::Metadata,
+  std::path::Path,
+};
 
 /// Current running package (crate) environment.
 ///
@@ -86,6 +89,7 @@ use super::Metadata;
 ///   .with_version("2.0.0");
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Environment {
   /// Package metadata (name, version, description).
   ///
@@ -133,6 +137,21 @@ impl Environment {
     Self::default()
   }
 
+  /// Reads the metadata from the `Cargo.toml` in `manifest_dir`, if it can
+  /// be read; see `PackageMetadata::with_manifest()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::config::package::prelude::*;
+  ///
+  /// let package = Package::new().with_manifest(env!("CARGO_MANIFEST_DIR"));
+  /// ```
+  #[must_use]
+  pub fn with_manifest(mut self, manifest_dir: impl AsRef<Path>) -> Self {
+    self.metadata = self.metadata.with_manifest(manifest_dir);
+    self
+  }
+
   /// Sets the package name, overriding the default.
   ///
   /// # Examples
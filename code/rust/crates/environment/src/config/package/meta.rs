@@ -10,8 +10,9 @@
 //! - **Project**: Workspace-level metadata (read from workspace Cargo.toml)
 //! - **Package**: Individual crate metadata (this module, typically from `env!()` macros)
 //!
-//! By default, package metadata is initialized by cloning project metadata,
-//! but this is typically overridden with the actual crate's compile-time values.
+//! By default, package metadata is read from the running crate's `Cargo.toml`,
+//! with `{ workspace = true }` fields resolved against `[workspace.package]`;
+//! without a manifest it falls back to cloning project metadata.
 //!
 //! # Compile-Time Integration
 //!
@@ -55,9 +56,12 @@
 //! );
 //! ```
 
-use super::super::{
-  _prelude::*,
-  project::Metadata as Project,
+use {
+  super::super::{
+    _prelude::*,
+    project::Metadata as Project,
+  },
+  std::collections::BTreeMap,
 };
 
 /// Package (crate) metadata.
@@ -68,9 +72,9 @@ use super::super::{
 /// - `description` - Brief description of the crate
 ///
 /// # Defaults
-/// By default, package metadata is cloned from the project (workspace) metadata.
-/// This provides sensible fallback values but is typically overridden with actual
-/// crate metadata from compile-time `env!()` macros.
+/// By default, package metadata is read from the running crate's `Cargo.toml`
+/// when cargo provides `CARGO_MANIFEST_DIR`, and otherwise cloned from the
+/// project (workspace) metadata. `with_manifest()` reads a given crate instead.
 ///
 /// # Builder Pattern
 /// Use `with_*` methods for method chaining:
@@ -106,6 +110,7 @@ use super::super::{
 ///   .with_version(env!("CARGO_PKG_VERSION"));
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metadata {
   /// Package name.
   ///
@@ -144,35 +149,86 @@ pub struct Metadata {
   /// # Typical Length
   /// 1-2 sentences (50-200 characters), fits well in logging and help text.
   pub description: String,
+
+  /// Package authors, from `authors`.
+  pub authors: Vec<String>,
+
+  /// Rust edition, from `edition`.
+  pub edition: String,
+
+  /// SPDX license expression, from `license`.
+  pub license: String,
+
+  /// Home page URL, from `homepage`.
+  pub homepage: String,
+
+  /// Source repository URL, from `repository`.
+  pub repository: String,
+
+  /// Search keywords, from `keywords`.
+  pub keywords: Vec<String>,
+
+  /// crates.io categories, from `categories`.
+  pub categories: Vec<String>,
+
+  /// Names of the binary targets.
+  ///
+  /// From `[[bin]]` entries plus, unless `autobins = false`, `src/main.rs`
+  /// (named after the package) and each `src/bin/*.rs` or `src/bin/*/main.rs`.
+  pub bins: Vec<String>,
+
+  /// Features and what each one enables, from `[features]`.
+  pub features: BTreeMap<String, Vec<String>>,
+
+  /// The manifest these values were read from, if any.
+  pub manifest: Option<PathBuf>,
 }
 
 impl Default for Metadata {
-  /// Creates metadata with default values cloned from project metadata.
+  /// Creates metadata for the running crate.
   ///
   /// # Behavior
-  /// Reads the project (workspace) metadata and clones its name, version, and description.
-  /// This serves as a fallback if package metadata is not explicitly set.
+  /// 1. If `CARGO_MANIFEST_DIR` is set at run time (as under `cargo run` and
+  ///    `cargo test`), reads that crate's `Cargo.toml` with inherited fields
+  ///    resolved; see `try_from_manifest()`
+  /// 2. Otherwise, or if that fails, clones the project (workspace) name,
+  ///    version, and description
   ///
-  /// In most cases, you'll want to override this with the actual crate's metadata
-  /// using builder methods and compile-time `env!()` macros.
+  /// Binaries run outside cargo should pass their compile-time manifest
+  /// directory with `with_manifest()`, as `setenv!()` does.
   ///
   /// # Performance
-  /// - First call: ~5-50ms (discovers workspace, reads Cargo.toml)
-  /// - Subsequent calls: <1µs (uses cached project metadata)
+  /// - With a manifest: ~5-15ms (reads two Cargo.toml files)
+  /// - Fallback: <1µs after the project metadata is cached
   ///
   /// # Examples
   /// ```no_run
   /// use env::package::prelude::*;
   /// let metadata = PackageMetadata::default();
-  /// // Will have the workspace's name, version, description
+  /// // The running crate's own metadata under cargo, else the workspace's
   /// ```
   fn default() -> Self {
-    let project = Project::default();
-    Self {
-      name: project.name,
-      version: project.version,
-      description: project.description,
-    }
+    var("CARGO_MANIFEST_DIR")
+      .ok()
+      .and_then(|dir| Self::try_from_manifest(&Path::new(&dir).join("Cargo.toml")).ok())
+      .unwrap_or_else(|| {
+        let project = Project::default();
+        Self {
+          name: project.name,
+          version: project.version,
+          description: project.description,
+          authors: Vec::new(),
+          edition: String::new(),
+          license: String::new(),
+          homepage: String::new(),
+          repository: String::new(),
+          keywords: Vec::new(),
+          categories: Vec::new(),
+          bins: Vec::new(),
+          features: BTreeMap::new(),
+          manifest: None,
+        }
+      })
   }
 }
 
@@ -266,4 +322,227 @@ impl Metadata {
     self.description = description.into();
     self
   }
+
+  /// Replaces every field with those read from the `Cargo.toml` in
+  /// `manifest_dir`, if it can be read.
+  ///
+  /// Meant for the calling crate's compile-time `env!("CARGO_MANIFEST_DIR")`;
+  /// when the directory no longer exists, as for an installed binary, the
+  /// metadata is left unchanged. Use `try_from_manifest()` to see errors.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::config::package::prelude::*;
+  ///
+  /// let metadata = PackageMetadata::new().with_manifest(env!("CARGO_MANIFEST_DIR"));
+  /// ```
+  #[must_use]
+  pub fn with_manifest(self, manifest_dir: impl AsRef<Path>) -> Self {
+    Self::try_from_manifest(&manifest_dir.as_ref().join("Cargo.toml")).unwrap_or(self)
+  }
+
+  /// Reads a crate's `Cargo.toml`, resolving `{ workspace = true }` fields
+  /// against `[workspace.package]` of the enclosing workspace.
+  ///
+  /// The workspace is the nearest directory at or above the manifest whose
+  /// `Cargo.toml` has a `[workspace]` section.
+  ///
+  /// # Errors
+  /// - `Error::Io` if a manifest cannot be read
+  /// - `Error::Toml` if a manifest is not valid TOML
+  /// - `Error::Inherit` if a field is inherited but no workspace is found or
+  ///   `[workspace.package]` does not set it, pointing at the field
+  ///
+  /// # Examples
+  /// ```
+  /// use env::config::package::prelude::*;
+  ///
+  /// let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+  /// let metadata = PackageMetadata::try_from_manifest(&manifest)?;
+  /// assert_eq!(metadata.name, "env");
+  /// assert_eq!(metadata.version, env!("CARGO_PKG_VERSION"));
+  /// assert!(metadata.features.contains_key("macros"));
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  pub fn try_from_manifest(manifest: &Path) -> Result<Self> {
+    Self::read_manifest(manifest, None)
+  }
+
+  /// Reads a workspace member's `Cargo.toml`, taking `{ workspace = true }`
+  /// fields from `shared`, the `[workspace.package]` table the caller has
+  /// already read.
+  ///
+  /// Unlike `try_from_manifest()`, this does not look for and re-read the
+  /// workspace manifest, which matters when loading many members at once.
+  ///
+  /// # Errors
+  /// As `try_from_manifest()`.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// let workspace = find_project_path();
+  /// let shared = try_read_toml_metadata(&workspace.join("Cargo.toml"))?.unwrap_or_default();
+  /// for member in ["crates/cli", "crates/environment"] {
+  ///   let manifest = workspace.join(member).join("Cargo.toml");
+  ///   let metadata = PackageMetadata::try_from_member_manifest(&manifest, &shared)?;
+  ///   assert_eq!(metadata.license, "MIT");
+  /// }
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  pub fn try_from_member_manifest(manifest: &Path, shared: &CargoToml) -> Result<Self> {
+    Self::read_manifest(manifest, Some(shared))
+  }
+
+  /// Reads `manifest`, inheriting from `shared` or, if absent, from the
+  /// enclosing workspace.
+  fn read_manifest(manifest: &Path, shared: Option<&CargoToml>) -> Result<Self> {
+    let contents = read_to_string(manifest).map_err(|error| Error::io(manifest, error))?;
+    let root = from_toml_str::<TomlTable>(&contents)
+      .map_err(|error| Error::toml(manifest, &contents, &error))?;
+    let mut package = match root.get("package") {
+      Some(TomlValue::Table(package)) => package.clone(),
+      _ => TomlTable::new(),
+    };
+    resolve_inherited(&mut package, manifest, &contents, shared)?;
+
+    let text = |key: &str| {
+      package
+        .get(key)
+        .and_then(TomlValue::as_str)
+        .map(String::from)
+        .unwrap_or_default()
+    };
+    let list = |value: Option<&TomlValue>| -> Vec<String> {
+      value
+        .and_then(TomlValue::as_array)
+        .map(|items| {
+          items
+            .iter()
+            .filter_map(TomlValue::as_str)
+            .map(String::from)
+            .collect()
+        })
+        .unwrap_or_default()
+    };
+
+    let name = text("name");
+    let features = root
+      .get("features")
+      .and_then(TomlValue::as_table)
+      .map(|features| {
+        features
+          .iter()
+          .map(|(feature, enables)| (feature.clone(), list(Some(enables))))
+          .collect()
+      })
+      .unwrap_or_default();
+    let autobins = package.get("autobins").and_then(TomlValue::as_bool) != Some(false);
+    let bins = binaries(&root, manifest, &name, autobins);
+
+    Ok(Self {
+      version: text("version"),
+      description: text("description"),
+      authors: list(package.get("authors")),
+      edition: text("edition"),
+      license: text("license"),
+      homepage: text("homepage"),
+      repository: text("repository"),
+      keywords: list(package.get("keywords")),
+      categories: list(package.get("categories")),
+      bins,
+      features,
+      manifest: Some(manifest.to_path_buf()),
+      name,
+    })
+  }
+}
+
+/// Replaces each `{ workspace = true }` field of `package` with the
+/// workspace's value, from `shared` if given.
+fn resolve_inherited(
+  package: &mut TomlTable,
+  manifest: &Path,
+  contents: &str,
+  shared: Option<&CargoToml>,
+) -> Result<()> {
+  let inherited: Vec<String> = package
+    .iter()
+    .filter(|(_, value)| value.get("workspace").and_then(TomlValue::as_bool) == Some(true))
+    .map(|(key, _)| key.clone())
+    .collect();
+  let Some(first) = inherited.first() else {
+    return Ok(());
+  };
+
+  let inherit_error = |key: &str, reason| {
+    Error::Inherit(Box::new(InheritError {
+      key: key.to_owned(),
+      path: manifest.to_path_buf(),
+      reason,
+      src: named_source(manifest, contents),
+      span: toml_span(contents, &["package", key]),
+    }))
+  };
+  let shared = if let Some(shared) = shared {
+    Cow::Borrowed(shared)
+  } else {
+    let Some(workspace) = manifest
+      .parent()
+      .into_iter()
+      .flat_map(Path::ancestors)
+      .map(|dir| dir.join("Cargo.toml"))
+      .find(|candidate| is_workspace_toml(candidate))
+    else {
+      return Err(inherit_error(first, "no workspace Cargo.toml was found"));
+    };
+    Cow::Owned(try_read_toml_metadata(&workspace)?.unwrap_or_default())
+  };
+
+  for key in inherited {
+    let Some(value) = shared.get(&key) else {
+      return Err(inherit_error(&key, "`[workspace.package]` does not set it"));
+    };
+    package.insert(key, value.clone());
+  }
+  Ok(())
+}
+
+/// The binary targets of a manifest.
+fn binaries(root: &TomlTable, manifest: &Path, name: &str, autobins: bool) -> Vec<String> {
+  let mut bins: Vec<String> = root
+    .get("bin")
+    .and_then(TomlValue::as_array)
+    .into_iter()
+    .flatten()
+    .filter_map(|bin| bin.get("name")?.as_str().map(String::from))
+    .collect();
+  let Some(src) = manifest.parent().map(|dir| dir.join("src")) else {
+    return bins;
+  };
+  if autobins {
+    let mut found = Vec::new();
+    if src.join("main.rs").is_file() {
+      found.push(name.to_owned());
+    }
+    if let Ok(entries) = std::fs::read_dir(src.join("bin")) {
+      let mut named: Vec<String> = entries
+        .filter_map(|entry| {
+          let path = entry.ok()?.path();
+          let is_target = path.extension().is_some_and(|extension| extension == "rs")
+            || path.join("main.rs").is_file();
+          is_target.then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+      named.sort();
+      found.extend(named);
+    }
+    for bin in found {
+      if !bins.contains(&bin) {
+        bins.push(bin);
+      }
+    }
+  }
+  bins
 }
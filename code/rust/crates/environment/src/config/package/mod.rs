@@ -45,7 +45,7 @@
 //! ```no_run
 //! use env::prelude::*;
 //!
-//! set_env(
+//! init_env(
 //!   Environment::new()
 //!     .with_pkg_name(env!("CARGO_PKG_NAME"))
 //!     .with_pkg_version(env!("CARGO_PKG_VERSION"))
@@ -35,6 +35,7 @@
 //! ```
 
 use super::{
+  super::_prelude::Result,
   Configuration,
   Metadata,
   Paths,
@@ -83,6 +84,7 @@ use super::{
 /// println!("Database: {}", project.configuration.db);
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Environment {
   /// Project metadata (name, version, description).
   ///
@@ -133,20 +135,8 @@ impl Default for Environment {
   fn default() -> Self {
     let metadata = Metadata::default();
     let paths = Paths::default();
-    let configuration = if Configuration::default().db.is_empty() {
-      Configuration {
-        db: paths.database.to_string_lossy().into_owned(),
-        ..Default::default()
-      }
-    } else {
-      Configuration::default()
-    };
-
-    Self {
-      metadata,
-      paths,
-      configuration,
-    }
+    let configuration = Configuration::load(&paths.project);
+    Self::assemble(metadata, paths, configuration)
   }
 }
 
@@ -159,6 +149,56 @@ impl Environment {
     Self::default()
   }
 
+  /// Creates the project environment, reporting invalid workspace files and
+  /// configuration values instead of printing or panicking.
+  ///
+  /// Follows the same initialization order as `Project::default()`.
+  ///
+  /// # Errors
+  /// See `ProjectMetadata::try_new()` and `ProjectConfig::try_load()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::config::project::prelude::*;
+  ///
+  /// let project = Project::try_new()?;
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  pub fn try_new() -> Result<Self> {
+    let metadata = Metadata::try_new()?;
+    let paths = Paths::default();
+    let configuration = Configuration::try_load(&paths.project)?;
+    Ok(Self::assemble(metadata, paths, configuration))
+  }
+
+  /// Re-reads the configuration files, keeping the metadata, paths and
+  /// any values set by environment variables or overrides.
+  ///
+  /// # Errors
+  /// See `ProjectConfig::try_reload()`.
+  pub fn try_reload(&self) -> Result<Self> {
+    let configuration = self.configuration.try_reload(&self.paths.project)?;
+    Ok(Self::assemble(
+      self.metadata.clone(),
+      self.paths.clone(),
+      configuration,
+    ))
+  }
+
+  /// Combines the parts, falling back to `{workspace}/assets/db` when no
+  /// database was configured.
+  fn assemble(metadata: Metadata, paths: Paths, mut configuration: Configuration) -> Self {
+    if configuration.db.is_empty() {
+      configuration.db = paths.database.to_string_lossy().into_owned();
+    }
+
+    Self {
+      metadata,
+      paths,
+      configuration,
+    }
+  }
+
   /// Sets the project name, overriding the Cargo.toml value.
   ///
   /// # Examples
@@ -230,4 +270,11 @@ impl Environment {
     self.configuration = self.configuration.with_ip(ip);
     self
   }
+
+  /// Sets the tracing filter directives, overriding the `RUST_LOG` environment variable.
+  #[must_use]
+  pub fn with_rust_log(mut self, rust_log: impl Into<String>) -> Self {
+    self.configuration = self.configuration.with_rust_log(rust_log);
+    self
+  }
 }
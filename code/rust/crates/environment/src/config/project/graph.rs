@@ -0,0 +1,277 @@
+//! The resolved package graph of the workspace, from `cargo metadata`.
+//!
+//! Where `Workspace` only reads the members' manifests, `WorkspaceGraph`
+//! asks cargo to resolve everything: every package in the build, its
+//! targets, the features it declares and enables, and the packages it
+//! depends on. That answers questions the manifests alone cannot, such as
+//! which solutions must be re-tested after a shared crate changes.
+//!
+//! Running cargo takes a few hundred milliseconds, so `WorkspaceGraph::cached()`
+//! keeps the first graph it loads for the rest of the process.
+//!
+//! # Examples
+//! ```no_run
+//! use env::config::project::prelude::*;
+//!
+//! let graph = WorkspaceGraph::cached()?;
+//! for package in graph.reverse_deps("grid") {
+//!   if let Some(solution) = package.solution {
+//!     println!("re-test {solution} ({})", package.name);
+//!   }
+//! }
+//! # Ok::<(), env::prelude::EnvError>(())
+//! ```
+
+use {
+  super::{
+    super::_prelude::*,
+    member::Solution,
+  },
+  cargo_metadata::{
+    DependencyKind,
+    MetadataCommand,
+  },
+  std::collections::{
+    BTreeMap,
+    BTreeSet,
+  },
+};
+
+/// The first graph loaded by `WorkspaceGraph::cached()`.
+static GRAPH: OnceLock<WorkspaceGraph> = OnceLock::new();
+
+/// Every package of a resolved workspace build.
+#[derive(Debug, Clone)]
+pub struct WorkspaceGraph {
+  /// The directory of the workspace `Cargo.toml`.
+  pub root: PathBuf,
+  /// Members and their resolved dependencies, sorted by name and version.
+  pub packages: Vec<GraphPackage>,
+}
+
+/// One package of a `WorkspaceGraph`.
+#[derive(Debug, Clone)]
+pub struct GraphPackage {
+  /// Cargo's opaque, unique package ID.
+  pub id: String,
+  /// Package name.
+  pub name: String,
+  /// Resolved version.
+  pub version: String,
+  /// Path of the package's `Cargo.toml`.
+  pub manifest: PathBuf,
+  /// Whether the package is a workspace member.
+  pub member: bool,
+  /// The puzzle this package solves, if it is a solution crate.
+  pub solution: Option<Solution>,
+  /// Libraries, binaries, tests, examples and benches.
+  pub targets: Vec<GraphTarget>,
+  /// `[features]` as declared.
+  pub features: BTreeMap<String, Vec<String>>,
+  /// The features enabled in this build.
+  pub enabled: Vec<String>,
+  /// The packages this one depends on.
+  pub dependencies: Vec<GraphDependency>,
+}
+
+/// A build target of a `GraphPackage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphTarget {
+  /// Target name.
+  pub name: String,
+  /// Kinds, such as `lib`, `bin` or `test`.
+  pub kinds: Vec<String>,
+  /// The target's root source file.
+  pub path: PathBuf,
+}
+
+/// A resolved dependency edge of a `GraphPackage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphDependency {
+  /// The `GraphPackage::id` depended on.
+  pub id: String,
+  /// The name the dependent uses for it, which differs if it was renamed.
+  pub name: String,
+  /// `normal`, `dev` and/or `build`.
+  pub kinds: Vec<String>,
+}
+
+impl WorkspaceGraph {
+  /// The graph of the workspace found by `find_project_path()`, loaded on
+  /// first use and kept for the rest of the process.
+  ///
+  /// Failures are not cached, so a later call tries again.
+  ///
+  /// # Errors
+  /// As `try_load()`.
+  pub fn cached() -> Result<&'static Self> {
+    if let Some(graph) = GRAPH.get() {
+      return Ok(graph);
+    }
+    let graph = Self::try_load(&find_project_path())?;
+    Ok(GRAPH.get_or_init(|| graph))
+  }
+
+  /// Runs `cargo metadata` for the workspace whose `Cargo.toml` is in
+  /// `root`.
+  ///
+  /// # Errors
+  /// `Error::Metadata` if cargo cannot be run or cannot resolve the
+  /// workspace, for example offline with dependencies not yet downloaded.
+  pub fn try_load(root: &Path) -> Result<Self> {
+    let metadata = MetadataCommand::new()
+      .manifest_path(root.join("Cargo.toml"))
+      .exec()
+      .map_err(|error| Error::Metadata(error.to_string()))?;
+
+    let nodes: BTreeMap<_, _> = metadata
+      .resolve
+      .iter()
+      .flat_map(|resolve| &resolve.nodes)
+      .map(|node| (&node.id, node))
+      .collect();
+    let mut packages: Vec<GraphPackage> = metadata
+      .packages
+      .iter()
+      .map(|package| {
+        let node = nodes.get(&package.id);
+        let member = metadata.workspace_members.contains(&package.id);
+        let manifest = package.manifest_path.clone().into_std_path_buf();
+        GraphPackage {
+          id: package.id.to_string(),
+          name: package.name.to_string(),
+          version: package.version.to_string(),
+          solution: member
+            .then(|| Solution::from_directory(manifest.parent()?))
+            .flatten(),
+          manifest,
+          member,
+          targets: package
+            .targets
+            .iter()
+            .map(|target| GraphTarget {
+              name: target.name.clone(),
+              kinds: target.kind.iter().map(ToString::to_string).collect(),
+              path: target.src_path.clone().into_std_path_buf(),
+            })
+            .collect(),
+          features: package.features.clone(),
+          enabled: node
+            .map(|node| node.features.iter().map(ToString::to_string).collect())
+            .unwrap_or_default(),
+          dependencies: node
+            .map(|node| node.deps.iter().map(GraphDependency::from).collect())
+            .unwrap_or_default(),
+        }
+      })
+      .collect();
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    Ok(Self {
+      root: metadata.workspace_root.into_std_path_buf(),
+      packages,
+    })
+  }
+
+  /// The workspace members, by name.
+  pub fn members(&self) -> impl Iterator<Item = &GraphPackage> {
+    self.packages.iter().filter(|package| package.member)
+  }
+
+  /// The package called `name`; the workspace member if there are several
+  /// versions.
+  #[must_use]
+  pub fn package(&self, name: &str) -> Option<&GraphPackage> {
+    let mut named = self.packages.iter().filter(|package| package.name == name);
+    let first = named.next()?;
+    Some(if first.member {
+      first
+    } else {
+      named.find(|package| package.member).unwrap_or(first)
+    })
+  }
+
+  /// The packages `name` depends on directly, of every kind.
+  #[must_use]
+  pub fn deps(&self, name: &str) -> Vec<&GraphPackage> {
+    let ids: BTreeSet<&str> = self
+      .packages
+      .iter()
+      .filter(|package| package.name == name)
+      .flat_map(|package| &package.dependencies)
+      .map(|dependency| dependency.id.as_str())
+      .collect();
+    self
+      .packages
+      .iter()
+      .filter(|package| ids.contains(package.id.as_str()))
+      .collect()
+  }
+
+  /// Every package that depends on `name`, directly or through other
+  /// packages, with dev and build dependencies included, sorted by name.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::config::project::prelude::*;
+  ///
+  /// let graph = WorkspaceGraph::try_load(&find_project_path())?;
+  /// let users: Vec<_> = graph
+  ///   .reverse_deps("grid")
+  ///   .iter()
+  ///   .map(|package| &package.name)
+  ///   .collect();
+  /// println!("grid is used by {users:?}");
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  #[must_use]
+  pub fn reverse_deps(&self, name: &str) -> Vec<&GraphPackage> {
+    let mut found: BTreeSet<&str> = self
+      .packages
+      .iter()
+      .filter(|package| package.name == name)
+      .map(|package| package.id.as_str())
+      .collect();
+    let targets = found.clone();
+    loop {
+      let before = found.len();
+      for package in &self.packages {
+        if package
+          .dependencies
+          .iter()
+          .any(|dependency| found.contains(dependency.id.as_str()))
+        {
+          found.insert(&package.id);
+        }
+      }
+      if found.len() == before {
+        break;
+      }
+    }
+    self
+      .packages
+      .iter()
+      .filter(|package| {
+        found.contains(package.id.as_str()) && !targets.contains(package.id.as_str())
+      })
+      .collect()
+  }
+}
+
+impl From<&cargo_metadata::NodeDep> for GraphDependency {
+  fn from(dependency: &cargo_metadata::NodeDep) -> Self {
+    Self {
+      id: dependency.pkg.to_string(),
+      name: dependency.name.clone(),
+      kinds: dependency
+        .dep_kinds
+        .iter()
+        .map(|info| match info.kind {
+          DependencyKind::Development => String::from("dev"),
+          DependencyKind::Build => String::from("build"),
+          _ => String::from("normal"),
+        })
+        .collect(),
+    }
+  }
+}
@@ -0,0 +1,320 @@
+//! Configuration layers and the provenance of every value.
+//!
+//! Configuration is assembled from several layers, each overriding the
+//! ones before it:
+//!
+//! | Layer | Location | Keys |
+//! |-------|----------|------|
+//! | Defaults | built in | - |
+//! | Workspace | `[workspace.metadata.aoc]` in `{project}/Cargo.toml` | `db`, `ip`, `port`, `rust_log`, `color`, `palette` |
+//! | File | `{project}/aoc.toml` | `db`, `ip`, `port`, `rust_log`, `color`, `palette` |
+//! | Dotenv | `{project}/.env`, then `{project}/.env.local` | `DATABASE_URL`, `IP`, `PORT`, `RUST_LOG`, `AOC_COLOR`, `AOC_PALETTE` |
+//! | Environment | process environment variables | `DATABASE_URL`, `IP`, `PORT`, `RUST_LOG`, `AOC_COLOR`, `AOC_PALETTE` |
+//! | Override | `Configuration::with_*`, CLI flags | - |
+//!
+//! Every value remembers the layer that last set it in `Origins`, which is
+//! what `aoc config show --origin` prints.
+//!
+//! # Examples
+//!
+//! ```toml
+//! # Cargo.toml
+//! [workspace.metadata.aoc]
+//! port = 8080
+//! rust_log = "cli=debug"
+//! ```
+//!
+//! ```no_run
+//! use env::config::project::prelude::*;
+//!
+//! let config = ProjectConfig::new().with_ip("0.0.0.0");
+//! println!("port {} from {}", config.port, config.origins.port);
+//! assert_eq!(config.origins.ip, ConfigSource::Override);
+//! ```
+
+use {
+  super::super::_prelude::*,
+  miette::{
+    NamedSource,
+    SourceSpan,
+  },
+};
+
+/// A configurable value, by its key in TOML layers and its variable name in
+/// `.env` files and the process environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Setting {
+  /// Key in `[workspace.metadata.aoc]` and `aoc.toml`.
+  pub key: &'static str,
+  /// Environment variable name.
+  pub variable: &'static str,
+}
+
+/// Every configurable value, in display order.
+pub const SETTINGS: [Setting; 6] = [
+  Setting {
+    key: "db",
+    variable: "DATABASE_URL",
+  },
+  Setting {
+    key: "ip",
+    variable: "IP",
+  },
+  Setting {
+    key: "port",
+    variable: "PORT",
+  },
+  Setting {
+    key: "rust_log",
+    variable: "RUST_LOG",
+  },
+  Setting {
+    key: "color",
+    variable: "AOC_COLOR",
+  },
+  Setting {
+    key: "palette",
+    variable: "AOC_PALETTE",
+  },
+];
+
+/// Name of the per-project configuration file.
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Dotenv files read from the project root, later ones winning.
+pub const DOTENV_FILES: [&str; 2] = [".env", ".env.local"];
+
+/// The layer a configuration value came from.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize),
+  serde(rename_all = "snake_case")
+)]
+pub enum Source {
+  /// The built-in default.
+  #[default]
+  Default,
+  /// `[workspace.metadata.aoc]` in the workspace manifest.
+  Workspace(PathBuf),
+  /// The `aoc.toml` file.
+  File(PathBuf),
+  /// A `.env` file.
+  Dotenv(PathBuf),
+  /// A process environment variable.
+  Environment(&'static str),
+  /// A builder method or command-line flag.
+  Override,
+}
+
+impl Display for Source {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Default => write!(f, "default"),
+      Self::Workspace(path) => write!(f, "[workspace.metadata.aoc] in {}", path.display()),
+      Self::File(path) | Self::Dotenv(path) => write!(f, "{}", path.display()),
+      Self::Environment(variable) => write!(f, "environment variable {variable}"),
+      Self::Override => write!(f, "override"),
+    }
+  }
+}
+
+/// Where each configuration value came from.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Origins {
+  /// Source of `Configuration::db`.
+  pub db: Source,
+  /// Source of `Configuration::ip`.
+  pub ip: Source,
+  /// Source of `Configuration::port`.
+  pub port: Source,
+  /// Source of `Configuration::rust_log`.
+  pub rust_log: Source,
+  /// Source of `Configuration::color`.
+  pub color: Source,
+  /// Source of `Configuration::palette`.
+  pub palette: Source,
+}
+
+impl Origins {
+  /// The source of the value with the given TOML key.
+  #[must_use]
+  pub fn get(&self, key: &str) -> Option<&Source> {
+    match key {
+      "db" => Some(&self.db),
+      "ip" => Some(&self.ip),
+      "port" => Some(&self.port),
+      "rust_log" => Some(&self.rust_log),
+      "color" => Some(&self.color),
+      "palette" => Some(&self.palette),
+      _ => None,
+    }
+  }
+
+  /// Mutable access to the source of the value with the given TOML key.
+  pub(super) fn get_mut(&mut self, key: &str) -> Option<&mut Source> {
+    match key {
+      "db" => Some(&mut self.db),
+      "ip" => Some(&mut self.ip),
+      "port" => Some(&mut self.port),
+      "rust_log" => Some(&mut self.rust_log),
+      "color" => Some(&mut self.color),
+      "palette" => Some(&mut self.palette),
+      _ => None,
+    }
+  }
+}
+
+/// Reads `[workspace.metadata.aoc]` from a workspace manifest.
+///
+/// A missing manifest or section is an empty layer.
+pub(super) fn workspace_layer(manifest: &Path) -> Result<Option<TomlTable>> {
+  let Some(contents) = read_optional(manifest)? else {
+    return Ok(None);
+  };
+  let mut root = parse_toml(manifest, &contents)?;
+  Ok(
+    match root
+      .remove("workspace")
+      .as_ref()
+      .and_then(|workspace| workspace.get("metadata")?.get("aoc"))
+    {
+      Some(TomlValue::Table(table)) => Some(table.clone()),
+      _ => None,
+    },
+  )
+}
+
+/// Reads an `aoc.toml` file; a missing file is an empty layer.
+pub(super) fn file_layer(path: &Path) -> Result<Option<TomlTable>> {
+  read_optional(path)?
+    .map(|contents| parse_toml(path, &contents))
+    .transpose()
+}
+
+/// Reads the variables of a `.env` file, without touching the process
+/// environment; a missing file is an empty layer.
+pub(super) fn dotenv_layer(path: &Path) -> Result<Vec<(String, String)>> {
+  let Some(contents) = read_optional(path)? else {
+    return Ok(Vec::new());
+  };
+  Ok(
+    dotenv_entries(path, &contents)?
+      .into_iter()
+      .map(|entry| (entry.variable, entry.value))
+      .collect(),
+  )
+}
+
+/// The text of a TOML value as it would appear in an environment variable.
+pub(super) fn toml_text(value: &TomlValue) -> String {
+  match value {
+    TomlValue::String(text) => text.clone(),
+    other => other.to_string(),
+  }
+}
+
+/// An invalid value for `key`, pointing at where `source` set it.
+pub(super) fn invalid_value(
+  key: &'static str,
+  value: String,
+  source: &Source,
+  help: &str,
+) -> Error {
+  let (src, span) = locate(source, key).unzip();
+  Error::Value(Box::new(ValueError {
+    key,
+    value,
+    origin: source.to_string(),
+    help: help.to_owned(),
+    src,
+    span,
+  }))
+}
+
+/// The file and span where `source` set `key`, if it is a file.
+fn locate(source: &Source, key: &str) -> Option<(NamedSource<String>, SourceSpan)> {
+  let (path, contents, span) = match source {
+    Source::Workspace(path) => {
+      let contents = read_to_string(path).ok()?;
+      let span = toml_span(&contents, &["workspace", "metadata", "aoc", key])?;
+      (path, contents, span)
+    }
+    Source::File(path) => {
+      let contents = read_to_string(path).ok()?;
+      let span = toml_span(&contents, &[key])?;
+      (path, contents, span)
+    }
+    Source::Dotenv(path) => {
+      let variable = SETTINGS.iter().find(|setting| setting.key == key)?.variable;
+      let contents = read_to_string(path).ok()?;
+      let span = dotenv_entries(path, &contents)
+        .ok()?
+        .into_iter()
+        .rfind(|entry| entry.variable == variable)?
+        .span;
+      (path, contents, span)
+    }
+    Source::Default | Source::Environment(_) | Source::Override => return None,
+  };
+  Some((named_source(path, &contents), span))
+}
+
+/// Reads a file that may not exist.
+fn read_optional(path: &Path) -> Result<Option<String>> {
+  match read_to_string(path) {
+    Ok(contents) => Ok(Some(contents)),
+    Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+    Err(error) => Err(Error::io(path, error)),
+  }
+}
+
+/// Parses a TOML document, pointing at the syntax error if there is one.
+fn parse_toml(path: &Path, contents: &str) -> Result<TomlTable> {
+  from_toml_str::<TomlTable>(contents).map_err(|error| Error::toml(path, contents, &error))
+}
+
+/// A `KEY=value` line of a `.env` file.
+struct Entry {
+  variable: String,
+  value: String,
+  /// The whole line, without surrounding whitespace.
+  span: SourceSpan,
+}
+
+/// Parses the lines of a `.env` file.
+///
+/// Understands `KEY=value` lines with an optional `export ` prefix and
+/// matching single or double quotes around the value; blank lines and
+/// `#` comments are skipped.
+fn dotenv_entries(path: &Path, contents: &str) -> Result<Vec<Entry>> {
+  let mut entries = Vec::new();
+  let mut offset = 0;
+  for raw in contents.split_inclusive('\n') {
+    let line = raw.trim();
+    let span = SourceSpan::from((offset + raw.len() - raw.trim_start().len(), line.len()));
+    offset += raw.len();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let assignment = line.strip_prefix("export ").unwrap_or(line);
+    let Some((variable, value)) = assignment
+      .split_once('=')
+      .filter(|(variable, _)| !variable.trim().is_empty())
+    else {
+      return Err(Error::dotenv(path, contents, span));
+    };
+    let value = value.trim();
+    let value = ['"', '\'']
+      .iter()
+      .find_map(|&quote| value.strip_prefix(quote)?.strip_suffix(quote))
+      .unwrap_or(value);
+    entries.push(Entry {
+      variable: variable.trim().to_owned(),
+      value: value.to_owned(),
+      span,
+    });
+  }
+  Ok(entries)
+}
@@ -0,0 +1,631 @@
+//! Typed Cargo manifests and format-preserving manifest edits.
+//!
+//! `Manifest` is a typed view of the parts of a `Cargo.toml` the workspace
+//! tools need: the package name, the `[workspace]` table, dependencies and
+//! features. `ManifestEditor` changes a manifest in place with `toml_edit`,
+//! keeping comments, blank lines and the `#~@` section markers of the root
+//! `Cargo.toml` intact, so scaffolding commands can register new day crates
+//! without rewriting the file.
+//!
+//! Array edits follow the style of this repository's manifests, where an
+//! entry is switched off by commenting out its line:
+//! - Adding an entry uncomments a `# "entry",` line if there is one, and
+//!   otherwise appends it after the last section.
+//! - Disabling a default feature comments its line out again.
+//!
+//! # Examples
+//! ```no_run
+//! use env::config::project::prelude::*;
+//!
+//! let root = find_project_path().join("Cargo.toml");
+//! let mut editor = ManifestEditor::try_open(&root)?;
+//! if editor.add_member("tasks/2024/01")? {
+//!   editor.save()?;
+//! }
+//! # Ok::<(), env::prelude::EnvError>(())
+//! ```
+
+use {
+  super::{
+    super::_prelude::*,
+    member::pattern_matches,
+  },
+  std::{
+    collections::BTreeMap,
+    fs::write,
+  },
+  toml_edit::{
+    Array,
+    DocumentMut,
+    InlineTable,
+    Item,
+    RawString,
+    Table,
+    Value,
+  },
+};
+
+/// The parts of a `Cargo.toml` the workspace tools use.
+///
+/// # Examples
+/// ```
+/// use env::config::project::prelude::*;
+///
+/// let manifest = Manifest::from_contents(
+///   "Cargo.toml",
+///   r#"
+///     [workspace]
+///     members = ["crates/*"]
+///     resolver = "2"
+///
+///     [workspace.dependencies]
+///     miette = "7"
+///   "#,
+/// )?;
+/// let workspace = manifest.workspace.expect("a workspace manifest");
+/// assert_eq!(workspace.members, ["crates/*"]);
+/// assert_eq!(workspace.dependencies, ["miette"]);
+/// assert!(manifest.package.is_none());
+/// # Ok::<(), env::prelude::EnvError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+  /// Where the manifest was read from.
+  pub path: PathBuf,
+  /// `[package] name`, if the manifest has a `[package]`.
+  pub package: Option<String>,
+  /// The `[workspace]` table, if this manifest is a workspace root.
+  pub workspace: Option<WorkspaceSection>,
+  /// The names of `[dependencies]`.
+  pub dependencies: Vec<String>,
+  /// `[features]`, each with the features and dependencies it enables.
+  pub features: BTreeMap<String, Vec<String>>,
+}
+
+/// The `[workspace]` table of a root manifest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceSection {
+  /// `members`, as written; entries may be globs.
+  pub members: Vec<String>,
+  /// `exclude`, as written.
+  pub exclude: Vec<String>,
+  /// `default-members`, as written.
+  pub default_members: Vec<String>,
+  /// `resolver`, if set.
+  pub resolver: Option<String>,
+  /// `[workspace.package]`, the fields members may inherit.
+  pub package: CargoToml,
+  /// The names of `[workspace.dependencies]`.
+  pub dependencies: Vec<String>,
+}
+
+impl Manifest {
+  /// Reads the manifest at `path`.
+  ///
+  /// # Errors
+  /// - `Error::Io` if the file cannot be read
+  /// - `Error::Toml` if it is not valid TOML
+  pub fn try_read(path: &Path) -> Result<Self> {
+    let contents = read_to_string(path).map_err(|error| Error::io(path, error))?;
+    Self::from_contents(path, &contents)
+  }
+
+  /// Parses `contents` as the manifest at `path`.
+  ///
+  /// # Errors
+  /// `Error::Toml` if `contents` is not valid TOML.
+  pub fn from_contents(path: impl Into<PathBuf>, contents: &str) -> Result<Self> {
+    let path = path.into();
+    let table =
+      from_toml_str::<TomlTable>(contents).map_err(|error| Error::toml(&path, contents, &error))?;
+    Ok(Self::from_table(path, &table))
+  }
+
+  /// Whether this manifest has a `[workspace]` table.
+  #[must_use]
+  pub const fn is_workspace(&self) -> bool {
+    self.workspace.is_some()
+  }
+
+  /// The typed view of a parsed manifest.
+  fn from_table(path: PathBuf, table: &TomlTable) -> Self {
+    let workspace = table
+      .get("workspace")
+      .and_then(TomlValue::as_table)
+      .map(|workspace| WorkspaceSection {
+        members: strings(workspace.get("members")),
+        exclude: strings(workspace.get("exclude")),
+        default_members: strings(workspace.get("default-members")),
+        resolver: workspace
+          .get("resolver")
+          .and_then(TomlValue::as_str)
+          .map(String::from),
+        package: workspace
+          .get("package")
+          .and_then(TomlValue::as_table)
+          .cloned()
+          .unwrap_or_default(),
+        dependencies: keys(workspace.get("dependencies")),
+      });
+
+    Self {
+      package: table
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(TomlValue::as_str)
+        .map(String::from),
+      workspace,
+      dependencies: keys(table.get("dependencies")),
+      features: table
+        .get("features")
+        .and_then(TomlValue::as_table)
+        .map(|features| {
+          features
+            .iter()
+            .map(|(feature, enables)| (feature.clone(), strings(Some(enables))))
+            .collect()
+        })
+        .unwrap_or_default(),
+      path,
+    }
+  }
+}
+
+/// The strings of a TOML array, skipping anything else.
+fn strings(value: Option<&TomlValue>) -> Vec<String> {
+  value
+    .and_then(TomlValue::as_array)
+    .map(|items| {
+      items
+        .iter()
+        .filter_map(TomlValue::as_str)
+        .map(String::from)
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// The keys of a TOML table, in order.
+fn keys(value: Option<&TomlValue>) -> Vec<String> {
+  value
+    .and_then(TomlValue::as_table)
+    .map(|table| table.keys().cloned().collect())
+    .unwrap_or_default()
+}
+
+/// A `Cargo.toml` open for editing.
+///
+/// Edits apply to the document in memory; `save()` writes it back. Every
+/// edit reports whether it changed anything, so callers can skip the write.
+///
+/// # Examples
+/// ```
+/// use env::config::project::prelude::*;
+///
+/// let mut editor = ManifestEditor::from_contents(
+///   "Cargo.toml",
+///   r#"[workspace]
+/// members = [
+///   #~@ Utilities
+///   "crates/cli",
+///   ## "crates/core",
+///
+///   #~@ Solutions
+///   "tasks/2024/01",
+/// ]
+/// "#,
+/// )?;
+/// assert!(editor.add_member("crates/core")?);
+/// assert!(editor.add_member("tasks/2024/02")?);
+/// assert!(editor.remove_member("tasks/2024/01")?);
+/// assert!(!editor.add_member("crates/cli")?);
+/// assert_eq!(
+///   editor.to_string(),
+///   r#"[workspace]
+/// members = [
+///   #~@ Utilities
+///   "crates/cli",
+///   "crates/core",
+///
+///   #~@ Solutions
+///   "tasks/2024/02",
+/// ]
+/// "#
+/// );
+/// # Ok::<(), env::prelude::EnvError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManifestEditor {
+  path: PathBuf,
+  document: DocumentMut,
+}
+
+impl ManifestEditor {
+  /// Opens the manifest at `path`.
+  ///
+  /// # Errors
+  /// - `Error::Io` if the file cannot be read
+  /// - `Error::Toml` if it is not valid TOML
+  pub fn try_open(path: impl Into<PathBuf>) -> Result<Self> {
+    let path = path.into();
+    let contents = read_to_string(&path).map_err(|error| Error::io(&path, error))?;
+    Self::from_contents(path, &contents)
+  }
+
+  /// Edits `contents` as the manifest at `path`, without reading it.
+  ///
+  /// # Errors
+  /// `Error::Toml` if `contents` is not valid TOML.
+  pub fn from_contents(path: impl Into<PathBuf>, contents: &str) -> Result<Self> {
+    let path = path.into();
+    let document = contents
+      .parse::<DocumentMut>()
+      .map_err(|error| Error::toml_edit(&path, contents, &error))?;
+    Ok(Self { path, document })
+  }
+
+  /// The path `save()` writes to.
+  #[must_use]
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// The typed view of the manifest as edited so far.
+  #[must_use]
+  pub fn manifest(&self) -> Manifest {
+    let table = from_toml_str::<TomlTable>(&self.document.to_string()).unwrap_or_default();
+    Manifest::from_table(self.path.clone(), &table)
+  }
+
+  /// Registers `member`, a path relative to the workspace root, in
+  /// `[workspace] members`.
+  ///
+  /// A commented-out `# "member",` line is uncommented in place; otherwise
+  /// the member goes at the end of the list, after the last section marker.
+  /// Returns `false` if a member entry, or a glob in it, already names the
+  /// path.
+  ///
+  /// # Errors
+  /// `Error::Manifest` if the manifest has no `[workspace]` table.
+  pub fn add_member(&mut self, member: &str) -> Result<bool> {
+    let members = self.members()?;
+    if members
+      .iter()
+      .filter_map(Value::as_str)
+      .any(|pattern| pattern_matches(pattern, member))
+    {
+      return Ok(false);
+    }
+    Ok(enable(members, member))
+  }
+
+  /// Removes the `member` entry from `[workspace] members`, keeping any
+  /// comments and section markers above it.
+  ///
+  /// Only an entry written exactly as `member` is removed, not a glob that
+  /// matches it; returns `false` if there is none.
+  ///
+  /// # Errors
+  /// `Error::Manifest` if the manifest has no `[workspace]` table.
+  pub fn remove_member(&mut self, member: &str) -> Result<bool> {
+    Ok(remove(self.members()?, member))
+  }
+
+  /// Makes `[dependencies] name` inherit from the workspace, as
+  /// `name = { workspace = true }` with the extra `features`.
+  ///
+  /// An existing entry for `name` is replaced, keeping `optional`.
+  ///
+  /// # Errors
+  /// `Error::Manifest` if `workspace` does not declare `name` in
+  /// `[workspace.dependencies]`.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::config::project::prelude::*;
+  ///
+  /// let workspace =
+  ///   Manifest::from_contents("Cargo.toml", "[workspace]\n[workspace.dependencies]\nmiette = \"7\"\n")?;
+  /// let mut editor = ManifestEditor::from_contents(
+  ///   "crates/cli/Cargo.toml",
+  ///   "[package]\nname = \"cli\"\n\n[dependencies]\n# Errors\nmiette = { version = \"7\", optional = true }\n",
+  /// )?;
+  /// assert!(editor.inherit_dependency("miette", &["fancy"], &workspace)?);
+  /// assert!(
+  ///   editor
+  ///     .to_string()
+  ///     .ends_with("# Errors\nmiette = { workspace = true, optional = true, features = [\"fancy\"] }\n")
+  /// );
+  /// assert!(editor.inherit_dependency("clap", &[], &workspace).is_err());
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  pub fn inherit_dependency(
+    &mut self,
+    name: &str,
+    features: &[&str],
+    workspace: &Manifest,
+  ) -> Result<bool> {
+    let declared = workspace.workspace.as_ref().is_some_and(|section| {
+      section
+        .dependencies
+        .iter()
+        .any(|dependency| dependency == name)
+    });
+    if !declared {
+      return Err(self.error(format!(
+        "`{name}` is not declared in `[workspace.dependencies]` of {}",
+        workspace.path.display()
+      )));
+    }
+
+    let dependencies = self
+      .document
+      .entry("dependencies")
+      .or_insert_with(|| Item::Table(Table::new()));
+    let Some(dependencies) = dependencies.as_table_like_mut() else {
+      return Err(self.error("`dependencies` is not a table".to_owned()));
+    };
+    let optional = dependencies
+      .get(name)
+      .and_then(|dependency| dependency.get("optional"))
+      .and_then(Item::as_bool);
+
+    let mut inherited = InlineTable::new();
+    inherited.insert("workspace", true.into());
+    if let Some(optional) = optional {
+      inherited.insert("optional", optional.into());
+    }
+    if !features.is_empty() {
+      inherited.insert("features", Value::Array(features.iter().copied().collect()));
+    }
+    let inherited = Value::InlineTable(inherited);
+
+    let before = dependencies
+      .get(name)
+      .map(ToString::to_string);
+    if before.as_deref().map(str::trim) == Some(inherited.to_string().trim()) {
+      return Ok(false);
+    }
+    if let Some(dependency) = dependencies.get_mut(name) {
+      *dependency = Item::Value(inherited);
+    } else {
+      dependencies.insert(name, Item::Value(inherited));
+    }
+    Ok(true)
+  }
+
+  /// Turns `feature` on or off in `[features] default`.
+  ///
+  /// Turning it off comments its line out, and turning it on uncomments
+  /// such a line if there is one.
+  ///
+  /// # Errors
+  /// `Error::Manifest` if the manifest defines no such feature or
+  /// dependency, or `[features]` is not a table.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::config::project::prelude::*;
+  ///
+  /// let mut editor = ManifestEditor::from_contents(
+  ///   "Cargo.toml",
+  ///   "[features]\ndefault = [\n  \"fmt\",\n  # \"watch\",\n]\nfmt = []\nwatch = []\n",
+  /// )?;
+  /// assert!(editor.set_default_feature("watch", true)?);
+  /// assert!(editor.set_default_feature("fmt", false)?);
+  /// assert!(!editor.set_default_feature("fmt", false)?);
+  /// assert_eq!(
+  ///   editor.to_string(),
+  ///   "[features]\ndefault = [\n  # \"fmt\",\n  \"watch\",\n]\nfmt = []\nwatch = []\n"
+  /// );
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  pub fn set_default_feature(&mut self, feature: &str, enabled: bool) -> Result<bool> {
+    let manifest = self.manifest();
+    let known = manifest.features.contains_key(feature)
+      || manifest
+        .dependencies
+        .iter()
+        .any(|dependency| dependency == feature)
+      || feature.starts_with("dep:")
+      || feature.contains('/');
+    if !known {
+      return Err(self.error(format!("there is no feature `{feature}`")));
+    }
+
+    let features = self
+      .document
+      .entry("features")
+      .or_insert_with(|| Item::Table(Table::new()));
+    let Some(default) = features.as_table_like_mut().map(|features| {
+      features
+        .entry("default")
+        .or_insert(Item::Value(Value::Array(Array::new())))
+    }) else {
+      return Err(self.error("`features` is not a table".to_owned()));
+    };
+    let Some(default) = default.as_array_mut() else {
+      return Err(self.error("`features.default` is not an array".to_owned()));
+    };
+    Ok(if enabled {
+      enable(default, feature)
+    } else {
+      disable(default, feature)
+    })
+  }
+
+  /// Writes the edited manifest back to `path()`.
+  ///
+  /// # Errors
+  /// `Error::Write` if the file cannot be written.
+  pub fn save(&self) -> Result<()> {
+    write(&self.path, self.document.to_string()).map_err(|source| Error::Write {
+      path: self.path.clone(),
+      source,
+    })
+  }
+
+  /// `[workspace] members`, created if missing.
+  fn members(&mut self) -> Result<&mut Array> {
+    let error = self.error("it has no `[workspace]` table".to_owned());
+    self
+      .document
+      .get_mut("workspace")
+      .and_then(Item::as_table_like_mut)
+      .and_then(|workspace| {
+        workspace
+          .entry("members")
+          .or_insert(Item::Value(Value::Array(Array::new())))
+          .as_array_mut()
+      })
+      .ok_or(error)
+  }
+
+  fn error(&self, reason: String) -> Error {
+    Error::Manifest {
+      path: self.path.clone(),
+      reason,
+    }
+  }
+}
+
+impl Display for ManifestEditor {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "{}", self.document)
+  }
+}
+
+/// The whitespace and comments before an array item.
+fn prefix(value: &Value) -> &str {
+  value
+    .decor()
+    .prefix()
+    .and_then(RawString::as_str)
+    .unwrap_or_default()
+}
+
+/// The whitespace and comments after the last array item.
+fn trailing(array: &Array) -> &str {
+  array.trailing().as_str().unwrap_or_default()
+}
+
+/// Whether `array` is written one item per line.
+fn is_multiline(array: &Array) -> bool {
+  array.iter().any(|value| prefix(value).contains('\n')) || trailing(array).contains('\n')
+}
+
+/// The index of the string `item` in `array`.
+fn position(array: &Array, item: &str) -> Option<usize> {
+  array.iter().position(|value| value.as_str() == Some(item))
+}
+
+/// Splits `text` around a `# "item",` line, returning the text before the
+/// comment, including its indentation, and the text after the line.
+fn uncomment(text: &str, item: &str) -> Option<(String, String)> {
+  let quoted = format!("\"{item}\"");
+  let mut start = 0;
+  for line in text.split('\n') {
+    let end = start + line.len();
+    let content = line.trim_start();
+    if let Some(comment) = content.strip_prefix('#')
+      && comment.trim().trim_end_matches(',').trim_end() == quoted
+    {
+      let indent = &line[..line.len() - content.len()];
+      return Some((
+        format!("{}{indent}", &text[..start]),
+        text[end..].to_owned(),
+      ));
+    }
+    start = end + 1;
+  }
+  None
+}
+
+/// Adds `item` to `array`: in place of a `# "item",` line if there is one,
+/// and otherwise at the end, after any trailing comments.
+fn enable(array: &mut Array, item: &str) -> bool {
+  if position(array, item).is_some() {
+    return false;
+  }
+  if !is_multiline(array) {
+    array.push(item);
+    return true;
+  }
+
+  let mut value = Value::from(item);
+  for index in 0..array.len() {
+    let Some(next) = array.get_mut(index) else {
+      break;
+    };
+    if let Some((before, after)) = uncomment(prefix(next), item) {
+      next.decor_mut().set_prefix(after);
+      value.decor_mut().set_prefix(before);
+      array.insert_formatted(index, value);
+      return true;
+    }
+  }
+
+  let (before, after) = uncomment(trailing(array), item).unwrap_or_else(|| {
+    let indent = array
+      .iter()
+      .filter_map(|value| prefix(value).rsplit_once('\n'))
+      .last()
+      .map_or("  ", |(_, indent)| indent);
+    let text = trailing(array);
+    let comments = text.trim_end();
+    let end = &text[comments.len()..];
+    let end = if end.contains('\n') { end } else { "\n" };
+    (format!("{comments}\n{indent}"), end.to_owned())
+  });
+  value.decor_mut().set_prefix(before);
+  array.push_formatted(value);
+  array.set_trailing(after);
+  array.set_trailing_comma(true);
+  true
+}
+
+/// Comments `item` out of `array`, or removes it if the array is on one
+/// line.
+fn disable(array: &mut Array, item: &str) -> bool {
+  let Some(index) = position(array, item) else {
+    return false;
+  };
+  let removed = array.remove(index);
+  if !is_multiline(array) && !prefix(&removed).contains('\n') {
+    return true;
+  }
+
+  let commented = format!("{}# \"{item}\",", prefix(&removed));
+  if let Some(next) = array.get_mut(index) {
+    let text = format!("{commented}{}", prefix(next));
+    next.decor_mut().set_prefix(text);
+  } else {
+    let text = format!("{commented}{}", trailing(array));
+    array.set_trailing(text);
+  }
+  true
+}
+
+/// Removes `item` from `array`, moving the comments above it to whatever
+/// follows.
+fn remove(array: &mut Array, item: &str) -> bool {
+  let Some(index) = position(array, item) else {
+    return false;
+  };
+  let removed = array.remove(index);
+  let above = prefix(&removed);
+  let comments = above.rsplit_once('\n').map_or("", |(comments, _)| comments);
+
+  if let Some(next) = array.get_mut(index) {
+    let text = if index == 0 && !above.contains('\n') {
+      above.to_owned()
+    } else {
+      format!("{comments}{}", prefix(next))
+    };
+    next.decor_mut().set_prefix(text);
+  } else {
+    let text = format!("{comments}{}", trailing(array));
+    array.set_trailing(text);
+  }
+  true
+}
@@ -0,0 +1,382 @@
+//! Workspace members, read straight from the workspace `Cargo.toml`.
+//!
+//! `Workspace::try_load()` parses `[workspace] members` and `exclude`,
+//! expands glob patterns such as `tasks/*/[0-9][0-9]` against the file
+//! system and reads each member's manifest. Unlike `cargo metadata` it
+//! spawns no process and resolves no dependencies, so it is cheap enough to
+//! run on every CLI invocation: a few milliseconds for a hundred members.
+//!
+//! Members whose directory ends in `<year>/<day>`, like `tasks/2024/07`, are
+//! puzzle solutions; `Workspace::solutions()` lists which ones exist.
+//!
+//! # Patterns
+//!
+//! Each `/`-separated part of a member pattern may use `*` (any run of
+//! characters), `?` (any one character) and `[...]` sets such as `[0-9]`
+//! or `[!.]`. Glob matches that are not directories are skipped. As with
+//! cargo, an `exclude` entry removes every member below it unless the
+//! member is listed literally, and the root is a member too if its manifest
+//! has a `[package]`.
+//!
+//! # Examples
+//! ```no_run
+//! use env::config::project::prelude::*;
+//!
+//! let workspace = Workspace::try_new()?;
+//! for solution in workspace.solutions() {
+//!   println!("{solution}");
+//! }
+//! # Ok::<(), env::prelude::EnvError>(())
+//! ```
+
+use {
+  super::super::_prelude::*,
+  std::fs::read_dir,
+};
+
+/// The members of a Cargo workspace.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+  /// The directory of the workspace `Cargo.toml`.
+  pub root: PathBuf,
+  /// `[workspace] members`, as written.
+  pub patterns: Vec<String>,
+  /// `[workspace] exclude`, as written.
+  pub exclude: Vec<String>,
+  /// Every member, in the order of `patterns` and sorted within a glob.
+  pub members: Vec<Member>,
+}
+
+/// One crate of the workspace.
+#[derive(Debug, Clone)]
+pub struct Member {
+  /// The directory holding the member's `Cargo.toml`.
+  pub directory: PathBuf,
+  /// The member's manifest, with inherited fields resolved.
+  pub metadata: PackageMetadata,
+  /// The puzzle this member solves, if it is a solution crate.
+  pub solution: Option<Solution>,
+}
+
+/// The puzzle of one Advent of Code day.
+///
+/// Orders by year, then day.
+///
+/// # Examples
+/// ```
+/// use env::config::project::prelude::*;
+///
+/// let solution = Solution { year: 2024, day: 7 };
+/// assert_eq!(solution.to_string(), "2024/07");
+/// let paths = ProjectPaths::from_roots("/aoc/code/rust", "/aoc");
+/// assert!(solution.paths(&paths).input.ends_with("2024/07/input.txt"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Solution {
+  /// The event year, 2015 onwards.
+  pub year: u16,
+  /// The day of the event, 1 to 25.
+  pub day: u8,
+}
+
+impl Workspace {
+  /// Loads the workspace found by `find_project_path()`.
+  ///
+  /// # Errors
+  /// As `try_load()`.
+  pub fn try_new() -> Result<Self> {
+    Self::try_load(&find_project_path())
+  }
+
+  /// Loads the workspace whose `Cargo.toml` is in `root`, with every
+  /// member's manifest.
+  ///
+  /// # Errors
+  /// - `Error::Io` if the workspace manifest or a member's cannot be read,
+  ///   including a literally listed member that does not exist
+  /// - `Error::Toml` if a manifest is not valid TOML
+  /// - `Error::Inherit` if a member inherits a field that
+  ///   `[workspace.package]` does not set
+  ///
+  /// # Examples
+  /// ```
+  /// use {
+  ///   env::config::project::prelude::*,
+  ///   std::fs::{
+  ///     create_dir_all,
+  ///     write,
+  ///   },
+  /// };
+  ///
+  /// let root = std::env::temp_dir().join(format!("env-workspace-{}", std::process::id()));
+  /// create_dir_all(&root)?;
+  /// write(
+  ///   root.join("Cargo.toml"),
+  ///   r#"
+  ///     [workspace]
+  ///     members = ["crates/cli", "tasks/*/[0-9][0-9]"]
+  ///     exclude = ["tasks/2015"]
+  ///
+  ///     [workspace.package]
+  ///     version = "0.1.0"
+  ///   "#,
+  /// )?;
+  /// for member in [
+  ///   "crates/cli",
+  ///   "tasks/2015/01",
+  ///   "tasks/2024/01",
+  ///   "tasks/2024/07",
+  ///   "tasks/2024/notes",
+  /// ] {
+  ///   let directory = root.join(member);
+  ///   create_dir_all(&directory)?;
+  ///   let name = member.replace('/', "-");
+  ///   write(
+  ///     directory.join("Cargo.toml"),
+  ///     format!("[package]\nname = \"{name}\"\nversion = {{ workspace = true }}\n"),
+  ///   )?;
+  /// }
+  ///
+  /// let workspace = Workspace::try_load(&root)?;
+  /// let names: Vec<_> = workspace
+  ///   .members
+  ///   .iter()
+  ///   .map(|member| member.metadata.name.as_str())
+  ///   .collect();
+  /// assert_eq!(names, ["crates-cli", "tasks-2024-01", "tasks-2024-07"]);
+  /// assert_eq!(workspace.members[1].metadata.version, "0.1.0");
+  /// assert_eq!(
+  ///   workspace.solutions(),
+  ///   [
+  ///     Solution { year: 2024, day: 1 },
+  ///     Solution { year: 2024, day: 7 }
+  ///   ]
+  /// );
+  /// assert!(workspace.solution(2024, 7).is_some());
+  /// # std::fs::remove_dir_all(&root)?;
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  pub fn try_load(root: &Path) -> Result<Self> {
+    let manifest = Manifest::try_read(&root.join("Cargo.toml"))?;
+    let workspace = manifest.workspace.unwrap_or_default();
+    let (patterns, exclude) = (workspace.members, workspace.exclude);
+
+    let mut directories = Vec::new();
+    if manifest.package.is_some() {
+      directories.push(root.to_path_buf());
+    }
+    for pattern in &patterns {
+      let literal = is_literal(pattern);
+      for directory in expand(root, pattern) {
+        let excluded = !literal
+          && exclude
+            .iter()
+            .any(|excluded| directory.starts_with(root.join(excluded)));
+        if !excluded && !directories.contains(&directory) {
+          directories.push(directory);
+        }
+      }
+    }
+
+    let members = directories
+      .into_iter()
+      .map(|directory| Member::try_load(directory, &workspace.package))
+      .collect::<Result<_>>()?;
+
+    Ok(Self {
+      root: root.to_path_buf(),
+      patterns,
+      exclude,
+      members,
+    })
+  }
+
+  /// The puzzles that have a solution crate, in order.
+  #[must_use]
+  pub fn solutions(&self) -> Vec<Solution> {
+    let mut solutions: Vec<Solution> = self
+      .members
+      .iter()
+      .filter_map(|member| member.solution)
+      .collect();
+    solutions.sort_unstable();
+    solutions
+  }
+
+  /// The solution crate for `day` of `year`, if there is one.
+  #[must_use]
+  pub fn solution(&self, year: u16, day: u8) -> Option<&Member> {
+    let wanted = Solution { year, day };
+    self
+      .members
+      .iter()
+      .find(|member| member.solution == Some(wanted))
+  }
+
+  /// The member whose package is called `name`.
+  #[must_use]
+  pub fn member(&self, name: &str) -> Option<&Member> {
+    self
+      .members
+      .iter()
+      .find(|member| member.metadata.name == name)
+  }
+}
+
+impl Member {
+  /// Reads the member in `directory`, inheriting from `shared`.
+  fn try_load(directory: PathBuf, shared: &CargoToml) -> Result<Self> {
+    let metadata =
+      PackageMetadata::try_from_member_manifest(&directory.join("Cargo.toml"), shared)?;
+    Ok(Self {
+      solution: Solution::from_directory(&directory),
+      directory,
+      metadata,
+    })
+  }
+}
+
+impl Solution {
+  /// The puzzle files of this day.
+  #[must_use]
+  pub fn paths(self, paths: &ProjectPaths) -> PuzzlePaths {
+    paths.puzzle(self.year, self.day)
+  }
+
+  /// The puzzle of a member in `.../<year>/<day>`, such as `tasks/2024/07`.
+  pub(super) fn from_directory(directory: &Path) -> Option<Self> {
+    let mut names = directory
+      .components()
+      .rev()
+      .map(|component| component.as_os_str().to_str());
+    let day = names.next()??;
+    let year = names.next()??;
+    if year.len() != 4 || day.len() > 2 {
+      return None;
+    }
+    let day = day.parse().ok().filter(|day| (1..=25).contains(day))?;
+    let year = year.parse().ok().filter(|year| *year >= 2015)?;
+    Some(Self { year, day })
+  }
+}
+
+impl Display for Solution {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "{}/{:02}", self.year, self.day)
+  }
+}
+
+/// Whether `pattern` has no glob syntax.
+fn is_literal(pattern: &str) -> bool {
+  !pattern.contains(['*', '?', '['])
+}
+
+/// Whether the member `path`, relative to the workspace root, is named by
+/// `pattern`.
+pub(super) fn pattern_matches(pattern: &str, path: &str) -> bool {
+  let parts = |text: &'_ str| -> Vec<Vec<char>> {
+    text
+      .split('/')
+      .filter(|part| !part.is_empty() && *part != ".")
+      .map(|part| part.chars().collect())
+      .collect()
+  };
+  let (pattern, path) = (parts(pattern), parts(path));
+  pattern.len() == path.len()
+    && pattern
+      .iter()
+      .zip(&path)
+      .all(|(pattern, name)| matches(pattern, name))
+}
+
+/// The paths below `root` that `pattern` names.
+///
+/// A literal pattern names its path whether or not it exists; a glob only
+/// matches existing directories.
+fn expand(root: &Path, pattern: &str) -> Vec<PathBuf> {
+  let mut paths = vec![root.to_path_buf()];
+  for part in pattern
+    .split('/')
+    .filter(|part| !part.is_empty() && *part != ".")
+  {
+    if is_literal(part) {
+      for path in &mut paths {
+        path.push(part);
+      }
+    } else {
+      let part: Vec<char> = part.chars().collect();
+      paths = paths
+        .iter()
+        .flat_map(|directory| matching_directories(directory, &part))
+        .collect();
+    }
+  }
+  if !is_literal(pattern) {
+    paths.retain(|path| path.is_dir());
+  }
+  paths
+}
+
+/// The subdirectories of `directory` whose names match `part`, sorted.
+fn matching_directories(directory: &Path, part: &[char]) -> Vec<PathBuf> {
+  let Ok(entries) = read_dir(directory) else {
+    return Vec::new();
+  };
+  let mut found: Vec<PathBuf> = entries
+    .filter_map(|entry| {
+      let path = entry.ok()?.path();
+      let name: Vec<char> = path.file_name()?.to_str()?.chars().collect();
+      (matches(part, &name) && path.is_dir()).then_some(path)
+    })
+    .collect();
+  found.sort();
+  found
+}
+
+/// Whether `name` matches the glob `pattern`.
+fn matches(pattern: &[char], name: &[char]) -> bool {
+  match pattern.split_first() {
+    None => name.is_empty(),
+    Some(('*', rest)) => (0..=name.len()).any(|skip| matches(rest, &name[skip..])),
+    Some((first, rest)) => {
+      let Some((character, name)) = name.split_first() else {
+        return false;
+      };
+      match first {
+        '?' => matches(rest, name),
+        '[' => match set(rest, *character) {
+          Some((found, after)) => found && matches(after, name),
+          None => *character == '[' && matches(rest, name),
+        },
+        literal => literal == character && matches(rest, name),
+      }
+    }
+  }
+}
+
+/// Matches `character` against the set that starts `pattern`, just after its
+/// `[`. Returns whether it matched and the pattern after the closing `]`, or
+/// `None` if the set is not closed.
+fn set(pattern: &[char], character: char) -> Option<(bool, &[char])> {
+  let (negated, mut rest) = match pattern.split_first() {
+    Some(('!' | '^', rest)) => (true, rest),
+    _ => (false, pattern),
+  };
+  let mut found = false;
+  let mut first = true;
+  loop {
+    rest = match rest {
+      [']', after @ ..] if !first => return Some((found != negated, after)),
+      [low, '-', high, after @ ..] if *high != ']' => {
+        found |= (*low..=*high).contains(&character);
+        after
+      }
+      [single, after @ ..] => {
+        found |= *single == character;
+        after
+      }
+      [] => return None,
+    };
+    first = false;
+  }
+}
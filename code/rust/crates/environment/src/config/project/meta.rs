@@ -66,6 +66,7 @@ pub fn set(data: CargoToml) -> &'static CargoToml {
 /// }
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metadata {
   pub name: String,
   pub version: String,
@@ -90,39 +91,14 @@ impl Default for Metadata {
   /// - Subsequent calls: <1µs (cache lookup)
   ///
   /// # Failures (non-fatal, uses defaults)
-  /// - Cargo.toml not found
-  /// - TOML parsing error
+  /// - Cargo.toml not found or unreadable, or not valid TOML: the diagnostic
+  ///   is printed to stderr; use `try_new()` to handle it instead
   /// - Expected section missing
   fn default() -> Self {
-    let meta = Self::read_project_toml();
-
-    // Workspace does not have name
-    let fallback_name = String::from("craole-cc");
-
-    // Try to get package metadata from project [package] section
-    let name = meta
-      .get("package")
-      .and_then(|p| p.get("name"))
-      .and_then(|n| n.as_str())
-      .map_or(fallback_name, String::from);
-
-    let version = meta
-      .get("version")
-      .and_then(|v| v.as_str())
-      .map(String::from)
-      .unwrap_or_default();
-
-    let description = meta
-      .get("description")
-      .and_then(|d| d.as_str())
-      .map(String::from)
-      .unwrap_or_default();
-
-    Self {
-      name,
-      version,
-      description,
-    }
+    Self::try_new().unwrap_or_else(|error| {
+      eprintln!("{:?}", miette::Report::new(error));
+      Self::from_toml(&CargoToml::new())
+    })
   }
 }
 
@@ -162,13 +138,68 @@ impl Metadata {
     self
   }
 
+  /// Creates metadata from the workspace Cargo.toml, reporting why it could
+  /// not be read.
+  ///
+  /// A manifest without a `[package]` or `[workspace.package]` section is
+  /// not an error; the fields then take their defaults.
+  ///
+  /// # Errors
+  /// - `Error::Io` if the Cargo.toml cannot be read
+  /// - `Error::Toml` if it is not valid TOML, pointing at the syntax error
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use env::config::project::prelude::*;
+  ///
+  /// let metadata = ProjectMetadata::try_new()?;
+  /// # Ok::<(), env::prelude::EnvError>(())
+  /// ```
+  pub fn try_new() -> Result<Self> {
+    Ok(Self::from_toml(Self::read_project_toml()?))
+  }
+
+  /// Extracts the fields from a parsed `[package]` or `[workspace.package]`
+  /// section, with defaults for missing ones.
+  fn from_toml(meta: &CargoToml) -> Self {
+    // Workspace does not have name
+    let fallback_name = String::from("craole-cc");
+
+    // Try to get package metadata from project [package] section
+    let name = meta
+      .get("package")
+      .and_then(|p| p.get("name"))
+      .and_then(|n| n.as_str())
+      .map_or(fallback_name, String::from);
+
+    let version = meta
+      .get("version")
+      .and_then(|v| v.as_str())
+      .map(String::from)
+      .unwrap_or_default();
+
+    let description = meta
+      .get("description")
+      .and_then(|d| d.as_str())
+      .map(String::from)
+      .unwrap_or_default();
+
+    Self {
+      name,
+      version,
+      description,
+    }
+  }
+
   /// Load and cache the project Cargo.toml metadata.
   ///
   /// Uses the static `METADATA` cache to ensure the file is only read once.
-  /// Calls `read_toml_metadata()` from `project::utils` to do the actual work.
+  /// Calls `try_read_toml_metadata()` from `project::tool` to do the actual
+  /// work; failures are not cached, so a fixed file is picked up next time.
   ///
   /// # Returns
-  /// `&'static CargoToml` - The parsed [package] or [workspace.package] section
+  /// `&'static CargoToml` - The parsed [package] or [workspace.package] section,
+  /// empty if the section is missing
   ///
   /// # Performance
   /// - First call: ~5-15ms (file I/O + TOML parsing)
@@ -176,19 +207,13 @@ impl Metadata {
   ///
   /// # Caching
   /// The result is cached in `METADATA` static `OnceLock`.
-  /// Thread-safe; multiple threads coordinate safely during first initialization.
-  fn read_project_toml() -> &'static CargoToml {
-    METADATA.get_or_init(|| {
-      let project_root = find_project_path();
-      let cargo_toml_path = project_root.join("Cargo.toml");
-
-      read_toml_metadata(&cargo_toml_path).unwrap_or_else(|| {
-        eprintln!(
-          "Failed to read metadata from Cargo.toml at {}",
-          cargo_toml_path.display()
-        );
-        TomlMap::new()
-      })
-    })
+  /// Thread-safe; if several threads race on first use, one result wins.
+  fn read_project_toml() -> Result<&'static CargoToml> {
+    if let Some(meta) = METADATA.get() {
+      return Ok(meta);
+    }
+    let cargo_toml_path = find_project_path().join("Cargo.toml");
+    let meta = try_read_toml_metadata(&cargo_toml_path)?.unwrap_or_default();
+    Ok(METADATA.get_or_init(|| meta))
   }
 }
@@ -22,6 +22,10 @@
 //! project/
 //! ├── config.rs      - Configuration from environment variables
 //! ├── core.rs        - Main Project Environment struct
+//! ├── graph.rs       - Resolved package graph (`metadata` feature)
+//! ├── layer.rs       - Configuration layers and value provenance
+//! ├── manifest.rs    - Typed manifests and format-preserving edits
+//! ├── member.rs      - Workspace members and solution crates
 //! ├── metadata.rs    - Workspace metadata from Cargo.toml
 //! ├── paths.rs       - Workspace paths and directories
 //! ├── utils.rs       - Workspace discovery and TOML parsing
@@ -71,7 +75,7 @@
 //! When `Project::default()` is called, it:
 //! 1. Discovers the workspace root via `find_project_path()` (~5-50ms)
 //! 2. Reads and parses `workspace_root/Cargo.toml` for metadata (~5-15ms)
-//! 3. Sets up path structure relative to workspace root (instant)
+//! 3. Finds the repository root and sets up the path structure (~2-5ms)
 //! 4. Loads environment variables for configuration (~1ms)
 //! 5. Falls back `DATABASE_URL` to `{workspace}/assets/db` if not set
 //!
@@ -80,13 +84,18 @@
 //! # Workspace Discovery Strategy
 //!
 //! The `project` module locates the workspace root using:
-//! 1. **Environment variables** - `PRJ_ROOT`, `PROJECT_ROOT`, `WORKSPACE_ROOT`, `CARGO_WORKSPACE_DIR`
+//! 1. **Environment variables** - `WORKSPACE_ROOT`, `CARGO_WORKSPACE_DIR`
 //! 2. **Walk up from `CARGO_MANIFEST_DIR`** - Looks for `[workspace]` section
 //! 3. **Walk up from current directory** - With workspace markers
 //! 4. **`cargo_metadata` fallback** - Guaranteed correct but slower
 //! 5. **Last resort** - Falls back to `CARGO_MANIFEST_DIR` or current directory
 //!
-//! See `utils::find_project_path()` for detailed strategy documentation.
+//! The repository root above it, where puzzle data and documentation live, is
+//! found like `scripts/locate-root.sh` does: `PROJECT_ROOT`, then git, then
+//! the nearest `.git` directory.
+//!
+//! See `utils::find_project_path()` and `utils::find_repository_path()` for
+//! detailed strategy documentation.
 //!
 //! # Common Patterns
 //!
@@ -123,13 +132,23 @@
 
 mod config;
 mod core;
+#[cfg(feature = "metadata")]
+mod graph;
+mod layer;
+mod manifest;
+mod member;
 mod meta;
 mod path;
 mod tool;
 
+#[cfg(feature = "metadata")]
+pub use graph::*;
 pub use {
   config::*,
   core::*,
+  layer::*,
+  manifest::*,
+  member::*,
   meta::*,
   path::*,
   tool::*,
@@ -168,15 +187,45 @@ pub use {
 /// ```
 ///
 /// # What's Included
-/// - **Types**: Configuration, Environment, Metadata, Paths
-/// - **Functions**: `find_project_path()`, `is_workspace_toml()`(), `read_toml_metadata()`
-/// - **Type aliases**: `ProjectConfig`, `Project`, `ProjectMetadata`, `ProjectPaths`
+/// - **Types**: Configuration, Environment, Metadata, Paths, `PuzzlePaths`,
+///   `Manifest`, `ManifestEditor`, `Solution`, `Workspace`, `WorkspaceSection`,
+///   and `WorkspaceGraph` with the `metadata` feature
+/// - **Functions**: `find_project_path()`, `find_repository_path()`, `is_workspace_toml()`(), `read_toml_metadata()`
+/// - **Type aliases**: `ProjectConfig`, `Project`, `ProjectMetadata`, `ProjectPaths`,
+///   `WorkspaceMember`
 pub mod prelude {
+  #[cfg(feature = "metadata")]
+  pub use super::graph::{
+    GraphDependency,
+    GraphPackage,
+    GraphTarget,
+    WorkspaceGraph,
+  };
   pub use super::{
     config::Configuration as ProjectConfig,
     core::Environment as Project,
+    layer::{
+      CONFIG_FILE,
+      DOTENV_FILES,
+      Origins as ConfigOrigins,
+      SETTINGS,
+      Source as ConfigSource,
+    },
+    manifest::{
+      Manifest,
+      ManifestEditor,
+      WorkspaceSection,
+    },
+    member::{
+      Member as WorkspaceMember,
+      Solution,
+      Workspace,
+    },
     meta::Metadata as ProjectMetadata,
-    path::Paths as ProjectPaths,
+    path::{
+      Paths as ProjectPaths,
+      PuzzlePaths,
+    },
     tool::*,
   };
 }
@@ -5,25 +5,42 @@
 //!
 //! # Path Structure
 //!
-//! All paths are discovered relative to the workspace root:
+//! The Cargo workspace is discovered first, then the repository around it:
 //! ```text
-//! {workspace_root}/
-//! ├── Cargo.toml
-//! ├── assets/
-//! │   └── db/
-//! ├── src/
-//! └── ...
+//! {repository}/
+//! ├── .cache/                 - cache
+//! ├── assets/                 - assets
+//! │   ├── data/               - data
+//! │   │   └── 2024/
+//! │   │       └── 01/         - puzzle(2024, 1)
+//! │   │           ├── input.txt
+//! │   │           ├── instructions.html
+//! │   │           ├── output.txt
+//! │   │           └── examples/
+//! │   ├── db/                 - database
+//! │   └── templates/          - templates
+//! ├── code/                   - code
+//! │   ├── rust/               - project, package, language("rust")
+//! │   │   └── Cargo.toml
+//! │   └── shellscript/        - language("shellscript")
+//! └── documentation/          - documentation
 //! ```
 //!
+//! Set `PROJECT_ROOT` to override the repository root and `WORKSPACE_ROOT`
+//! to override the workspace root.
+//!
 //! # Examples
 //!
 //! ```no_run
-//! use env::project::prelude::*;
+//! use env::config::project::prelude::*;
 //!
 //! let paths = ProjectPaths::default();
 //! println!("Project root: {}", paths.project.display());
-//! println!("Assets: {}", paths.assets.display());
+//! println!("Repository root: {}", paths.repository.display());
 //! println!("Database: {}", paths.database.display());
+//!
+//! let puzzle = paths.puzzle(2024, 1);
+//! println!("Input: {}", puzzle.input.display());
 //! ```
 
 use super::super::_prelude::*;
@@ -31,17 +48,25 @@ use super::super::_prelude::*;
 /// Project directory structure and path management.
 ///
 /// Contains references to all important directories for the application.
-/// All paths are discovered automatically from the workspace root via `find_project_path()`.
+/// The workspace root is discovered via `find_project_path()` and the
+/// repository root around it via `find_repository_path()`.
 ///
 /// # Fields
 /// - `project` - Workspace root directory (contains Cargo.toml)
 /// - `package` - Currently set to same as project (can be customized if needed)
-/// - `assets` - Static assets directory ({project}/assets)
-/// - `database` - Database files directory ({project}/assets/db)
+/// - `repository` - Repository root directory ({repository})
+/// - `assets` - Static assets directory ({repository}/assets)
+/// - `database` - Database files directory ({repository}/assets/db)
+/// - `data` - Puzzle data directory ({repository}/assets/data)
+/// - `templates` - Solution templates directory ({repository}/assets/templates)
+/// - `cache` - Cache directory ({repository}/.cache)
+/// - `documentation` - Documentation directory ({repository}/documentation)
+/// - `code` - Per-language code directory ({repository}/code)
 ///
 /// # Defaults
 /// All paths are discovered from the workspace root on first access.
-/// To customize, create a `Paths` instance and modify fields before use.
+/// To customize, create a `Paths` instance and modify fields before use, or
+/// build one from explicit roots with `Paths::from_roots()`.
 ///
 /// # Thread Safety
 /// Safe to clone and share; all fields are `PathBuf` types.
@@ -49,7 +74,7 @@ use super::super::_prelude::*;
 /// # Examples
 /// ```no_run
 /// use {
-///   env::project::prelude::*,
+///   env::config::project::prelude::*,
 ///   std::path::PathBuf,
 /// };
 ///
@@ -60,13 +85,14 @@ use super::super::_prelude::*;
 /// paths.database = PathBuf::from("/custom/db/path");
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Paths {
   /// Workspace root directory (contains workspace Cargo.toml).
   ///
   /// Discovered via `find_project_path()` which walks up from the current location
   /// or uses environment variable overrides.
   ///
-  /// This is the definitive project root for all other path calculations.
+  /// This is the root for Cargo and configuration files (`aoc.toml`, `.env`).
   pub project: PathBuf,
 
   /// Package directory (typically same as project).
@@ -76,17 +102,25 @@ pub struct Paths {
   /// different package roots).
   pub package: PathBuf,
 
+  /// Repository root directory.
+  ///
+  /// Discovered via `find_repository_path()`, like `scripts/locate-root.sh`,
+  /// or overridden with the `PROJECT_ROOT` environment variable.
+  ///
+  /// This is the root for every path below.
+  pub repository: PathBuf,
+
   /// Static assets directory.
   ///
-  /// Standard location: `{project}/assets`
+  /// Standard location: `{repository}/assets`
   ///
-  /// Used for storing static files, configuration files, and other assets
-  /// bundled with the application.
+  /// Used for storing puzzle data, icons, and other assets shared by every
+  /// language.
   pub assets: PathBuf,
 
   /// Database files directory.
   ///
-  /// Standard location: `{project}/assets/db`
+  /// Standard location: `{repository}/assets/db`
   ///
   /// Used for `SQLite` databases and other file-based database storage.
   /// Falls back to this path if `DATABASE_URL` environment variable is not set.
@@ -95,39 +129,151 @@ pub struct Paths {
   /// The actual database location can be overridden via `DATABASE_URL` env var
   /// in `Configuration::db`.
   pub database: PathBuf,
+
+  /// Puzzle data directory, one `{year}/{day}` directory per puzzle.
+  ///
+  /// Standard location: `{repository}/assets/data`
+  ///
+  /// Filled by `scripts/fetch-aoc.sh`; see `puzzle()` for the files inside.
+  pub data: PathBuf,
+
+  /// Solution templates directory.
+  ///
+  /// Standard location: `{repository}/assets/templates`
+  pub templates: PathBuf,
+
+  /// Cache directory for downloaded and derived files.
+  ///
+  /// Standard location: `{repository}/.cache`
+  pub cache: PathBuf,
+
+  /// Documentation directory.
+  ///
+  /// Standard location: `{repository}/documentation`
+  pub documentation: PathBuf,
+
+  /// Directory holding one subdirectory per language.
+  ///
+  /// Standard location: `{repository}/code`; see `language()`.
+  pub code: PathBuf,
+}
+
+/// The files of one puzzle, under `{data}/{year}/{day:02}`.
+///
+/// Paths are computed, not checked; any of them may not exist yet.
+///
+/// # Examples
+/// ```no_run
+/// use env::config::project::prelude::*;
+///
+/// let puzzle = ProjectPaths::default().puzzle(2023, 5);
+/// assert!(puzzle.input.ends_with("2023/05/input.txt"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PuzzlePaths {
+  /// The puzzle's directory: `{data}/{year}/{day:02}`.
+  pub directory: PathBuf,
+  /// Personal puzzle input: `input.txt`.
+  pub input: PathBuf,
+  /// Puzzle description as fetched: `instructions.html`.
+  pub instructions: PathBuf,
+  /// The solution's answers, one line per part, in the placeholder that
+  /// `scripts/fetch-aoc.sh` creates: `output.txt`.
+  pub answers: PathBuf,
+  /// Example inputs from the description: `examples/`.
+  pub examples: PathBuf,
 }
 
 impl Default for Paths {
-  /// Creates paths by discovering the workspace root and deriving standard locations.
+  /// Creates paths by discovering the workspace and repository roots and
+  /// deriving standard locations.
   ///
   /// # Process
   /// 1. Call `find_project_path()` to locate workspace root
-  /// 2. Set `package` to same as `project` (can be customized)
-  /// 3. Derive `assets` = `project/assets`
-  /// 4. Derive `database` = `assets/db`
+  /// 2. Call `find_repository_path()` to locate the repository around it
+  /// 3. Derive the rest with `Paths::from_roots()`
   ///
   /// # Performance
-  /// ~5-50ms on first call (workspace discovery)
-  /// <1µs on subsequent calls (caching in `find_project_path`)
+  /// ~5-50ms on first call (workspace discovery, one `git` call)
   ///
   /// # Examples
   /// ```no_run
-  /// use env::project::prelude::*;
+  /// use env::config::project::prelude::*;
   /// let paths = ProjectPaths::default();
   /// assert!(paths.assets.ends_with("assets"));
   /// assert!(paths.database.ends_with("db"));
   /// ```
   fn default() -> Self {
     let project = find_project_path();
-    let package = project.clone();
-    let assets = project.join("assets");
-    let database = assets.join("db");
+    let repository = find_repository_path(&project);
+    Self::from_roots(project, repository)
+  }
+}
+
+impl Paths {
+  /// Derives the standard locations from explicit workspace and repository
+  /// roots, without any discovery.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::config::project::prelude::*;
+  ///
+  /// let paths = ProjectPaths::from_roots("/aoc/code/rust", "/aoc");
+  /// assert_eq!(paths.data, std::path::Path::new("/aoc/assets/data"));
+  /// assert_eq!(paths.language("rust"), paths.project);
+  /// ```
+  #[must_use]
+  pub fn from_roots(project: impl Into<PathBuf>, repository: impl Into<PathBuf>) -> Self {
+    let project = project.into();
+    let repository = repository.into();
+    let assets = repository.join("assets");
 
     Self {
+      package: project.clone(),
       project,
-      package,
+      database: assets.join("db"),
+      data: assets.join("data"),
+      templates: assets.join("templates"),
       assets,
-      database,
+      cache: repository.join(".cache"),
+      documentation: repository.join("documentation"),
+      code: repository.join("code"),
+      repository,
+    }
+  }
+
+  /// The code directory of one language: `{repository}/code/{language}`.
+  #[must_use]
+  pub fn language(&self, language: &str) -> PathBuf {
+    self.code.join(language)
+  }
+
+  /// The files of the puzzle for `day` of `year`.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::config::project::prelude::*;
+  ///
+  /// let puzzle = ProjectPaths::from_roots("/aoc/code/rust", "/aoc").puzzle(2024, 7);
+  /// assert_eq!(
+  ///   puzzle.input,
+  ///   std::path::Path::new("/aoc/assets/data/2024/07/input.txt")
+  /// );
+  /// assert_eq!(
+  ///   puzzle.examples,
+  ///   std::path::Path::new("/aoc/assets/data/2024/07/examples")
+  /// );
+  /// ```
+  #[must_use]
+  pub fn puzzle(&self, year: u16, day: u8) -> PuzzlePaths {
+    let directory = self.data.join(year.to_string()).join(format!("{day:02}"));
+    PuzzlePaths {
+      input: directory.join("input.txt"),
+      instructions: directory.join("instructions.html"),
+      answers: directory.join("output.txt"),
+      examples: directory.join("examples"),
+      directory,
     }
   }
 }
@@ -9,7 +9,7 @@
 //! The workspace root is located using a 5-step process with fast paths first:
 //!
 //! 1. **Environment variable override** (~instant)
-//!    - `PRJ_ROOT`, `PROJECT_ROOT`, `WORKSPACE_ROOT`, or `CARGO_WORKSPACE_DIR`
+//!    - `WORKSPACE_ROOT` or `CARGO_WORKSPACE_DIR`
 //!    - Useful for testing or non-standard layouts
 //!
 //! 2. **Walk up from `CARGO_MANIFEST_DIR`** (~1-2ms)
@@ -42,7 +42,10 @@
 //! }
 //! ```
 
-use super::super::_prelude::*;
+use {
+  super::super::_prelude::*,
+  std::process::Command,
+};
 
 /// Find the workspace root directory with fast detection methods first, then fallbacks.
 ///
@@ -71,7 +74,6 @@ use super::super::_prelude::*;
 pub fn find_project_path() -> PathBuf {
   // Methods 0 & 1: Explicit override via environment variables
   let env_vars = [
-    "PROJECT_ROOT",
     "WORKSPACE_ROOT",
     "CARGO_WORKSPACE_DIR", // Rust 1.80+, instant
   ];
@@ -114,6 +116,69 @@ pub fn find_project_path() -> PathBuf {
     .unwrap_or_else(|_| PathBuf::from("."))
 }
 
+/// Find the repository root containing the Cargo workspace at `workspace`.
+///
+/// The Cargo workspace lives in `{repository}/code/rust`, while puzzle data,
+/// documentation and scripts live at the repository root. Follows the same
+/// steps as `scripts/locate-root.sh`:
+///
+/// 1. The `PROJECT_ROOT` environment variable, if it names a directory
+/// 2. `git rev-parse --show-toplevel`, run in `workspace`
+/// 3. The nearest ancestor of `workspace` with a `.git` directory
+/// 4. Fallback: two levels above a workspace in `code/<lang>`, otherwise
+///    `workspace` itself
+///
+/// # Returns
+/// `PathBuf` - The detected repository root (never fails)
+///
+/// # Performance
+/// - Override: <1ms
+/// - Common case: ~2-5ms (spawns `git`)
+///
+/// # Examples
+/// ```no_run
+/// use env::config::project::prelude::*;
+///
+/// let repository = find_repository_path(&find_project_path());
+/// assert!(repository.join("assets").exists());
+/// ```
+#[must_use]
+pub fn find_repository_path(workspace: &Path) -> PathBuf {
+  // Method 1: Explicit override via environment variable
+  if let Ok(root) = var("PROJECT_ROOT") {
+    let path = PathBuf::from(root);
+    if path.is_dir() {
+      return path;
+    }
+  }
+
+  // Method 2: Ask git
+  if let Ok(output) = Command::new("git")
+    .args(["rev-parse", "--show-toplevel"])
+    .current_dir(workspace)
+    .output()
+    && output.status.success()
+  {
+    let root = String::from_utf8_lossy(&output.stdout);
+    let root = root.trim();
+    if !root.is_empty() {
+      return PathBuf::from(root);
+    }
+  }
+
+  // Method 3: Walk up looking for .git
+  if let Some(root) = workspace.ancestors().find(|dir| dir.join(".git").is_dir()) {
+    return root.to_path_buf();
+  }
+
+  // Fallback: {repository}/code/<lang>, or the workspace itself
+  workspace
+    .parent()
+    .filter(|code| code.file_name().is_some_and(|name| name == "code"))
+    .and_then(Path::parent)
+    .map_or_else(|| workspace.to_path_buf(), Path::to_path_buf)
+}
+
 /// Walk up from `CARGO_MANIFEST_DIR` to find workspace Cargo.toml.
 ///
 /// Checks each parent directory for a workspace Cargo.toml up to 10 levels.
@@ -183,52 +248,35 @@ fn search_from_current_dir() -> Option<PathBuf> {
   None
 }
 
-/// Check if a Cargo.toml file defines a workspace (optimized for speed).
+/// Check if a Cargo.toml file defines a workspace.
 ///
-/// Uses two optimizations:
-/// 1. **Fast reject**: Files < 50 bytes can't be workspace Cargo.toml
-/// 2. **Content check**: Must have `[workspace]` section + `members` or `resolver`
+/// Parses the file as a `Manifest` and checks for a `[workspace]` table,
+/// after a fast reject of files that never mention `workspace`.
 ///
 /// # Parameters
 /// - `path` - Path to Cargo.toml to check
 ///
 /// # Returns
-/// `bool` - True if this is a workspace Cargo.toml, false otherwise
+/// `bool` - True if this is a workspace Cargo.toml, false otherwise,
+/// including when the file cannot be read or parsed
 ///
 /// # Performance
-/// Typically <1ms (single file read + string search)
+/// Typically <1ms (single file read + TOML parse)
 ///
 /// # Examples
-/// ```no_run
-/// use {
-///   env::project::prelude::*,
-///   std::path::Path,
-/// };
+/// ```
+/// use env::config::project::prelude::*;
 ///
-/// let is_ws = is_workspace_toml(Path::new("Cargo.toml"));
-/// println!("Is workspace: {}", is_ws);
+/// assert!(is_workspace_toml(&find_project_path().join("Cargo.toml")));
+/// let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+/// assert!(!is_workspace_toml(&manifest));
 /// ```
 #[must_use]
 pub fn is_workspace_toml(path: &Path) -> bool {
-  // Fast path: check file size first (workspace Cargo.toml usually > 100 bytes)
-  if let Ok(file_metadata) = metadata(path)
-    && file_metadata.len() < 50
-  {
-    return false; // Too small to be a workspace Cargo.toml
-  }
-
-  // Read and check for workspace markers
-  if let Ok(contents) = read_to_string(path) {
-    // Quick check: must have [workspace] section
-    if !contents.contains("[workspace]") {
-      return false;
-    }
-
-    // Workspace should have members or resolver
-    contents.contains("members") || contents.contains("resolver")
-  } else {
-    false
-  }
+  read_to_string(path).is_ok_and(|contents| {
+    contents.contains("workspace")
+      && Manifest::from_contents(path, &contents).is_ok_and(|manifest| manifest.is_workspace())
+  })
 }
 
 /// Fallback workspace detection using `cargo_metadata` (slower but guaranteed correct).
@@ -246,6 +294,10 @@ pub fn is_workspace_toml(path: &Path) -> bool {
 ///
 /// # Feature
 /// Requires `metadata` feature to be enabled
+///
+/// To list the workspace members, prefer `Workspace::try_load()`, which reads
+/// the manifests directly instead of spawning cargo. For targets, features
+/// and resolved dependencies, use `WorkspaceGraph`.
 #[cfg(feature = "metadata")]
 #[must_use]
 pub fn find_project_path_via_cargo_meta() -> Option<PathBuf> {
@@ -272,8 +324,8 @@ pub fn find_project_path_via_cargo_meta() -> Option<PathBuf> {
 /// # Behavior
 /// - **Workspace file**: Extracts `[workspace.package]` section
 /// - **Package file**: Extracts `[package]` section
-/// - **Parse error**: Returns None with eprintln! message
-/// - **Missing section**: Returns None with eprintln! message
+/// - **Read or parse error**: Returns None; use `try_read_toml_metadata()` for the reason
+/// - **Missing section**: Returns None
 ///
 /// # Examples
 /// ```no_run
@@ -296,23 +348,42 @@ pub fn find_project_path_via_cargo_meta() -> Option<PathBuf> {
 /// - Total: ~5-15ms
 #[must_use]
 pub fn read_toml_metadata(cargo_toml_path: &Path) -> Option<CargoToml> {
-  let contents = read_to_string(cargo_toml_path).ok()?;
-  let toml_value = from_toml_str::<TomlValue>(&contents).ok()?;
-  let root_table = toml_value.as_table()?;
+  try_read_toml_metadata(cargo_toml_path).ok().flatten()
+}
 
-  // Check if this is a workspace toml
-  if is_workspace_toml(cargo_toml_path) {
+/// Read and parse a Cargo.toml file, reporting why it could not be read.
+///
+/// Like `read_toml_metadata()`, but a missing or unreadable file and invalid
+/// TOML are errors, with the syntax error located in the file. A manifest
+/// without the expected section is not an error and yields `None`.
+///
+/// # Errors
+/// - `Error::Io` if the file cannot be read
+/// - `Error::Toml` if it is not valid TOML
+///
+/// # Examples
+/// ```no_run
+/// use {
+///   env::config::project::prelude::*,
+///   std::path::Path,
+/// };
+///
+/// let metadata = try_read_toml_metadata(Path::new("Cargo.toml"))?.unwrap_or_default();
+/// println!("Version: {:?}", metadata.get("version"));
+/// # Ok::<(), env::prelude::EnvError>(())
+/// ```
+pub fn try_read_toml_metadata(cargo_toml_path: &Path) -> Result<Option<CargoToml>> {
+  let contents =
+    read_to_string(cargo_toml_path).map_err(|error| Error::io(cargo_toml_path, error))?;
+  let root_table = from_toml_str::<CargoToml>(&contents)
+    .map_err(|error| Error::toml(cargo_toml_path, &contents, &error))?;
+
+  let section = if root_table.get("workspace").is_some_and(TomlValue::is_table) {
     // Read from [workspace.package]
-    root_table
-      .get("workspace")
-      .and_then(|w| w.get("package"))
-      .and_then(|p| p.as_table())
-      .cloned()
+    root_table.get("workspace").and_then(|w| w.get("package"))
   } else {
     // Read from [package]
-    root_table
-      .get("package")
-      .and_then(|p| p.as_table())
-      .cloned()
-  }
+    root_table.get("package")
+  };
+  Ok(section.and_then(|p| p.as_table()).cloned())
 }
@@ -0,0 +1,209 @@
+//! Hot reloading of the project configuration for long-running commands.
+//!
+//! `Reloadable` owns an `Environment` and watches the project root for
+//! changes to `Cargo.toml`, `aoc.toml` and the `.env` files. On every
+//! change it re-reads the configuration layers with
+//! `Environment::try_reload()` and, if the configuration changed, swaps in
+//! the new environment and notifies its subscribers.
+//!
+//! Values set by environment variables or `with_*` overrides are kept
+//! across reloads, and so are the metadata and paths. An invalid edit is
+//! reported to subscribers as `Reload::Failed` while the last valid
+//! environment stays current, so a typo never takes a server down.
+//!
+//! `get_env()` is unaffected: the global environment is still initialized
+//! once. Long-running commands read `Reloadable::current()` instead.
+//!
+//! # Examples
+//! ```no_run
+//! use env::prelude::*;
+//!
+//! let config = Reloadable::watch(Environment::try_new()?)?;
+//! let updates = config.subscribe();
+//! for update in updates {
+//!   match update {
+//!     Reload::Reloaded(env) => println!("now on port {}", env.project.configuration.port),
+//!     Reload::Failed(error) => eprintln!("{error}"),
+//!   }
+//! }
+//! # Ok::<(), EnvError>(())
+//! ```
+
+use {
+  super::_prelude::*,
+  notify::{
+    Event,
+    EventKind,
+    RecommendedWatcher,
+    RecursiveMode,
+    Watcher,
+  },
+  std::sync::{
+    Mutex,
+    PoisonError,
+    RwLock,
+    mpsc::{
+      Receiver,
+      Sender,
+      channel,
+    },
+  },
+};
+
+/// A change reported to `Reloadable` subscribers.
+#[derive(Debug, Clone)]
+pub enum Reload {
+  /// The configuration changed; this is the new current environment.
+  Reloaded(Arc<Environment>),
+  /// A file changed but could not be loaded, or watching failed. The
+  /// previous environment stays current.
+  Failed(Arc<Error>),
+}
+
+/// An environment that follows edits to the project's configuration files.
+///
+/// Watching stops when the handle is dropped.
+#[derive(Debug)]
+pub struct Reloadable {
+  shared: Arc<Shared>,
+  _watcher: RecommendedWatcher,
+}
+
+impl Reloadable {
+  /// Starts watching the project root of `env` for configuration changes.
+  ///
+  /// # Errors
+  /// `Error::Watch` if the project root cannot be watched.
+  pub fn watch(env: Environment) -> Result<Self> {
+    let root = env.project.paths.project.clone();
+    let shared = Arc::new(Shared {
+      current: RwLock::new(Arc::new(env)),
+      subscribers: Mutex::new(Vec::new()),
+    });
+
+    let handler = Arc::clone(&shared);
+    let mut watcher =
+      notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) if is_config_change(&event) => {
+          handler.reload();
+        }
+        Ok(_) => {}
+        Err(error) => handler.notify(&Reload::Failed(Arc::new(Error::Watch(error.to_string())))),
+      })
+      .map_err(|error| Error::Watch(error.to_string()))?;
+    watcher
+      .watch(&root, RecursiveMode::NonRecursive)
+      .map_err(|error| Error::Watch(error.to_string()))?;
+
+    Ok(Self {
+      shared,
+      _watcher: watcher,
+    })
+  }
+
+  /// The current environment.
+  ///
+  /// Cheap to call often; hold on to the returned `Arc` for a consistent
+  /// view across several reads.
+  #[must_use]
+  pub fn current(&self) -> Arc<Environment> {
+    self.shared.current()
+  }
+
+  /// Receives every future reload.
+  ///
+  /// Dropping the receiver unsubscribes.
+  #[must_use]
+  pub fn subscribe(&self) -> Receiver<Reload> {
+    let (sender, receiver) = channel();
+    self
+      .shared
+      .subscribers
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .push(sender);
+    receiver
+  }
+
+  /// Re-reads the configuration now, as if a file had changed.
+  ///
+  /// Returns what was sent to subscribers, or `None` if the configuration
+  /// is unchanged.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// let root = std::env::temp_dir().join(format!("env-reloadable-{}", std::process::id()));
+  /// std::fs::create_dir_all(&root)?;
+  /// std::fs::write(root.join(".env"), "PORT=4000\n")?;
+  ///
+  /// let mut env = Environment::new();
+  /// env.project.paths.project = root.clone();
+  /// env.project.configuration = ProjectConfig::try_load(&root)?;
+  /// let config = Reloadable::watch(env)?;
+  ///
+  /// std::fs::write(root.join(".env"), "PORT=5000\n")?;
+  /// config.reload();
+  /// assert_eq!(config.current().project.configuration.port, 5000);
+  /// # std::fs::remove_dir_all(&root)?;
+  /// # Ok::<(), Box<dyn std::error::Error>>(())
+  /// ```
+  #[allow(clippy::must_use_candidate)] // Mostly called for the side effect.
+  pub fn reload(&self) -> Option<Reload> {
+    self.shared.reload()
+  }
+}
+
+/// State shared between the handle and the watcher thread.
+#[derive(Debug)]
+struct Shared {
+  current: RwLock<Arc<Environment>>,
+  subscribers: Mutex<Vec<Sender<Reload>>>,
+}
+
+impl Shared {
+  fn current(&self) -> Arc<Environment> {
+    Arc::clone(&self.current.read().unwrap_or_else(PoisonError::into_inner))
+  }
+
+  /// Reloads under the write lock, so concurrent reloads cannot overwrite
+  /// a newer environment with one read from older files.
+  fn reload(&self) -> Option<Reload> {
+    let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+    let reload = match current.try_reload() {
+      Ok(env) if env.project.configuration == current.project.configuration => return None,
+      Ok(env) => {
+        let env = Arc::new(env);
+        *current = Arc::clone(&env);
+        Reload::Reloaded(env)
+      }
+      Err(error) => Reload::Failed(Arc::new(error)),
+    };
+    drop(current);
+    self.notify(&reload);
+    Some(reload)
+  }
+
+  /// Sends `reload` to every subscriber, forgetting those that hung up.
+  fn notify(&self, reload: &Reload) {
+    self
+      .subscribers
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .retain(|subscriber| subscriber.send(reload.clone()).is_ok());
+  }
+}
+
+/// Whether `event` touches one of the files configuration is read from.
+fn is_config_change(event: &Event) -> bool {
+  !matches!(event.kind, EventKind::Access(_))
+    && event.paths.iter().any(|path| {
+      path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+          name == "Cargo.toml" || name == CONFIG_FILE || DOTENV_FILES.contains(&name)
+        })
+    })
+}
@@ -0,0 +1,107 @@
+//! Thread-scoped environment overrides.
+//!
+//! The global `ENV` is initialized once per process, which is right for
+//! binaries but not for tests that need a different project root or port.
+//! `with_env()` runs a closure with another `Environment` layered over the
+//! global one on the current thread only; `current_env()` and `getenv!()`
+//! see the innermost layer, while `get_env()` keeps returning the global
+//! `&'static Environment` without looking at thread-local state.
+//!
+//! Scopes nest, and each one ends when its closure returns or panics.
+//! Other threads, including those the closure spawns, are unaffected.
+//!
+//! # Examples
+//! ```
+//! use env::prelude::*;
+//!
+//! let port = with_env(Environment::new().with_port(4000_u16), || getenv!(port));
+//! assert_eq!(port, 4000);
+//! assert_eq!(getenv!(port), get_env().project.configuration.port);
+//! ```
+
+use {
+  super::_prelude::*,
+  std::{
+    cell::RefCell,
+    ops::Deref,
+  },
+};
+
+thread_local! {
+  /// The overrides active on this thread, innermost last.
+  static SCOPES: RefCell<Vec<Arc<Environment>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with `env` as the current environment on this thread.
+///
+/// `env` is dropped once the scope ends and no `CurrentEnv` handed out
+/// inside it is still alive.
+///
+/// # Examples
+/// ```
+/// use env::prelude::*;
+///
+/// with_env(Environment::new().with_ip("0.0.0.0"), || {
+///   assert_eq!(current_env().project.configuration.ip, "0.0.0.0");
+///   with_env(Environment::new().with_ip("127.0.0.1"), || {
+///     assert_eq!(getenv!(ip), "127.0.0.1");
+///   });
+///   assert_eq!(getenv!(ip), "0.0.0.0");
+/// });
+/// ```
+pub fn with_env<R>(env: Environment, f: impl FnOnce() -> R) -> R {
+  SCOPES.with_borrow_mut(|scopes| scopes.push(Arc::new(env)));
+  let _scope = Scope;
+  f()
+}
+
+/// The innermost environment set by `with_env()` on this thread, or the
+/// global environment outside of any scope.
+///
+/// # Examples
+/// ```no_run
+/// use env::prelude::*;
+///
+/// println!(
+///   "Project root: {}",
+///   current_env().project.paths.project.display()
+/// );
+/// ```
+pub fn current_env() -> CurrentEnv {
+  SCOPES
+    .with_borrow(|scopes| scopes.last().cloned())
+    .map_or_else(|| CurrentEnv::Global(get_env()), CurrentEnv::Scoped)
+}
+
+/// The environment returned by `current_env()`.
+///
+/// Dereferences to `Environment`. Outside of any scope this is just the
+/// global `&'static Environment`; inside one it keeps the scoped
+/// environment alive for as long as it is held.
+#[derive(Debug, Clone)]
+pub enum CurrentEnv {
+  /// The global environment, from `get_env()`.
+  Global(&'static Environment),
+  /// The innermost `with_env()` environment.
+  Scoped(Arc<Environment>),
+}
+
+impl Deref for CurrentEnv {
+  type Target = Environment;
+
+  fn deref(&self) -> &Environment {
+    match self {
+      Self::Global(env) => env,
+      Self::Scoped(env) => env,
+    }
+  }
+}
+
+/// Ends the innermost scope when dropped, even while unwinding.
+struct Scope;
+
+impl Drop for Scope {
+  fn drop(&mut self) {
+    SCOPES.with_borrow_mut(Vec::pop);
+  }
+}
@@ -0,0 +1,613 @@
+//! Diagnostic snapshots of the environment, for `aoc doctor` and bug reports.
+//!
+//! Each part of a `Snapshot` is taken with `Capture::capture()` from the
+//! piece of the environment it describes, so parts can also be taken on their
+//! own:
+//!
+//! | Part | Captured from | Records |
+//! |------|---------------|---------|
+//! | `Vec<PathStatus>` | `ProjectPaths` | each path and whether it exists |
+//! | `Toolchain` | `ProjectPaths` | `rustc`, `cargo` and `git` versions |
+//! | `Features` | `Environment` | which `env` features were compiled in |
+//! | `Session` | `ProjectPaths` | whether a session key is set, and when it last worked |
+//! | `Provenance` | `ProjectConfig` | each configuration value and its layer |
+//! | `Assets` | `ProjectPaths` | puzzle files per year |
+//!
+//! `Snapshot::checks()` turns a snapshot into a checklist with fix-it hints,
+//! and `Snapshot::to_toml()` exports it for pasting into an issue.
+//!
+//! The session key itself is never captured, only where it was found and
+//! its length.
+//!
+//! # Examples
+//! ```no_run
+//! use env::prelude::*;
+//!
+//! let snapshot = Snapshot::capture(get_env());
+//! for check in snapshot.checks() {
+//!   println!("{} {}: {}", check.status, check.label, check.detail);
+//! }
+//! println!("{}", snapshot.to_toml());
+//! ```
+
+use {
+  super::_prelude::*,
+  std::{
+    collections::BTreeMap,
+    fs::read_dir,
+    process::Command,
+  },
+};
+
+/// The environment variable `scripts/fetch-aoc.sh` reads the session key from.
+pub const SESSION_VARIABLE: &str = "SESSION_KEY";
+
+/// Everything `aoc doctor` reports.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+  /// The running package, as `name version`.
+  pub package: String,
+  /// The resolved paths.
+  pub paths: Vec<PathStatus>,
+  /// Tool versions.
+  pub toolchain: Toolchain,
+  /// Compiled-in features of the `env` crate.
+  pub features: Features,
+  /// The adventofcode.com session key.
+  pub session: Session,
+  /// Configuration values and their layers.
+  pub provenance: Provenance,
+  /// Puzzle files per year.
+  pub assets: Assets,
+}
+
+/// One resolved path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStatus {
+  /// The field of `ProjectPaths` it came from.
+  pub name: &'static str,
+  /// The path.
+  pub path: PathBuf,
+  /// Whether it existed when captured.
+  pub exists: bool,
+}
+
+/// Versions of the tools the workspace needs, `None` if not installed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Toolchain {
+  /// `rustc --version`.
+  pub rustc: Option<String>,
+  /// `cargo --version`.
+  pub cargo: Option<String>,
+  /// `git --version`.
+  pub git: Option<String>,
+}
+
+/// The features of the `env` crate, split by whether they are enabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Features {
+  /// Compiled-in features.
+  pub enabled: Vec<&'static str>,
+  /// Available but not compiled in.
+  pub disabled: Vec<&'static str>,
+}
+
+/// Whether a session key is available, without the key itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Session {
+  /// Where the key was found: the environment variable or the key file.
+  pub source: Option<String>,
+  /// The key's length in characters, as a sanity check.
+  pub length: usize,
+  /// When a fetch with the key last succeeded.
+  pub validated: Option<StdSysTime>,
+}
+
+/// Configuration values with the layer each came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+  /// `(key, value, source)` in `SETTINGS` order.
+  pub values: Vec<(&'static str, String, String)>,
+}
+
+/// Puzzle files in the data directory, per year.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Assets {
+  /// Counts by year.
+  pub years: BTreeMap<u16, YearAssets>,
+}
+
+/// Puzzle files of one year.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct YearAssets {
+  /// Day directories.
+  pub days: usize,
+  /// Days with an `input.txt`.
+  pub inputs: usize,
+  /// Days with an `instructions.html`.
+  pub instructions: usize,
+  /// Days with an answers file, `output.txt`.
+  pub answers: usize,
+}
+
+/// How a check turned out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Status {
+  /// Nothing to do.
+  Pass,
+  /// Works, but something is missing or stale.
+  Warn,
+  /// Needs fixing before the workspace can be used.
+  Fail,
+}
+
+/// One line of the `aoc doctor` checklist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+  /// How it turned out.
+  pub status: Status,
+  /// What was checked.
+  pub label: String,
+  /// What was found.
+  pub detail: String,
+  /// How to fix it, for anything but `Status::Pass`.
+  pub hint: Option<String>,
+}
+
+impl Capture<Environment> for Snapshot {
+  fn capture(env: &Environment) -> Self {
+    let paths = &env.project.paths;
+    Self {
+      package: format!(
+        "{} {}",
+        env.package.metadata.name, env.package.metadata.version
+      ),
+      paths: Capture::capture(paths),
+      toolchain: Capture::capture(paths),
+      features: Capture::capture(env),
+      session: Capture::capture(paths),
+      provenance: Capture::capture(&env.project.configuration),
+      assets: Capture::capture(paths),
+    }
+  }
+}
+
+impl Capture<ProjectPaths> for Vec<PathStatus> {
+  fn capture(paths: &ProjectPaths) -> Self {
+    [
+      ("project", &paths.project),
+      ("package", &paths.package),
+      ("repository", &paths.repository),
+      ("code", &paths.code),
+      ("assets", &paths.assets),
+      ("data", &paths.data),
+      ("database", &paths.database),
+      ("templates", &paths.templates),
+      ("documentation", &paths.documentation),
+      ("cache", &paths.cache),
+    ]
+    .into_iter()
+    .map(|(name, path)| PathStatus {
+      name,
+      path: path.clone(),
+      exists: path.exists(),
+    })
+    .collect()
+  }
+}
+
+impl Capture<ProjectPaths> for Toolchain {
+  /// Runs each tool in the workspace, so a `rust-toolchain.toml` applies.
+  fn capture(paths: &ProjectPaths) -> Self {
+    let version = |tool: &str| {
+      let output = Command::new(tool)
+        .arg("--version")
+        .current_dir(&paths.project)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+      Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+    Self {
+      rustc: version("rustc"),
+      cargo: version("cargo"),
+      git: version("git"),
+    }
+  }
+}
+
+impl Capture<Environment> for Features {
+  fn capture(_: &Environment) -> Self {
+    let features = [
+      ("derive", cfg!(feature = "derive")),
+      ("fmt", cfg!(feature = "fmt")),
+      ("json", cfg!(feature = "json")),
+      ("logfile", cfg!(feature = "logfile")),
+      ("macros", cfg!(feature = "macros")),
+      ("metadata", cfg!(feature = "metadata")),
+      ("serde", cfg!(feature = "serde")),
+      ("time", cfg!(feature = "time")),
+      ("tracing", cfg!(feature = "tracing")),
+      ("watch", cfg!(feature = "watch")),
+    ];
+    let (enabled, disabled): (Vec<_>, Vec<_>) =
+      features.into_iter().partition(|(_, enabled)| *enabled);
+    Self {
+      enabled: enabled.into_iter().map(|(name, _)| name).collect(),
+      disabled: disabled.into_iter().map(|(name, _)| name).collect(),
+    }
+  }
+}
+
+impl Capture<ProjectPaths> for Session {
+  /// Looks where `scripts/fetch-aoc.sh` does: `SESSION_KEY`, then
+  /// `{repository}/.env/session.key`.
+  fn capture(paths: &ProjectPaths) -> Self {
+    let found = Self::find(paths).map(|(source, key)| (source, key.expose().chars().count()));
+
+    let validated = metadata(Self::stamp_file(paths))
+      .and_then(|stamp| stamp.modified())
+      .ok();
+    found.map_or_else(Self::default, |(source, length)| Self {
+      source: Some(source),
+      length,
+      validated,
+    })
+  }
+}
+
+impl Session {
+  /// The session key, from where `scripts/fetch-aoc.sh` reads it, registered
+  /// with `register_secret()` so logs mask it.
+  #[must_use]
+  pub fn key(paths: &ProjectPaths) -> Option<Secret<String>> {
+    Self::find(paths).map(|(_, key)| key)
+  }
+
+  /// The session key and where it was found: `SESSION_KEY`, then
+  /// `{repository}/.env/session.key`.
+  fn find(paths: &ProjectPaths) -> Option<(String, Secret<String>)> {
+    let file = Self::key_file(paths);
+    let (source, key) = match var(SESSION_VARIABLE) {
+      Ok(key) => (
+        format!("environment variable {SESSION_VARIABLE}"),
+        Secret::new(key),
+      ),
+      Err(_) => (
+        file.display().to_string(),
+        Secret::new(read_to_string(&file).ok()?),
+      ),
+    };
+    let key = Secret::new(key.expose().trim().to_owned());
+    (!key.expose().is_empty()).then(|| (source, key.register()))
+  }
+
+  /// Where `scripts/fetch-aoc.sh` reads the key from when `SESSION_KEY` is
+  /// not set.
+  #[must_use]
+  pub fn key_file(paths: &ProjectPaths) -> PathBuf {
+    paths.repository.join(".env").join("session.key")
+  }
+
+  /// The file `scripts/fetch-aoc.sh` touches whenever the key works.
+  #[must_use]
+  pub fn stamp_file(paths: &ProjectPaths) -> PathBuf {
+    paths.cache.join("session.validated")
+  }
+}
+
+impl Capture<ProjectConfig> for Provenance {
+  fn capture(config: &ProjectConfig) -> Self {
+    Self {
+      values: config
+        .entries()
+        .into_iter()
+        .map(|(key, value, source)| (key, redact(&value).into_owned(), source.to_string()))
+        .collect(),
+    }
+  }
+}
+
+impl Capture<ProjectPaths> for Assets {
+  fn capture(paths: &ProjectPaths) -> Self {
+    let numbered = |directory: &Path| -> Vec<(String, PathBuf)> {
+      read_dir(directory)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+          let path = entry.ok()?.path();
+          let name = path.file_name()?.to_str()?.to_owned();
+          (path.is_dir() && name.bytes().all(|byte| byte.is_ascii_digit())).then_some((name, path))
+        })
+        .collect()
+    };
+
+    let years = numbered(&paths.data)
+      .into_iter()
+      .filter_map(|(year, directory)| {
+        let year = year.parse().ok()?;
+        let mut assets = YearAssets::default();
+        for (day, _) in numbered(&directory) {
+          let Ok(day) = day.parse() else { continue };
+          let puzzle = paths.puzzle(year, day);
+          assets.days += 1;
+          assets.inputs += usize::from(puzzle.input.is_file());
+          assets.instructions += usize::from(puzzle.instructions.is_file());
+          assets.answers += usize::from(puzzle.answers.is_file());
+        }
+        Some((year, assets))
+      })
+      .collect();
+    Self { years }
+  }
+}
+
+impl Snapshot {
+  /// The snapshot as a checklist, in report order.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// let env = Environment::new();
+  /// let snapshot = Snapshot::capture(&env);
+  /// let checks = snapshot.checks();
+  /// assert!(
+  ///   checks
+  ///     .iter()
+  ///     .any(|check| check.label == "path project" && check.status == CheckStatus::Pass)
+  /// );
+  /// assert!(
+  ///   checks
+  ///     .iter()
+  ///     .all(|check| (check.status == CheckStatus::Pass) == check.hint.is_none())
+  /// );
+  /// ```
+  #[must_use]
+  pub fn checks(&self) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    for status in &self.paths {
+      let required = matches!(status.name, "project" | "repository" | "data");
+      checks.push(if status.exists {
+        Check::pass(format!("path {}", status.name), status.path.display())
+      } else if required {
+        Check::fail(
+          format!("path {}", status.name),
+          format!("{} is missing", status.path.display()),
+          "Run from inside the repository, or set `WORKSPACE_ROOT` and `PROJECT_ROOT`.",
+        )
+      } else {
+        Check::warn(
+          format!("path {}", status.name),
+          format!("{} is missing", status.path.display()),
+          format!("Create it with `mkdir -p {}`.", status.path.display()),
+        )
+      });
+    }
+
+    for (tool, version, status) in [
+      ("rustc", &self.toolchain.rustc, Status::Fail),
+      ("cargo", &self.toolchain.cargo, Status::Fail),
+      ("git", &self.toolchain.git, Status::Warn),
+    ] {
+      let hint = if tool == "git" {
+        "Install git; without it the repository root is guessed from the layout."
+      } else {
+        "Install Rust with rustup: https://rustup.rs"
+      };
+      checks.push(version.as_ref().map_or_else(
+        || Check {
+          status,
+          label: tool.to_owned(),
+          detail: "not found".to_owned(),
+          hint: Some(hint.to_owned()),
+        },
+        |version| Check::pass(tool, version),
+      ));
+    }
+
+    checks.push(Check::pass("features", self.features.enabled.join(", ")));
+
+    checks.push(match (&self.session.source, self.session.validated) {
+      (None, _) => Check::fail(
+        "session key",
+        "not set",
+        format!(
+          "Save your adventofcode.com `session` cookie to `.env/session.key` in the repository, or set `{SESSION_VARIABLE}`."
+        ),
+      ),
+      (Some(source), None) => Check::warn(
+        "session key",
+        format!("set in {source} ({} characters), never validated", self.session.length),
+        "Fetch a puzzle with `scripts/fetch-aoc.sh` to check that the key works.",
+      ),
+      (Some(source), Some(validated)) => Check::pass(
+        "session key",
+        format!(
+          "set in {source} ({} characters), last validated {}",
+          self.session.length,
+          timestamp(validated)
+        ),
+      ),
+    });
+
+    for (key, value, source) in &self.provenance.values {
+      checks.push(Check::pass(
+        format!("config {key}"),
+        format!("{value:?} from {source}"),
+      ));
+    }
+
+    if self.assets.years.is_empty() {
+      checks.push(Check::warn(
+        "assets",
+        "no puzzle data",
+        "Fetch puzzles with `scripts/fetch-aoc.sh all`.",
+      ));
+    }
+    for (year, assets) in &self.assets.years {
+      let detail = format!(
+        "{} days, {} inputs, {} instructions, {} answers",
+        assets.days, assets.inputs, assets.instructions, assets.answers
+      );
+      checks.push(if assets.inputs < assets.days {
+        Check::warn(
+          format!("assets {year}"),
+          detail,
+          format!("Fetch the missing inputs with `scripts/fetch-aoc.sh {year} <day>`."),
+        )
+      } else {
+        Check::pass(format!("assets {year}"), detail)
+      });
+    }
+
+    checks
+  }
+
+  /// The snapshot as a TOML table, for bug reports.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// let snapshot = Snapshot::capture(&Environment::new());
+  /// let report = snapshot.to_toml();
+  /// assert!(report["paths"].get("project").is_some());
+  /// assert!(report["session"].get("token").is_none());
+  /// ```
+  #[must_use]
+  pub fn to_toml(&self) -> TomlTable {
+    let text = |text: &str| TomlValue::String(text.to_owned());
+    let table = |entries: Vec<(String, TomlValue)>| TomlValue::Table(entries.into_iter().collect());
+    let optional = |value: &Option<String>| value.as_deref().map_or_else(|| text("missing"), text);
+
+    let paths = self
+      .paths
+      .iter()
+      .map(|status| {
+        let entry = table(vec![
+          ("path".to_owned(), text(&status.path.display().to_string())),
+          ("exists".to_owned(), TomlValue::Boolean(status.exists)),
+        ]);
+        (status.name.to_owned(), entry)
+      })
+      .collect();
+    let toolchain = vec![
+      ("rustc".to_owned(), optional(&self.toolchain.rustc)),
+      ("cargo".to_owned(), optional(&self.toolchain.cargo)),
+      ("git".to_owned(), optional(&self.toolchain.git)),
+    ];
+    let features = |names: &[&str]| TomlValue::Array(names.iter().map(|name| text(name)).collect());
+    let mut session = vec![(
+      "set".to_owned(),
+      TomlValue::Boolean(self.session.source.is_some()),
+    )];
+    if let Some(source) = &self.session.source {
+      session.push(("source".to_owned(), text(source)));
+      session.push((
+        "length".to_owned(),
+        TomlValue::Integer(i64::try_from(self.session.length).unwrap_or(i64::MAX)),
+      ));
+    }
+    if let Some(validated) = self.session.validated {
+      session.push(("validated".to_owned(), text(&timestamp(validated))));
+    }
+    let configuration = self
+      .provenance
+      .values
+      .iter()
+      .map(|(key, value, source)| {
+        let entry = table(vec![
+          ("value".to_owned(), text(value)),
+          ("source".to_owned(), text(source)),
+        ]);
+        ((*key).to_owned(), entry)
+      })
+      .collect();
+    let count = |count: usize| TomlValue::Integer(i64::try_from(count).unwrap_or(i64::MAX));
+    let assets = self
+      .assets
+      .years
+      .iter()
+      .map(|(year, assets)| {
+        let entry = table(vec![
+          ("days".to_owned(), count(assets.days)),
+          ("inputs".to_owned(), count(assets.inputs)),
+          ("instructions".to_owned(), count(assets.instructions)),
+          ("answers".to_owned(), count(assets.answers)),
+        ]);
+        (year.to_string(), entry)
+      })
+      .collect();
+
+    [
+      ("package".to_owned(), text(&self.package)),
+      ("paths".to_owned(), table(paths)),
+      ("toolchain".to_owned(), table(toolchain)),
+      (
+        "features".to_owned(),
+        table(vec![
+          ("enabled".to_owned(), features(&self.features.enabled)),
+          ("disabled".to_owned(), features(&self.features.disabled)),
+        ]),
+      ),
+      ("session".to_owned(), table(session)),
+      ("configuration".to_owned(), table(configuration)),
+      ("assets".to_owned(), table(assets)),
+    ]
+    .into_iter()
+    .collect()
+  }
+}
+
+impl Check {
+  fn pass(label: impl Into<String>, detail: impl Display) -> Self {
+    Self {
+      status: Status::Pass,
+      label: label.into(),
+      detail: detail.to_string(),
+      hint: None,
+    }
+  }
+
+  fn warn(label: impl Into<String>, detail: impl Display, hint: impl Into<String>) -> Self {
+    Self {
+      status: Status::Warn,
+      hint: Some(hint.into()),
+      ..Self::pass(label, detail)
+    }
+  }
+
+  fn fail(label: impl Into<String>, detail: impl Display, hint: impl Into<String>) -> Self {
+    Self {
+      status: Status::Fail,
+      ..Self::warn(label, detail, hint)
+    }
+  }
+}
+
+impl Display for Status {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.pad(match self {
+      Self::Pass => "ok",
+      Self::Warn => "warn",
+      Self::Fail => "fail",
+    })
+  }
+}
+
+/// A point in time for the report: local RFC 3339 with the `time` feature,
+/// otherwise seconds since the Unix epoch.
+fn timestamp(time: StdSysTime) -> String {
+  cfg_if! {
+    if #[cfg(feature = "time")] {
+      DateTime::<Local>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+    } else {
+      let seconds = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+      format!("{seconds}s after the Unix epoch")
+    }
+  }
+}
@@ -1,5 +1,9 @@
 pub mod format;
+pub mod theme;
 
 pub mod prelude {
-  pub use super::format::*;
+  pub use super::{
+    format::*,
+    theme::*,
+  };
 }
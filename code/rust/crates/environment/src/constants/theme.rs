@@ -0,0 +1,264 @@
+//! Semantic terminal styling.
+//!
+//! A `Theme` maps what a piece of output means, its `Role`, to a style, so
+//! every command paints successes, stars and answers the same way. Whether
+//! to paint at all follows, in order:
+//!
+//! 1. `--color=always|never`, or `color` in the layered configuration
+//! 2. `NO_COLOR`: any non-empty value turns color off
+//! 3. `CLICOLOR_FORCE`: any value but `0` turns color on, even when piped
+//! 4. Whether stdout is a terminal that supports color
+//!
+//! The palette, 16 colors, 256 colors or truecolor, is detected from the
+//! terminal unless `palette` is configured.
+//!
+//! # Examples
+//! ```
+//! use env::prelude::*;
+//!
+//! let theme = Theme::new(ColorChoice::Always, Some(Palette::Ansi16));
+//! assert_eq!(theme.paint(Role::Success, "ok"), "\x1b[32mok\x1b[0m");
+//! assert_eq!(Theme::plain().paint(Role::Star, "*"), "*");
+//! ```
+
+use {
+  crate::prelude::*,
+  nu_ansi_term::Style,
+  std::env::var_os,
+  supports_color::ColorLevel,
+};
+
+/// Whether to paint output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "snake_case")
+)]
+pub enum ColorChoice {
+  /// Paint when stdout is a color terminal, honouring `NO_COLOR` and
+  /// `CLICOLOR_FORCE`.
+  #[default]
+  Auto,
+  /// Always paint.
+  Always,
+  /// Never paint.
+  Never,
+}
+
+/// The colors a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Palette {
+  /// The 16 basic ANSI colors.
+  #[cfg_attr(feature = "serde", serde(rename = "16"))]
+  Ansi16,
+  /// The 256-color xterm palette.
+  #[cfg_attr(feature = "serde", serde(rename = "256"))]
+  Ansi256,
+  /// 24-bit RGB.
+  #[cfg_attr(feature = "serde", serde(rename = "truecolor"))]
+  TrueColor,
+}
+
+/// What a piece of output means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+  /// Something worked.
+  Success,
+  /// Something failed.
+  Failure,
+  /// Something needs attention but did not fail.
+  Warning,
+  /// A puzzle answer.
+  Answer,
+  /// A collected star.
+  Star,
+  /// Secondary detail, such as hints and sources.
+  Dim,
+  /// A section title.
+  Heading,
+}
+
+/// Styles for each `Role`, or plain text when color is off.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Theme {
+  palette: Option<Palette>,
+}
+
+impl ColorChoice {
+  /// The palette to paint stdout with, or `None` for plain text.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// assert_eq!(ColorChoice::Never.resolve(), None);
+  /// assert!(ColorChoice::Always.resolve().is_some());
+  /// ```
+  #[must_use]
+  pub fn resolve(self) -> Option<Palette> {
+    let set = |variable: &str| var_os(variable).filter(|value| !value.is_empty());
+    let forced = match self {
+      Self::Never => return None,
+      Self::Always => true,
+      Self::Auto if set("NO_COLOR").is_some() => return None,
+      Self::Auto => set("CLICOLOR_FORCE").is_some_and(|value| value != "0"),
+    };
+    match supports_color::on(supports_color::Stream::Stdout) {
+      Some(level) => Some(Palette::from(level)),
+      None if forced => Some(Palette::from_env()),
+      None => None,
+    }
+  }
+}
+
+impl FromStr for ColorChoice {
+  type Err = String;
+
+  fn from_str(choice: &str) -> StdResult<Self, Self::Err> {
+    match choice.trim().to_ascii_lowercase().as_str() {
+      "auto" => Ok(Self::Auto),
+      "always" => Ok(Self::Always),
+      "never" => Ok(Self::Never),
+      other => Err(format!(
+        "unknown color choice `{other}`; expected auto, always or never"
+      )),
+    }
+  }
+}
+
+impl Display for ColorChoice {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.pad(match self {
+      Self::Auto => "auto",
+      Self::Always => "always",
+      Self::Never => "never",
+    })
+  }
+}
+
+impl Palette {
+  /// The palette `COLORTERM` and `TERM` advertise, for when color is forced
+  /// onto something that is not a terminal.
+  #[must_use]
+  pub fn from_env() -> Self {
+    let colorterm = var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+      Self::TrueColor
+    } else if var("TERM").is_ok_and(|term| term.contains("256")) {
+      Self::Ansi256
+    } else {
+      Self::Ansi16
+    }
+  }
+}
+
+impl From<ColorLevel> for Palette {
+  fn from(level: ColorLevel) -> Self {
+    if level.has_16m {
+      Self::TrueColor
+    } else if level.has_256 {
+      Self::Ansi256
+    } else {
+      Self::Ansi16
+    }
+  }
+}
+
+impl FromStr for Palette {
+  type Err = String;
+
+  fn from_str(palette: &str) -> StdResult<Self, Self::Err> {
+    match palette.trim().to_ascii_lowercase().as_str() {
+      "16" | "ansi" | "ansi16" => Ok(Self::Ansi16),
+      "256" | "ansi256" => Ok(Self::Ansi256),
+      "truecolor" | "24bit" | "rgb" => Ok(Self::TrueColor),
+      other => Err(format!(
+        "unknown palette `{other}`; expected 16, 256 or truecolor"
+      )),
+    }
+  }
+}
+
+impl Display for Palette {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.pad(match self {
+      Self::Ansi16 => "16",
+      Self::Ansi256 => "256",
+      Self::TrueColor => "truecolor",
+    })
+  }
+}
+
+impl Theme {
+  /// A theme that never paints.
+  #[must_use]
+  pub const fn plain() -> Self {
+    Self { palette: None }
+  }
+
+  /// A theme for `choice`, with `palette` or, if `None`, the terminal's.
+  #[must_use]
+  pub fn new(choice: ColorChoice, palette: Option<Palette>) -> Self {
+    Self {
+      palette: choice.resolve().map(|detected| palette.unwrap_or(detected)),
+    }
+  }
+
+  /// The palette in use, or `None` if output is plain.
+  #[must_use]
+  pub const fn palette(&self) -> Option<Palette> {
+    self.palette
+  }
+
+  /// Whether this theme paints at all.
+  #[must_use]
+  pub const fn is_enabled(&self) -> bool {
+    self.palette.is_some()
+  }
+
+  /// The style for `role`; the default style when color is off.
+  ///
+  /// # Examples
+  /// ```
+  /// use env::prelude::*;
+  ///
+  /// let theme = Theme::new(ColorChoice::Always, Some(Palette::TrueColor));
+  /// assert_eq!(
+  ///   theme.style(Role::Star).foreground,
+  ///   Some(Color::Rgb(255, 255, 102))
+  /// );
+  /// ```
+  #[must_use]
+  pub fn style(&self, role: Role) -> Style {
+    let Some(palette) = self.palette else {
+      return Style::new();
+    };
+    let (basic, fixed, rgb) = match role {
+      Role::Success => (Green, 40, (0, 204, 0)),
+      Role::Failure => (Red, 160, (255, 68, 68)),
+      Role::Warning => (Yellow, 214, (255, 170, 0)),
+      Role::Answer => (White, 231, (255, 255, 255)),
+      Role::Star => (Yellow, 227, (255, 255, 102)),
+      Role::Dim => (DarkGray, 242, (102, 102, 102)),
+      Role::Heading => (Blue, 75, (95, 175, 255)),
+    };
+    let color = match palette {
+      Palette::Ansi16 => basic,
+      Palette::Ansi256 => Color::Fixed(fixed),
+      Palette::TrueColor => Color::Rgb(rgb.0, rgb.1, rgb.2),
+    };
+    let style = color.normal();
+    match role {
+      Role::Failure | Role::Answer | Role::Star | Role::Heading => style.bold(),
+      Role::Dim => style.dimmed(),
+      Role::Success | Role::Warning => style,
+    }
+  }
+
+  /// `text` styled for `role`.
+  pub fn paint(&self, role: Role, text: impl Display) -> String {
+    self.style(role).paint(text.to_string()).to_string()
+  }
+}
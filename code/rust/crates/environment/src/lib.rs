@@ -1,6 +1,11 @@
 #[macro_use]
 pub mod config;
 pub mod constants;
+#[cfg(feature = "fmt")]
+pub mod logline;
+#[cfg(feature = "macros")]
+pub mod memo;
 pub mod prelude;
+pub mod secret;
 pub mod time;
 pub mod traits;
@@ -0,0 +1,285 @@
+//! Logging setup for binaries built on `env`.
+//!
+//! `Logline` assembles a `tracing` subscriber from the pieces this crate
+//! already has: the filter comes from `RUST_LOG` or the project
+//! configuration, timestamps from the chrono helpers, and colour from the
+//! configured `Theme`, so `--color`, `NO_COLOR` and `CLICOLOR_FORCE` apply to
+//! logs as they do to everything else. Output goes to stderr, so it never mixes
+//! with answers printed on stdout, and every output masks registered
+//! secrets through `Redacting`.
+//!
+//! # Features
+//! - `fmt` - compact and pretty output (enables this module)
+//! - `json` - JSON output, one object per line
+//! - `logfile` - a rolling log file, by default under `{project}/target/logs`
+//! - `time` - local and UTC timestamps through chrono; without it both
+//!   clocks print the system time in UTC
+//!
+//! # Examples
+//!
+//! The defaults, with the filter from the global environment:
+//! ```no_run
+//! use env::prelude::*;
+//!
+//! logline::init();
+//! info!("ready");
+//! ```
+//!
+//! Everything spelled out:
+//! ```no_run
+//! use env::prelude::*;
+//!
+//! Logline::new()
+//!   .with_filter("cli=debug,env=info")
+//!   .with_format(LogFormat::Pretty)
+//!   .with_clock(LogClock::Utc)
+//!   .with_color(false)
+//!   .try_init()?;
+//! # Ok::<(), EnvError>(())
+//! ```
+
+#[cfg(feature = "logfile")]
+pub use tracing_appender::rolling::Rotation;
+use {
+  crate::{
+    config::_prelude::*,
+    constants::theme::ColorChoice,
+    secret::Redacting,
+  },
+  tracing::Subscriber,
+  tracing_subscriber::{
+    EnvFilter,
+    Layer,
+    fmt::{
+      MakeWriter,
+      time::FormatTime,
+    },
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+  },
+};
+
+/// Filter used when neither the builder, the configuration nor `RUST_LOG`
+/// provides one.
+pub const DEFAULT_FILTER: &str = "info";
+
+/// A type-erased formatting layer.
+type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+/// Installs the default logger, with the filter from the global
+/// environment's configuration.
+///
+/// Failures, such as a logger already being installed, are reported on
+/// stderr rather than returned; use `Logline::try_init` to handle them.
+pub fn init() {
+  Logline::from_env(get_env()).init();
+}
+
+/// How each event is laid out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+  /// One short line per event.
+  #[default]
+  Compact,
+  /// Several indented lines per event, with source locations.
+  Pretty,
+  /// One JSON object per line, for machines.
+  #[cfg(feature = "json")]
+  Json,
+}
+
+/// Which clock timestamps are read from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Clock {
+  /// Local time, RFC 3339.
+  #[default]
+  Local,
+  /// UTC, RFC 3339.
+  Utc,
+  /// No timestamps at all.
+  Off,
+}
+
+/// Builder for the process-wide logger.
+///
+/// # Filter Resolution
+/// 1. `with_filter()` or the configuration passed to `from_env()`
+/// 2. the `RUST_LOG` environment variable
+/// 3. `DEFAULT_FILTER`
+#[derive(Debug, Default, Clone)]
+pub struct Logline {
+  filter: Option<String>,
+  format: Format,
+  clock: Clock,
+  color: Option<bool>,
+  #[cfg(feature = "logfile")]
+  rotation: Option<Rotation>,
+  #[cfg(feature = "logfile")]
+  directory: Option<PathBuf>,
+}
+
+impl Logline {
+  /// A logger with default settings.
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// A logger using the environment's `rust_log` and `color` configuration
+  /// and, with the `logfile` feature, its project directory for log files.
+  #[must_use]
+  pub fn from_env(env: &Environment) -> Self {
+    let logline = Self::new().with_color(env.project.configuration.theme().is_enabled());
+    #[cfg(feature = "logfile")]
+    let logline = logline.with_directory(env.project.paths.project.join("target").join("logs"));
+    match env.project.configuration.rust_log.as_str() {
+      "" => logline,
+      filter => logline.with_filter(filter),
+    }
+  }
+
+  /// Sets the filter directives, in `RUST_LOG` syntax.
+  #[must_use]
+  pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+    self.filter = Some(filter.into());
+    self
+  }
+
+  /// Sets the event layout.
+  #[must_use]
+  pub const fn with_format(mut self, format: Format) -> Self {
+    self.format = format;
+    self
+  }
+
+  /// Sets the timestamp clock.
+  #[must_use]
+  pub const fn with_clock(mut self, clock: Clock) -> Self {
+    self.clock = clock;
+    self
+  }
+
+  /// Forces colour on or off instead of following `ColorChoice::Auto`.
+  #[must_use]
+  pub const fn with_color(mut self, color: bool) -> Self {
+    self.color = Some(color);
+    self
+  }
+
+  /// Also writes every event, without colour, to a log file rotated on
+  /// `rotation` and named after the running package.
+  #[cfg(feature = "logfile")]
+  #[must_use]
+  pub const fn with_file(mut self, rotation: Rotation) -> Self {
+    self.rotation = Some(rotation);
+    self
+  }
+
+  /// Sets the directory log files are written to.
+  ///
+  /// Defaults to `{project}/target/logs`, which is already ignored by git.
+  #[cfg(feature = "logfile")]
+  #[must_use]
+  pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+    self.directory = Some(directory.into());
+    self
+  }
+
+  /// The filter directives that will be used.
+  #[must_use]
+  pub fn filter(&self) -> String {
+    self
+      .filter
+      .clone()
+      .or_else(|| var("RUST_LOG").ok().filter(|filter| !filter.is_empty()))
+      .unwrap_or_else(|| String::from(DEFAULT_FILTER))
+  }
+
+  /// Installs the logger, reporting failures on stderr.
+  pub fn init(self) {
+    if let Err(error) = self.try_init() {
+      eprintln!("{error}");
+    }
+  }
+
+  /// Installs the logger.
+  ///
+  /// # Errors
+  /// If the filter directives are invalid, the log file cannot be created,
+  /// or a global logger is already installed.
+  pub fn try_init(self) -> Result<()> {
+    let filter =
+      EnvFilter::try_new(self.filter()).map_err(|error| Error::Logging(error.to_string()))?;
+    let color = self
+      .color
+      .unwrap_or_else(|| ColorChoice::Auto.resolve().is_some());
+
+    #[allow(unused_mut)]
+    let mut layers = vec![self.layer(color, Redacting(std::io::stderr))];
+    #[cfg(feature = "logfile")]
+    if let Some(rotation) = self.rotation.clone() {
+      layers.push(self.layer(false, Redacting(self.appender(rotation)?)));
+    }
+
+    tracing_subscriber::registry()
+      .with(filter)
+      .with(layers)
+      .try_init()
+      .map_err(|error| Error::Logging(error.to_string()))
+  }
+
+  /// Opens the rolling log file.
+  #[cfg(feature = "logfile")]
+  fn appender(&self, rotation: Rotation) -> Result<tracing_appender::rolling::RollingFileAppender> {
+    let env = get_env();
+    let directory = self
+      .directory
+      .clone()
+      .unwrap_or_else(|| env.project.paths.project.join("target").join("logs"));
+    tracing_appender::rolling::RollingFileAppender::builder()
+      .rotation(rotation)
+      .filename_prefix(&env.package.metadata.name)
+      .filename_suffix("log")
+      .build(directory)
+      .map_err(|error| Error::Logging(error.to_string()))
+  }
+
+  /// A formatting layer writing to `writer`, with the configured clock.
+  fn layer<S, W>(&self, color: bool, writer: W) -> BoxedLayer<S>
+  where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+  {
+    match self.clock {
+      #[cfg(feature = "time")]
+      Clock::Local => self.timed(color, writer, TracingLocal::rfc_3339()),
+      #[cfg(feature = "time")]
+      Clock::Utc => self.timed(color, writer, TracingUtc::rfc_3339()),
+      #[cfg(not(feature = "time"))]
+      Clock::Local | Clock::Utc => {
+        self.timed(color, writer, tracing_subscriber::fmt::time::SystemTime)
+      }
+      Clock::Off => self.timed(color, writer, ()),
+    }
+  }
+
+  /// A formatting layer with a given timer, in the configured format.
+  fn timed<S, W, T>(&self, color: bool, writer: W, timer: T) -> BoxedLayer<S>
+  where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    T: FormatTime + Send + Sync + 'static,
+  {
+    let layer = tracing_subscriber::fmt::layer()
+      .with_writer(writer)
+      .with_ansi(color)
+      .with_timer(timer);
+    match self.format {
+      Format::Compact => layer.compact().boxed(),
+      Format::Pretty => layer.pretty().boxed(),
+      #[cfg(feature = "json")]
+      Format::Json => layer.json().boxed(),
+    }
+  }
+}
@@ -0,0 +1,204 @@
+//! Memo tables for recursive counting puzzles.
+//!
+//! Puzzles like 2023/12's springs, 2021/21's Dirac dice and 2024/11's
+//! pebbles recurse over the same small states again and again. Rather
+//! than threading a `HashMap` through every call by hand, mark the
+//! function with `#[memoize]`: it keeps a `Memo` table next to the
+//! function, looks every call up in it first, and stores what the body
+//! returns.
+//!
+//! # Options
+//!
+//! - `key = <expr>` - what the table is keyed by; any expression over the
+//!   arguments yielding an owned `Eq + Hash` value. Defaults to the tuple
+//!   of all arguments, with borrowed ones converted by `ToOwned`. Use it to
+//!   leave out a borrowed context that never changes within a run.
+//! - `scope = thread` (default) - one table per thread, kept between calls
+//!   until `clear_<name>()` is called, e.g. between part 1 and part 2.
+//! - `scope = call` - a fresh table for every outermost call, shared by its
+//!   recursive calls and dropped when it returns.
+//!
+//! The function must be a free function with named arguments and no type
+//! parameters, and it must return a `Clone` value.
+//!
+//! # Examples
+//!
+//! 2024/11's pebbles, keyed by the tuple of arguments:
+//!
+//! ```
+//! use env::prelude::*;
+//!
+//! #[memoize]
+//! fn pebbles(stone: u64, blinks: u8) -> u64 {
+//!   if blinks == 0 {
+//!     return 1;
+//!   }
+//!   let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+//!   if stone == 0 {
+//!     pebbles(1, blinks - 1)
+//!   } else if digits % 2 == 0 {
+//!     let half = 10u64.pow(digits / 2);
+//!     pebbles(stone / half, blinks - 1) + pebbles(stone % half, blinks - 1)
+//!   } else {
+//!     pebbles(stone * 2024, blinks - 1)
+//!   }
+//! }
+//!
+//! assert_eq!(
+//!   [125, 17]
+//!     .map(|stone| pebbles(stone, 25))
+//!     .iter()
+//!     .sum::<u64>(),
+//!   55_312
+//! );
+//! clear_pebbles();
+//! ```
+//!
+//! 2023/12's springs, keyed by how much of the row and the groups is left,
+//! with a fresh table for every row:
+//!
+//! ```
+//! use env::prelude::*;
+//!
+//! #[memoize(key = (springs.len(), groups.len()), scope = call)]
+//! fn arrangements(springs: &[u8], groups: &[usize]) -> u64 {
+//!   let Some((&group, rest)) = groups.split_first() else {
+//!     return u64::from(!springs.contains(&b'#'));
+//!   };
+//!   let mut total = 0;
+//!   for start in 0..springs.len() {
+//!     let end = start + group;
+//!     if end > springs.len() {
+//!       break;
+//!     }
+//!     let fits = !springs[start..end].contains(&b'.') && springs.get(end) != Some(&b'#');
+//!     if fits {
+//!       total += arrangements(springs.get(end + 1..).unwrap_or_default(), rest);
+//!     }
+//!     if springs[start] == b'#' {
+//!       break;
+//!     }
+//!   }
+//!   total
+//! }
+//!
+//! assert_eq!(arrangements(b"???.###", &[1, 1, 3]), 1);
+//! assert_eq!(arrangements(b".??..??...?##.", &[1, 1, 3]), 4);
+//! assert_eq!(arrangements(b"?###????????", &[3, 2, 1]), 10);
+//! ```
+//!
+//! Recursive calls go through the cache whatever the function is called,
+//! so every value below is computed once:
+//!
+//! ```
+//! use {
+//!   env::prelude::*,
+//!   std::cell::Cell,
+//! };
+//!
+//! thread_local! {
+//!   static CALLS: Cell<u32> = const { Cell::new(0) };
+//! }
+//!
+//! #[memoize]
+//! fn compute(n: u64) -> u64 {
+//!   CALLS.with(|calls| calls.set(calls.get() + 1));
+//!   if n < 2 { n } else { compute(n - 1) + compute(n - 2) }
+//! }
+//!
+//! assert_eq!(compute(80), 23_416_728_348_467_685);
+//! assert_eq!(CALLS.with(Cell::get), 81);
+//! ```
+
+use std::{
+  any::Any,
+  cell::{
+    Cell,
+    RefCell,
+  },
+  collections::HashMap,
+  hash::Hash,
+  thread::LocalKey,
+};
+
+/// A type-erased memo table, one per memoized function and thread.
+///
+/// The table is created on first insertion with whatever key and value
+/// types the function uses, so the `static` holding it needs no type
+/// parameters.
+#[derive(Debug, Default)]
+pub struct Memo {
+  table: RefCell<Option<Box<dyn Any>>>,
+  depth: Cell<usize>,
+}
+
+impl Memo {
+  /// An empty table.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      table: RefCell::new(None),
+      depth: Cell::new(0),
+    }
+  }
+
+  /// The value cached for `key`, if any.
+  #[must_use]
+  pub fn get<K, V>(&self, key: &K) -> Option<V>
+  where
+    K: Eq + Hash + 'static,
+    V: Clone + 'static,
+  {
+    self
+      .table
+      .borrow()
+      .as_ref()?
+      .downcast_ref::<HashMap<K, V>>()?
+      .get(key)
+      .cloned()
+  }
+
+  /// Caches `value` for `key`.
+  pub fn insert<K, V>(&self, key: K, value: V)
+  where
+    K: Eq + Hash + 'static,
+    V: 'static,
+  {
+    let mut table = self.table.borrow_mut();
+    let table = table.get_or_insert_with(|| Box::new(HashMap::<K, V>::new()));
+    if let Some(table) = table.downcast_mut::<HashMap<K, V>>() {
+      table.insert(key, value);
+    }
+  }
+
+  /// Forgets every cached value.
+  pub fn clear(&self) {
+    self.table.borrow_mut().take();
+  }
+
+  /// Opens a call tree on a thread-local table; the table is cleared when
+  /// the outermost returned `Scope` is dropped.
+  #[must_use]
+  pub fn enter(memo: &'static LocalKey<Self>) -> Scope {
+    memo.with(|memo| memo.depth.set(memo.depth.get() + 1));
+    Scope { memo }
+  }
+}
+
+/// An open call tree of a `scope = call` memoized function.
+#[derive(Debug)]
+pub struct Scope {
+  memo: &'static LocalKey<Memo>,
+}
+
+impl Drop for Scope {
+  fn drop(&mut self) {
+    self.memo.with(|memo| {
+      let depth = memo.depth.get() - 1;
+      memo.depth.set(depth);
+      if depth == 0 {
+        memo.clear();
+      }
+    });
+  }
+}
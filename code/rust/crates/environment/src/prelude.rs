@@ -1,7 +1,25 @@
+#[cfg(feature = "logfile")]
+pub use crate::logline::Rotation as LogRotation;
+#[cfg(feature = "fmt")]
+pub use crate::logline::{
+  self,
+  Clock as LogClock,
+  Format as LogFormat,
+  Logline,
+};
+#[cfg(feature = "tracing")]
+pub use tracing::{
+  debug,
+  error,
+  info,
+  trace,
+  warn,
+};
 pub use {
   crate::{
     config::prelude::*,
     constants::prelude::*,
+    secret::*,
     time::prelude::*,
     traits::*,
   },
@@ -50,3 +68,11 @@ pub use {
   },
   thiserror::Error as ThisError,
 };
+#[cfg(feature = "macros")]
+pub use {
+  crate::{
+    getenv,
+    setenv,
+  },
+  env_macros::memoize,
+};
@@ -0,0 +1,134 @@
+//! Human-readable durations.
+//!
+//! `Elapsed` wraps a `std::time::Duration` and prints it in the largest unit
+//! that keeps it above one: `850 ns`, `12.40 µs`, `1.23 ms` or `2.05 s`.
+//! Nanoseconds are whole; other units have two decimals unless the format
+//! string asks for a precision. Width, fill and alignment apply to the
+//! whole text, so timings line up in tables. It serializes as whole
+//! nanoseconds, so timings can be stored in TOML or JSON and compared later.
+//!
+//! # Examples
+//! ```
+//! use env::prelude::*;
+//!
+//! let elapsed = Elapsed::from(StdDuration::from_micros(1234));
+//! assert_eq!(elapsed.to_string(), "1.23 ms");
+//! assert_eq!(format!("{elapsed:.3}"), "1.234 ms");
+//! assert_eq!(format!("[{elapsed:>9}]"), "[  1.23 ms]");
+//! assert_eq!(format!("[{elapsed:*<9.1}]"), "[1.2 ms***]");
+//! assert_eq!(format_duration(StdDuration::from_nanos(850)), "850 ns");
+//! ```
+
+use {
+  super::_prelude::*,
+  serde::{
+    Deserialize,
+    Serialize,
+  },
+  std::fmt::{
+    Alignment,
+    Write,
+  },
+};
+
+/// A measured duration that prints in adaptive units.
+#[derive(
+  Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(from = "u64", into = "u64")]
+pub struct Elapsed(pub StdDuration);
+
+/// `duration` in adaptive units with two decimals, such as `1.23 ms`.
+#[must_use]
+pub fn format_duration(duration: StdDuration) -> String {
+  Elapsed(duration).to_string()
+}
+
+impl Elapsed {
+  /// The duration of `nanos` nanoseconds.
+  #[must_use]
+  pub const fn from_nanos(nanos: u64) -> Self {
+    Self(StdDuration::from_nanos(nanos))
+  }
+
+  /// The wrapped duration.
+  #[must_use]
+  pub const fn as_duration(self) -> StdDuration {
+    self.0
+  }
+
+  /// Whole nanoseconds, saturating after about 584 years.
+  #[must_use]
+  pub fn as_nanos(self) -> u64 {
+    u64::try_from(self.0.as_nanos()).unwrap_or(u64::MAX)
+  }
+}
+
+impl From<StdDuration> for Elapsed {
+  fn from(duration: StdDuration) -> Self {
+    Self(duration)
+  }
+}
+
+impl From<Elapsed> for StdDuration {
+  fn from(elapsed: Elapsed) -> Self {
+    elapsed.0
+  }
+}
+
+impl From<u64> for Elapsed {
+  fn from(nanos: u64) -> Self {
+    Self::from_nanos(nanos)
+  }
+}
+
+impl From<Elapsed> for u64 {
+  fn from(elapsed: Elapsed) -> Self {
+    elapsed.as_nanos()
+  }
+}
+
+impl Display for Elapsed {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let nanos = self.0.as_nanos();
+    if nanos < 1_000 {
+      return pad(f, &format!("{nanos} ns"));
+    }
+    // Pick the unit after rounding, so 999.999 µs prints as 1.00 ms.
+    let precision = f.precision().unwrap_or(2);
+    let rounding = 10_f64.powi(i32::try_from(precision).unwrap_or(i32::MAX));
+    #[allow(clippy::cast_precision_loss)] // Exact to well below the printed precision.
+    let nanos = nanos as f64;
+    let (value, unit) = [(1e3, "µs"), (1e6, "ms")]
+      .into_iter()
+      .map(|(scale, unit)| (nanos / scale, unit))
+      .find(|(value, _)| (value * rounding).round() / rounding < 1e3)
+      .unwrap_or((nanos / 1e9, "s"));
+    pad(f, &format!("{value:.precision$} {unit}"))
+  }
+}
+
+/// Writes `text` with the formatter's width, fill and alignment.
+///
+/// `Formatter::pad` would also truncate `text` to the precision, which here
+/// counts decimals instead.
+fn pad(f: &mut Formatter<'_>, text: &str) -> FmtResult {
+  let padding = f
+    .width()
+    .unwrap_or(0)
+    .saturating_sub(text.chars().count());
+  let (before, after) = match f.align() {
+    Some(Alignment::Right) => (padding, 0),
+    Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+    Some(Alignment::Left) | None => (0, padding),
+  };
+  let fill = f.fill();
+  for _ in 0..before {
+    f.write_char(fill)?;
+  }
+  f.write_str(text)?;
+  for _ in 0..after {
+    f.write_char(fill)?;
+  }
+  Ok(())
+}
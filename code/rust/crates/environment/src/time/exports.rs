@@ -1,4 +1,9 @@
 pub mod external {
+  #[cfg(feature = "time")]
+  pub use super::super::{
+    duration::*,
+    stopwatch::*,
+  };
   #[cfg(feature = "time")]
   pub use chrono::{
     DateTime,
@@ -16,6 +21,7 @@ pub mod external {
     super::super::utils::*,
     std::time::{
       Duration as StdDuration,
+      Instant,
       SystemTime as StdSysTime,
     },
   };
@@ -27,6 +33,7 @@ pub mod internal {
     chrono::prelude::*,
     std::time::{
       Duration as StdDuration,
+      Instant,
       SystemTime as StdSysTime,
     },
   };
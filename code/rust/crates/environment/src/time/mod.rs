@@ -1,3 +1,7 @@
+#[cfg(feature = "time")]
+pub mod duration;
+#[cfg(feature = "time")]
+pub mod stopwatch;
 pub mod utils;
 
 mod exports;
@@ -0,0 +1,190 @@
+//! Measuring elapsed time.
+//!
+//! A `Stopwatch` measures from when it starts and records named laps, such
+//! as parsing and each part of a puzzle. A `TimingGuard` times a scope and
+//! reports it to `tracing` when dropped.
+//!
+//! # Examples
+//! ```
+//! use env::prelude::*;
+//!
+//! let mut stopwatch = Stopwatch::start();
+//! let input = "1\n2\n3";
+//! let numbers: Vec<u32> = input.lines().map(|line| line.parse().unwrap()).collect();
+//! stopwatch.lap("parse");
+//! let sum: u32 = numbers.iter().sum();
+//! stopwatch.lap("part one");
+//!
+//! assert_eq!(sum, 6);
+//! for lap in stopwatch.laps() {
+//!   println!("{:>10}  {}", lap.label, lap.split);
+//! }
+//! assert!(stopwatch.laps()[1].total <= stopwatch.elapsed());
+//! ```
+
+use super::_prelude::*;
+
+/// Measures time since it started, with named laps.
+#[derive(Debug, Clone)]
+pub struct Stopwatch {
+  started: Instant,
+  last: Instant,
+  laps: Vec<Lap>,
+}
+
+/// One recorded lap of a `Stopwatch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lap {
+  /// What was measured.
+  pub label: String,
+  /// Time since the previous lap, or since the start for the first.
+  pub split: Elapsed,
+  /// Time since the start.
+  pub total: Elapsed,
+}
+
+/// Times the scope it lives in and emits a `tracing` event when dropped.
+///
+/// The guard owns a `timing` span carrying the `label`; enter it to nest
+/// the events of the timed work under it. The event is emitted inside that
+/// span, with the `elapsed` time in adaptive units and `nanos` for machine
+/// consumers.
+///
+/// # Examples
+/// ```
+/// use env::prelude::*;
+///
+/// fn solve(input: &str) -> usize {
+///   let timing = TimingGuard::new("part one");
+///   let _entered = timing.span().enter();
+///   input.lines().count()
+/// }
+///
+/// assert_eq!(solve("a\nb"), 2);
+/// ```
+#[cfg(feature = "tracing")]
+#[derive(Debug)]
+pub struct TimingGuard {
+  label: Cow<'static, str>,
+  level: tracing::Level,
+  span: tracing::Span,
+  started: Instant,
+}
+
+impl Stopwatch {
+  /// A stopwatch started now.
+  #[must_use]
+  pub fn start() -> Self {
+    let now = Instant::now();
+    Self {
+      started: now,
+      last: now,
+      laps: Vec::new(),
+    }
+  }
+
+  /// Time since the start.
+  #[must_use]
+  pub fn elapsed(&self) -> Elapsed {
+    self.started.elapsed().into()
+  }
+
+  /// Records a lap and returns its split, the time since the previous lap.
+  pub fn lap(&mut self, label: impl Into<String>) -> Elapsed {
+    let now = Instant::now();
+    let split = Elapsed(now - self.last);
+    self.last = now;
+    self.laps.push(Lap {
+      label: label.into(),
+      split,
+      total: Elapsed(now - self.started),
+    });
+    split
+  }
+
+  /// The laps recorded so far, oldest first.
+  #[must_use]
+  pub fn laps(&self) -> &[Lap] {
+    &self.laps
+  }
+
+  /// Starts again from now, forgetting every lap.
+  pub fn restart(&mut self) {
+    *self = Self::start();
+  }
+}
+
+impl Default for Stopwatch {
+  fn default() -> Self {
+    Self::start()
+  }
+}
+
+#[cfg(feature = "tracing")]
+impl TimingGuard {
+  /// Starts timing `label`, reporting at `INFO`.
+  #[must_use]
+  pub fn new(label: impl Into<Cow<'static, str>>) -> Self {
+    Self::at(tracing::Level::INFO, label)
+  }
+
+  /// Starts timing `label`, with the span and event at `level`.
+  #[must_use]
+  pub fn at(level: tracing::Level, label: impl Into<Cow<'static, str>>) -> Self {
+    use tracing::{
+      Level,
+      debug_span,
+      error_span,
+      info_span,
+      trace_span,
+      warn_span,
+    };
+
+    let label = label.into();
+    let span = match level {
+      Level::ERROR => error_span!("timing", label = %label),
+      Level::WARN => warn_span!("timing", label = %label),
+      Level::INFO => info_span!("timing", label = %label),
+      Level::DEBUG => debug_span!("timing", label = %label),
+      Level::TRACE => trace_span!("timing", label = %label),
+    };
+    Self {
+      label,
+      level,
+      span,
+      started: Instant::now(),
+    }
+  }
+
+  /// The span that lasts as long as this guard.
+  #[must_use]
+  pub const fn span(&self) -> &tracing::Span {
+    &self.span
+  }
+
+  /// Time since the guard was created.
+  #[must_use]
+  pub fn elapsed(&self) -> Elapsed {
+    self.started.elapsed().into()
+  }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for TimingGuard {
+  fn drop(&mut self) {
+    use tracing::{
+      Level,
+      event,
+    };
+
+    let elapsed = self.elapsed();
+    let (label, nanos) = (&self.label, elapsed.as_nanos());
+    self.span.in_scope(|| match self.level {
+      Level::ERROR => event!(Level::ERROR, %elapsed, nanos, "{label} took {elapsed}"),
+      Level::WARN => event!(Level::WARN, %elapsed, nanos, "{label} took {elapsed}"),
+      Level::INFO => event!(Level::INFO, %elapsed, nanos, "{label} took {elapsed}"),
+      Level::DEBUG => event!(Level::DEBUG, %elapsed, nanos, "{label} took {elapsed}"),
+      Level::TRACE => event!(Level::TRACE, %elapsed, nanos, "{label} took {elapsed}"),
+    });
+  }
+}
@@ -1,3 +1,17 @@
+/// Builds a value by taking a snapshot of `T`.
+///
+/// Used for read-only reports such as `Snapshot`, where each part records
+/// the state of one piece of the environment at the time it was captured.
+///
+/// # Examples
+/// ```
+/// use env::prelude::*;
+///
+/// let paths = ProjectPaths::from_roots("/aoc/code/rust", "/aoc");
+/// let statuses: Vec<PathStatus> = Capture::capture(&paths);
+/// assert!(statuses.iter().any(|status| status.name == "data"));
+/// ```
 pub trait Capture<T> {
+  /// Takes the snapshot.
   fn capture(source: &T) -> Self;
 }
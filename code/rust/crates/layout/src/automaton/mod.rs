@@ -0,0 +1,387 @@
+//! Cellular automata over arbitrary topologies.
+//!
+//! An `Automaton` is assembled from three independent parts:
+//!
+//! - a `Storage` holding the cell states: `Dense` for bounded boxes,
+//!   `Sparse` for unbounded spaces of any cell type;
+//! - a `Neighbourhood` naming the neighbours of a cell: `Offsets` for the
+//!   usual Moore, von Neumann and hexagonal ones, or any closure;
+//! - a `Rule` computing the next state of a cell from its `Site`.
+//!
+//! Every generation is computed into a second buffer, so rules always see
+//! the previous generation in full.
+//!
+//! # Examples
+//!
+//! 2015/18's lights are Conway's game of life on a bounded grid:
+//!
+//! ```
+//! use grid::prelude::*;
+//!
+//! let lights = Dense::parse(".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..", |c| {
+//!   c == '#'
+//! });
+//! let life = |site: &Site<'_, [i64; 2], bool>| {
+//!   matches!((site.state, site.live()), (true, 2 | 3) | (false, 3))
+//! };
+//!
+//! let mut lights = Automaton::new(lights, Offsets::moore(), life);
+//! lights.run(4);
+//! assert_eq!(lights.population(), 4);
+//! assert_eq!(
+//!   lights.storage().render(|on| if on { '#' } else { '.' }),
+//!   "......\n......\n..##..\n..##..\n......\n......\n"
+//! );
+//! ```
+//!
+//! 2020/17's cubes live in an unbounded three-dimensional space:
+//!
+//! ```
+//! use grid::prelude::*;
+//!
+//! let slice = Dense::parse(".#.\n..#\n###", |c| c == '#');
+//! let cubes = slice
+//!   .live()
+//!   .map(|([x, y], on)| ([x, y, 0], on))
+//!   .collect::<Sparse<_, _>>();
+//! let rule = |site: &Site<'_, [i64; 3], bool>| {
+//!   matches!((site.state, site.live()), (true, 2 | 3) | (false, 3))
+//! };
+//!
+//! let mut cubes = Automaton::new(cubes, Offsets::moore(), rule);
+//! cubes.run(6);
+//! assert_eq!(cubes.population(), 112);
+//! ```
+
+mod neighbourhood;
+mod storage;
+
+use crate::_prelude::*;
+pub use {
+  neighbourhood::*,
+  storage::*,
+};
+
+/// Everything a rule may look at to decide a cell's next state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Site<'a, C, S> {
+  /// The cell being updated.
+  pub cell: C,
+  /// Its current state.
+  pub state: S,
+  /// Current states of its neighbours, in neighbourhood order.
+  pub neighbours: &'a [S],
+  /// Number of generations computed so far.
+  pub generation: usize,
+}
+
+impl<C, S: Copy + Default + PartialEq> Site<'_, C, S> {
+  /// Number of neighbours in `state`.
+  #[must_use]
+  pub fn count(&self, state: S) -> usize {
+    self.neighbours.iter().filter(|&&s| s == state).count()
+  }
+
+  /// Number of neighbours not in the default state.
+  #[must_use]
+  pub fn live(&self) -> usize {
+    self
+      .neighbours
+      .iter()
+      .filter(|&&s| s != S::default())
+      .count()
+  }
+}
+
+/// Computes the next state of a cell.
+pub trait Rule<C, S> {
+  /// The state of `site.cell` in the next generation.
+  fn next(&self, site: &Site<'_, C, S>) -> S;
+}
+
+impl<C, S, F> Rule<C, S> for F
+where
+  F: Fn(&Site<'_, C, S>) -> S,
+{
+  fn next(&self, site: &Site<'_, C, S>) -> S {
+    self(site)
+  }
+}
+
+/// A storage, neighbourhood and rule, stepped one generation at a time.
+///
+/// # Examples
+///
+/// 2020/11's seats look along eight lines of sight, skipping the floor;
+/// the neighbourhood is a closure over the seat layout:
+///
+/// ```
+/// use grid::prelude::*;
+///
+/// #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// enum Seat {
+///   #[default]
+///   Floor,
+///   Empty,
+///   Occupied,
+/// }
+///
+/// let layout = Dense::parse(
+///   "L.LL.LL.LL
+/// LLLLLLL.LL
+/// L.L.L..L..
+/// LLLL.LL.LL
+/// L.LL.LL.LL
+/// L.LLLLL.LL
+/// ..L.L.....
+/// LLLLLLLLLL
+/// L.LLLLLL.L
+/// L.LLLLL.LL",
+///   |c| if c == 'L' { Seat::Empty } else { Seat::Floor },
+/// );
+///
+/// let directions = Offsets::<2>::moore();
+/// let visible = |[x, y]: [i64; 2]| {
+///   let mut seats = Vec::new();
+///   directions.for_each([0, 0], &mut |[dx, dy]| {
+///     let seat = (1..)
+///       .map(|k| [x + k * dx, y + k * dy])
+///       .take_while(|&cell| layout.index(cell).is_some())
+///       .find(|&cell| layout.get(cell) != Seat::Floor);
+///     seats.extend(seat);
+///   });
+///   seats
+/// };
+/// let rule = |site: &Site<'_, [i64; 2], Seat>| match (site.state, site.count(Seat::Occupied)) {
+///   (Seat::Empty, 0) => Seat::Occupied,
+///   (Seat::Occupied, 5..) => Seat::Empty,
+///   (state, _) => state,
+/// };
+///
+/// let mut seats = Automaton::new(layout.clone(), visible, rule);
+/// assert_eq!(seats.run_until_stable(), 6);
+/// assert_eq!(seats.count(Seat::Occupied), 26);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Automaton<St, Nb, R>
+where
+  St: Storage,
+{
+  storage: St,
+  back: St,
+  neighbourhood: Nb,
+  rule: R,
+  generation: usize,
+  neighbours: Vec<St::State>,
+}
+
+impl<St, Nb, R> Automaton<St, Nb, R>
+where
+  St: Storage,
+  Nb: Neighbourhood<St::Cell>,
+  R: Rule<St::Cell, St::State>,
+{
+  /// An automaton starting from the states in `storage`.
+  pub fn new(storage: St, neighbourhood: Nb, rule: R) -> Self {
+    Self {
+      back: storage.clone(),
+      storage,
+      neighbourhood,
+      rule,
+      generation: 0,
+      neighbours: Vec::new(),
+    }
+  }
+
+  /// Computes the next generation, returning whether any cell changed.
+  pub fn step(&mut self) -> bool {
+    let Self {
+      storage,
+      back,
+      neighbourhood,
+      rule,
+      generation,
+      neighbours,
+    } = self;
+
+    back.clear();
+    let mut changed = false;
+    for cell in storage.candidates(neighbourhood) {
+      neighbours.clear();
+      neighbourhood.for_each(cell, &mut |neighbour| {
+        neighbours.push(storage.get(neighbour));
+      });
+      let state = storage.get(cell);
+      let next = rule.next(&Site {
+        cell,
+        state,
+        neighbours,
+        generation: *generation,
+      });
+      changed |= next != state;
+      back.set(cell, next);
+    }
+
+    mem::swap(storage, back);
+    *generation += 1;
+    changed
+  }
+
+  /// Computes `generations` generations.
+  pub fn run(&mut self, generations: usize) {
+    for _ in 0..generations {
+      self.step();
+    }
+  }
+
+  /// Steps until a generation changes nothing, returning the number of
+  /// generations that did change something.
+  ///
+  /// Never returns for patterns that keep changing.
+  pub fn run_until_stable(&mut self) -> usize {
+    let start = self.generation;
+    while self.step() {}
+    self.generation - start - 1
+  }
+
+  /// The current states.
+  #[must_use]
+  pub const fn storage(&self) -> &St {
+    &self.storage
+  }
+
+  /// Gives up the automaton, keeping the current states.
+  #[must_use]
+  pub fn into_storage(self) -> St {
+    self.storage
+  }
+
+  /// Number of generations computed so far.
+  #[must_use]
+  pub const fn generation(&self) -> usize {
+    self.generation
+  }
+
+  /// Number of cells not in the default state.
+  #[must_use]
+  pub fn population(&self) -> usize {
+    self.storage.live().count()
+  }
+
+  /// Number of cells in `state`, which must not be the default state.
+  #[must_use]
+  pub fn count(&self, state: St::State) -> usize {
+    self
+      .storage
+      .live()
+      .filter(|&(_, current)| current == state)
+      .count()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    std::collections::BTreeSet,
+  };
+
+  const GLIDER: &str = ".#.\n..#\n###";
+
+  fn life<C>(site: &Site<'_, C, bool>) -> bool {
+    matches!((site.state, site.live()), (true, 2 | 3) | (false, 3))
+  }
+
+  fn cells<St: Storage<Cell = [i64; 2], State = bool>>(storage: &St) -> BTreeSet<[i64; 2]> {
+    storage.live().map(|(cell, _)| cell).collect()
+  }
+
+  #[test]
+  fn gliders_move_diagonally() {
+    let glider = Dense::parse(GLIDER, |c| c == '#')
+      .live()
+      .collect::<Sparse<_, _>>();
+    let start = cells(&glider);
+
+    let mut automaton = Automaton::new(glider, Offsets::moore(), life);
+    automaton.run(8);
+    let moved = start.iter().map(|&[x, y]| [x + 2, y + 2]).collect();
+    assert_eq!(cells(automaton.storage()), moved);
+    assert_eq!(automaton.generation(), 8);
+  }
+
+  #[test]
+  fn dense_and_sparse_agree_inside_the_bounds() {
+    let mut dense = Dense::new([12, 12]);
+    for (cell, on) in Dense::parse(GLIDER, |c| c == '#').live() {
+      dense.set(cell, on);
+    }
+    let sparse = dense.live().collect::<Sparse<_, _>>();
+
+    let mut dense = Automaton::new(dense, Offsets::moore(), life);
+    let mut sparse = Automaton::new(sparse, Offsets::moore(), life);
+    for _ in 0..20 {
+      assert_eq!(dense.step(), sparse.step());
+      assert_eq!(cells(dense.storage()), cells(sparse.storage()));
+    }
+  }
+
+  #[test]
+  fn dense_edges_read_as_dead() {
+    let blinker = Dense::parse("###", |c| c == '#');
+    let mut automaton = Automaton::new(blinker, Offsets::moore(), life);
+    automaton.step();
+    assert_eq!(cells(automaton.storage()), BTreeSet::from([[1, 0]]));
+    assert!(automaton.step());
+    assert_eq!(automaton.population(), 0);
+    assert!(!automaton.step());
+  }
+
+  #[test]
+  fn oscillators_and_still_lifes() {
+    let blinker = Sparse::from_iter([[0, 0], [1, 0], [2, 0]].map(|cell| (cell, true)));
+    let mut automaton = Automaton::new(blinker.clone(), Offsets::moore(), life);
+    automaton.run(2);
+    assert_eq!(automaton.storage(), &blinker);
+
+    let block = Sparse::from_iter([[0, 0], [1, 0], [0, 1], [1, 1]].map(|cell| (cell, true)));
+    let mut automaton = Automaton::new(block.clone(), Offsets::moore(), life);
+    assert_eq!(automaton.run_until_stable(), 0);
+    assert_eq!(automaton.into_storage(), block);
+  }
+
+  #[test]
+  fn dense_positions_round_trip() {
+    let grid = Dense::<u8, 3>::new([3, 4, 5]);
+    for index in 0..60 {
+      assert_eq!(grid.index(grid.position(index)), Some(index));
+    }
+    assert_eq!(grid.index([0, 0, 0]), Some(0));
+    assert_eq!(grid.index([1, 0, 0]), Some(1));
+    assert_eq!(grid.index([0, 1, 0]), Some(3));
+    assert_eq!(grid.index([-1, 0, 0]), None);
+    assert_eq!(grid.index([0, 4, 0]), None);
+  }
+
+  #[test]
+  fn offsets_are_distinct_and_exclude_the_cell() {
+    fn around<const N: usize>(offsets: &Offsets<N>) -> Vec<[i64; N]> {
+      let mut cells = Vec::new();
+      offsets.for_each([0; N], &mut |cell| cells.push(cell));
+      cells
+    }
+
+    let moore = around(&Offsets::<3>::moore());
+    assert_eq!(moore.iter().collect::<BTreeSet<_>>().len(), 26);
+    assert!(!moore.contains(&[0; 3]));
+    assert!(moore.iter().all(|cell| cell.iter().all(|d| d.abs() <= 1)));
+
+    let von_neumann = around(&Offsets::<4>::von_neumann());
+    assert_eq!(von_neumann.len(), 8);
+    assert!(
+      von_neumann
+        .iter()
+        .all(|cell| cell.iter().map(|d| d.abs()).sum::<i64>() == 1)
+    );
+  }
+}
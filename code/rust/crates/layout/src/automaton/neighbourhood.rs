@@ -0,0 +1,111 @@
+//! Which cells count as neighbours.
+//!
+//! `Offsets` covers every neighbourhood that is the same around each cell:
+//! Moore (2015/18, 2020/17), von Neumann and hexagonal (2020/24). Anything
+//! else is a closure from a cell to its neighbours, such as 2020/11's line
+//! of sight or 2019/24's recursive levels.
+
+use crate::_prelude::*;
+
+/// Yields the neighbours of a cell.
+pub trait Neighbourhood<C> {
+  /// Calls `visit` once per neighbour of `cell`.
+  fn for_each(&self, cell: C, visit: &mut dyn FnMut(C));
+}
+
+impl<C, F, I> Neighbourhood<C> for F
+where
+  F: Fn(C) -> I,
+  I: IntoIterator<Item = C>,
+{
+  fn for_each(&self, cell: C, visit: &mut dyn FnMut(C)) {
+    self(cell).into_iter().for_each(visit);
+  }
+}
+
+/// A neighbourhood given by fixed offsets from every cell.
+///
+/// # Examples
+///
+/// ```
+/// use grid::prelude::*;
+///
+/// assert_eq!(Offsets::<2>::moore().len(), 8);
+/// assert_eq!(Offsets::<4>::moore().len(), 80);
+/// assert_eq!(Offsets::<3>::von_neumann().len(), 6);
+///
+/// let mut around = Vec::new();
+/// Offsets::hex().for_each([0, 0], &mut |cell| around.push(cell));
+/// assert!(around.contains(&[1, -1]) && !around.contains(&[1, 1]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Offsets<const N: usize> {
+  offsets: Vec<[i64; N]>,
+}
+
+impl<const N: usize> Offsets<N> {
+  /// A neighbourhood from explicit offsets.
+  #[must_use]
+  pub const fn new(offsets: Vec<[i64; N]>) -> Self {
+    Self { offsets }
+  }
+
+  /// Every cell within one step on each axis, diagonals included.
+  #[must_use]
+  pub fn moore() -> Self {
+    let offsets = (0..3usize.pow(u32::try_from(N).unwrap_or(u32::MAX)))
+      .map(|mut index| {
+        array::from_fn(|_| {
+          let digit = index % 3;
+          index /= 3;
+          i64::try_from(digit).unwrap_or_default() - 1
+        })
+      })
+      .filter(|offset: &[i64; N]| offset.iter().any(|&d| d != 0))
+      .collect();
+    Self { offsets }
+  }
+
+  /// The cells one step away along a single axis.
+  #[must_use]
+  pub fn von_neumann() -> Self {
+    let offsets = (0..N)
+      .flat_map(|axis| {
+        [-1, 1].map(|step| {
+          let mut offset = [0; N];
+          offset[axis] = step;
+          offset
+        })
+      })
+      .collect();
+    Self { offsets }
+  }
+
+  /// Number of neighbours of every cell.
+  #[must_use]
+  pub const fn len(&self) -> usize {
+    self.offsets.len()
+  }
+
+  /// Whether cells have no neighbours at all.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.offsets.is_empty()
+  }
+}
+
+impl Offsets<2> {
+  /// The six neighbours of a hexagon in axial coordinates `[q, r]`.
+  #[must_use]
+  pub fn hex() -> Self {
+    Self::new(vec![[1, 0], [-1, 0], [0, 1], [0, -1], [1, -1], [-1, 1]])
+  }
+}
+
+impl<const N: usize> Neighbourhood<[i64; N]> for Offsets<N> {
+  fn for_each(&self, cell: [i64; N], visit: &mut dyn FnMut([i64; N])) {
+    for offset in &self.offsets {
+      visit(array::from_fn(|axis| cell[axis] + offset[axis]));
+    }
+  }
+}
@@ -0,0 +1,242 @@
+//! Where cell states live between generations.
+//!
+//! `Dense` is a bounded box of cells in `N` dimensions; everything outside
+//! reads as the default state and cannot change. `Sparse` stores only the
+//! cells whose state is not the default, over an unbounded space of any
+//! cell type, and grows as the pattern does.
+
+use {
+  super::Neighbourhood,
+  crate::_prelude::*,
+};
+
+/// Cell states of an automaton.
+///
+/// The default state is the "dead" or "outside" one: it is what cells
+/// beyond a bounded storage read as, and what a sparse storage does not
+/// bother to keep.
+pub trait Storage: Clone {
+  /// Address of a cell.
+  type Cell: Copy;
+  /// State of a cell.
+  type State: Copy + Default + PartialEq;
+
+  /// The state of a cell.
+  fn get(&self, cell: Self::Cell) -> Self::State;
+
+  /// Changes the state of a cell; may be ignored outside the storage's
+  /// bounds.
+  fn set(&mut self, cell: Self::Cell, state: Self::State);
+
+  /// Resets every cell to the default state.
+  fn clear(&mut self);
+
+  /// Every cell whose state is not the default.
+  fn live(&self) -> impl Iterator<Item = (Self::Cell, Self::State)> + '_;
+
+  /// Every cell whose state may change in the next generation.
+  fn candidates<Nb: Neighbourhood<Self::Cell>>(&self, neighbourhood: &Nb) -> Vec<Self::Cell>;
+}
+
+/// A bounded, row-major box of cells.
+///
+/// Axis 0 varies fastest, so a two-dimensional `Dense` is indexed
+/// `[x, y]` and its rows are contiguous.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Dense<S, const N: usize> {
+  dims: [usize; N],
+  cells: Vec<S>,
+}
+
+impl<S: Copy + Default, const N: usize> Dense<S, N> {
+  /// A box of the given size with every cell in the default state.
+  #[must_use]
+  pub fn new(dims: [usize; N]) -> Self {
+    Self {
+      dims,
+      cells: vec![S::default(); dims.iter().product()],
+    }
+  }
+
+  /// Size along every axis.
+  #[must_use]
+  pub const fn dims(&self) -> [usize; N] {
+    self.dims
+  }
+
+  /// Position of a cell in the underlying vector.
+  #[must_use]
+  pub fn index(&self, cell: [i64; N]) -> Option<usize> {
+    let mut index = 0;
+    for axis in (0..N).rev() {
+      let coordinate = usize::try_from(cell[axis]).ok()?;
+      if coordinate >= self.dims[axis] {
+        return None;
+      }
+      index = index * self.dims[axis] + coordinate;
+    }
+    Some(index)
+  }
+
+  /// The cell stored at an index of the underlying vector.
+  #[must_use]
+  pub fn position(&self, mut index: usize) -> [i64; N] {
+    array::from_fn(|axis| {
+      let coordinate = index % self.dims[axis];
+      index /= self.dims[axis];
+      i64::try_from(coordinate).unwrap_or(i64::MAX)
+    })
+  }
+}
+
+impl<S: Copy + Default> Dense<S, 2> {
+  /// Reads a grid of characters, one row per line.
+  ///
+  /// Lines shorter than the longest one are padded with the default state.
+  pub fn parse(text: &str, mut state: impl FnMut(char) -> S) -> Self {
+    let lines = text.lines().collect::<Vec<_>>();
+    let width = lines
+      .iter()
+      .map(|line| line.chars().count())
+      .max()
+      .unwrap_or(0);
+    let mut grid = Self::new([width, lines.len()]);
+    for (y, line) in lines.iter().enumerate() {
+      for (x, c) in line.chars().enumerate() {
+        grid.cells[y * width + x] = state(c);
+      }
+    }
+    grid
+  }
+
+  /// Draws the grid, one row per line.
+  #[must_use]
+  pub fn render(&self, mut symbol: impl FnMut(S) -> char) -> String {
+    let mut text = String::with_capacity(self.cells.len() + self.dims[1]);
+    for row in self.cells.chunks(self.dims[0].max(1)) {
+      text.extend(row.iter().map(|&state| symbol(state)));
+      text.push('\n');
+    }
+    text
+  }
+}
+
+impl<S: Copy + Default + PartialEq, const N: usize> Storage for Dense<S, N> {
+  type Cell = [i64; N];
+  type State = S;
+
+  fn get(&self, cell: [i64; N]) -> S {
+    self
+      .index(cell)
+      .map_or_else(S::default, |index| self.cells[index])
+  }
+
+  fn set(&mut self, cell: [i64; N], state: S) {
+    if let Some(index) = self.index(cell) {
+      self.cells[index] = state;
+    }
+  }
+
+  fn clear(&mut self) {
+    self.cells.fill(S::default());
+  }
+
+  fn live(&self) -> impl Iterator<Item = ([i64; N], S)> + '_ {
+    self
+      .cells
+      .iter()
+      .enumerate()
+      .filter(|&(_, &state)| state != S::default())
+      .map(|(index, &state)| (self.position(index), state))
+  }
+
+  fn candidates<Nb: Neighbourhood<[i64; N]>>(&self, _: &Nb) -> Vec<[i64; N]> {
+    (0..self.cells.len())
+      .map(|index| self.position(index))
+      .collect()
+  }
+}
+
+/// An unbounded set of cells, storing only those not in the default state.
+#[derive(Debug, Clone)]
+pub struct Sparse<C, S> {
+  cells: HashMap<C, S>,
+}
+
+impl<C, S> Sparse<C, S> {
+  /// An empty space.
+  #[must_use]
+  pub fn new() -> Self {
+    Self {
+      cells: HashMap::new(),
+    }
+  }
+}
+
+impl<C: Eq + Hash, S: PartialEq> PartialEq for Sparse<C, S> {
+  fn eq(&self, other: &Self) -> bool {
+    self.cells == other.cells
+  }
+}
+
+impl<C: Eq + Hash, S: Eq> Eq for Sparse<C, S> {}
+
+impl<C, S> Default for Sparse<C, S> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<C, S> FromIterator<(C, S)> for Sparse<C, S>
+where
+  C: Copy + Eq + Hash,
+  S: Copy + Default + PartialEq,
+{
+  fn from_iter<I: IntoIterator<Item = (C, S)>>(cells: I) -> Self {
+    let mut sparse = Self::new();
+    for (cell, state) in cells {
+      sparse.set(cell, state);
+    }
+    sparse
+  }
+}
+
+impl<C, S> Storage for Sparse<C, S>
+where
+  C: Copy + Eq + Hash,
+  S: Copy + Default + PartialEq,
+{
+  type Cell = C;
+  type State = S;
+
+  fn get(&self, cell: C) -> S {
+    self.cells.get(&cell).copied().unwrap_or_default()
+  }
+
+  fn set(&mut self, cell: C, state: S) {
+    if state == S::default() {
+      self.cells.remove(&cell);
+    } else {
+      self.cells.insert(cell, state);
+    }
+  }
+
+  fn clear(&mut self) {
+    self.cells.clear();
+  }
+
+  fn live(&self) -> impl Iterator<Item = (C, S)> + '_ {
+    self.cells.iter().map(|(&cell, &state)| (cell, state))
+  }
+
+  fn candidates<Nb: Neighbourhood<C>>(&self, neighbourhood: &Nb) -> Vec<C> {
+    let mut candidates = HashSet::with_capacity(self.cells.len() * 4);
+    for &cell in self.cells.keys() {
+      candidates.insert(cell);
+      neighbourhood.for_each(cell, &mut |neighbour| {
+        candidates.insert(neighbour);
+      });
+    }
+    candidates.into_iter().collect()
+  }
+}
@@ -0,0 +1,28 @@
+use crate::_prelude::*;
+
+pub type Result<T> = StdResult<T, Error>;
+
+#[derive(Debug, ThisError, Diagnostic)]
+pub enum Error {
+  #[error("no pixels are lit")]
+  #[diagnostic(code(grid::empty))]
+  Empty,
+
+  #[error("no font is {height} pixels tall")]
+  #[diagnostic(
+    code(grid::height),
+    help("Letters are 6 pixels tall in the small font and 10 in the large one.")
+  )]
+  Height { height: usize },
+
+  #[error("unrecognised letter at column {column}:\n{glyph}")]
+  #[diagnostic(
+    code(grid::glyph),
+    help("The letter is not in the font tables; add it to `ocr` once its answer is known.")
+  )]
+  Glyph { column: usize, glyph: String },
+
+  #[error("`{letter}` is not in the {font:?} font")]
+  #[diagnostic(code(grid::letter))]
+  Letter { letter: char, font: Font },
+}
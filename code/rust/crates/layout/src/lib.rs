@@ -0,0 +1,17 @@
+//! Grids, cellular automata and pixel utilities shared by puzzle solutions.
+
+pub mod automaton;
+pub mod bits;
+pub mod error;
+pub mod ocr;
+pub mod prelude;
+
+mod _prelude {
+  pub use crate::{
+    error::{
+      Error,
+      Result,
+    },
+    prelude::*,
+  };
+}
@@ -0,0 +1,435 @@
+//! Reading the block letters some puzzles answer with.
+//!
+//! 2016/08, 2019/08, 2019/11, 2021/13 and 2022/10 draw their answers in a
+//! small font, 6 pixels tall; 2018/10 uses a large one, 10 pixels tall.
+//! The functions here take the lit pixels in any form, pick the font from
+//! the height of the drawing, and match the letters against both fonts'
+//! glyph tables, so these answers become plain strings that can be checked
+//! like any other.
+//!
+//! # Examples
+//!
+//! ```
+//! use grid::prelude::*;
+//!
+//! let screen = "\
+//! .██...██...██.
+//! █..█.█..█.█..█
+//! █..█.█..█.█...
+//! ████.█..█.█...
+//! █..█.█..█.█..█
+//! █..█..██...██.";
+//! assert_eq!(ocr::read_text(screen).unwrap(), "AOC");
+//!
+//! let pixels: BitGrid = ocr::draw("AOC", Font::Small).unwrap();
+//! assert_eq!(
+//!   pixels.render(|on| if on { '█' } else { '.' }),
+//!   format!("{screen}\n")
+//! );
+//! ```
+
+use crate::{
+  _prelude::*,
+  bits::Word,
+};
+
+/// One of the two fonts puzzle answers are drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Font {
+  /// Letters 6 pixels tall, one blank column apart.
+  Small,
+  /// Letters 10 pixels tall, two blank columns apart.
+  Large,
+}
+
+impl Font {
+  /// The font whose letters are `height` pixels tall.
+  #[must_use]
+  pub const fn of_height(height: usize) -> Option<Self> {
+    match height {
+      6 => Some(Self::Small),
+      10 => Some(Self::Large),
+      _ => None,
+    }
+  }
+
+  /// Height of every letter.
+  #[must_use]
+  pub const fn height(self) -> usize {
+    match self {
+      Self::Small => 6,
+      Self::Large => 10,
+    }
+  }
+
+  /// Blank columns between letters.
+  #[must_use]
+  pub const fn spacing(self) -> usize {
+    match self {
+      Self::Small => 1,
+      Self::Large => 2,
+    }
+  }
+
+  /// Every letter the font knows, with its rows of `#` and `.`.
+  fn glyphs(self) -> Vec<(char, Vec<&'static str>)> {
+    let (letters, art) = match self {
+      Self::Small => SMALL,
+      Self::Large => LARGE,
+    };
+    let mut rows = art.iter().map(|row| row.split(' ')).collect::<Vec<_>>();
+    letters
+      .chars()
+      .map(|letter| (letter, rows.iter_mut().filter_map(Iterator::next).collect()))
+      .collect()
+  }
+
+  fn glyph(self, letter: char) -> Option<Vec<&'static str>> {
+    self
+      .glyphs()
+      .into_iter()
+      .find(|&(known, _)| known == letter)
+      .map(|(_, rows)| rows)
+  }
+}
+
+/// Letters of the small font, and their glyphs side by side.
+const SMALL: (&str, &[&str]) = (
+  "ABCEFGHIJKLOPRSUYZ",
+  &[
+    ".##. ###. .##. #### #### .##. #..# ### ..## #..# #... .##. ###. ###. .### #..# #...# ####",
+    "#..# #..# #..# #... #... #..# #..# .#. ...# #.#. #... #..# #..# #..# #... #..# #...# ...#",
+    "#..# ###. #... ###. ###. #... #### .#. ...# ##.. #... #..# #..# #..# #... #..# .#.#. ..#.",
+    "#### #..# #... #... #... #.## #..# .#. ...# #.#. #... #..# ###. ###. .##. #..# ..#.. .#..",
+    "#..# #..# #..# #... #... #..# #..# .#. #..# #.#. #... #..# #... #.#. ...# #..# ..#.. #...",
+    "#..# ###. .##. #### #... .### #..# ### .##. #..# #### .##. #... #..# ###. .##. ..#.. ####",
+  ],
+);
+
+/// Letters of the large font, and their glyphs side by side.
+const LARGE: (&str, &[&str]) = (
+  "ABCEFGHJKLNPRXZ",
+  &[
+    "..##.. #####. .####. ###### ###### .####. #....# ...### #....# #..... #....# #####. #####. #....# ######",
+    ".#..#. #....# #....# #..... #..... #....# #....# ....#. #...#. #..... ##...# #....# #....# #....# .....#",
+    "#....# #....# #..... #..... #..... #..... #....# ....#. #..#.. #..... ##...# #....# #....# .#..#. .....#",
+    "#....# #....# #..... #..... #..... #..... #....# ....#. #.#... #..... #.#..# #....# #....# .#..#. ....#.",
+    "#....# #####. #..... #####. #####. #..... ###### ....#. ##.... #..... #.#..# #####. #####. ..##.. ...#..",
+    "###### #....# #..... #..... #..... #..### #....# ....#. ##.... #..... #..#.# #..... #..#.. ..##.. ..#...",
+    "#....# #....# #..... #..... #..... #....# #....# ....#. #.#... #..... #..#.# #..... #...#. .#..#. .#....",
+    "#....# #....# #..... #..... #..... #....# #....# #...#. #..#.. #..... #...## #..... #...#. .#..#. #.....",
+    "#....# #....# #....# #..... #..... #...## #....# #...#. #...#. #..... #...## #..... #....# #....# #.....",
+    "#....# #####. .####. ###### #..... .###.# #....# .###.. #....# ###### #....# #..... #....# #....# ######",
+  ],
+);
+
+/// Reads the letters drawn by the set cells of a grid.
+///
+/// Blank rows and columns around the drawing are ignored, and letters may
+/// touch: 2016/08's and 2019/08's `Y` runs into the letter after it.
+///
+/// # Errors
+/// If nothing is lit, the drawing is neither 6 nor 10 pixels tall, or a
+/// letter is not in the font.
+///
+/// # Examples
+///
+/// ```
+/// use grid::prelude::*;
+///
+/// let image: BitGrid = BitGrid::parse(
+///   "\
+/// .........
+/// .#...#####
+/// .#...#...#
+/// ..#.#...#.
+/// ...#...#..
+/// ...#..#...
+/// ...#..####",
+///   |c| c == '#',
+/// );
+/// assert_eq!(ocr::read(&image).unwrap(), "YZ");
+/// ```
+pub fn read<W: Word>(pixels: &BitGrid<W>) -> Result<String> {
+  let mut ones = pixels.ones();
+  let (x, y) = ones.next().ok_or(Error::Empty)?;
+  let (mut left, mut right, mut bottom) = (x, x, y);
+  for (x, y) in ones {
+    left = left.min(x);
+    right = right.max(x);
+    bottom = bottom.max(y);
+  }
+  let top = y;
+  let height = bottom - top + 1;
+  let font = Font::of_height(height).ok_or(Error::Height { height })?;
+
+  let lit = |x: usize, y: usize| pixels.get(left + x, top + y);
+  let blank = |x: usize| (0..height).all(|y| !lit(x, y));
+  let fits = |x: usize, rows: &[&str]| {
+    rows.iter().enumerate().all(|(y, row)| {
+      row
+        .chars()
+        .enumerate()
+        .all(|(dx, c)| lit(x + dx, y) == (c == '#'))
+    })
+  };
+
+  let mut glyphs = font.glyphs();
+  glyphs.sort_by_key(|(_, rows)| usize::MAX - rows[0].len());
+
+  let width = right - left + 1;
+  let mut text = String::new();
+  let mut x = 0;
+  while x < width {
+    if blank(x) {
+      x += 1;
+      continue;
+    }
+    let Some((letter, rows)) = glyphs.iter().find(|(_, rows)| fits(x, rows)) else {
+      let end = (x..width).find(|&x| blank(x)).unwrap_or(width);
+      let glyph = (0..height)
+        .map(|y| {
+          (x..end)
+            .map(|x| if lit(x, y) { '#' } else { '.' })
+            .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+      return Err(Error::Glyph { column: x, glyph });
+    };
+    text.push(*letter);
+    x += rows[0].len();
+  }
+  Ok(text)
+}
+
+/// Reads the letters drawn by a set of lit points `[x, y]`, with `y`
+/// growing downwards.
+///
+/// # Errors
+/// As for `read`.
+pub fn read_points(points: impl IntoIterator<Item = [i64; 2]>) -> Result<String> {
+  let points = points.into_iter().collect::<Vec<_>>();
+  let corner = |axis: usize| points.iter().map(|point| point[axis]).min();
+  let (Some(left), Some(top)) = (corner(0), corner(1)) else {
+    return Err(Error::Empty);
+  };
+  let offsets = points
+    .iter()
+    .map(|&[x, y]| {
+      let offset = |value: i64, min: i64| usize::try_from(value - min).unwrap_or(usize::MAX);
+      (offset(x, left), offset(y, top))
+    })
+    .collect::<Vec<_>>();
+  let width = offsets.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+  let height = offsets.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+  if Font::of_height(height).is_none() {
+    return Err(Error::Height { height });
+  }
+
+  let mut pixels = BitGrid::<u64>::new(width, height);
+  for (x, y) in offsets {
+    pixels.set(x, y, true);
+  }
+  read(&pixels)
+}
+
+/// Reads the letters drawn in text, with `#` or `█` for lit pixels.
+///
+/// # Errors
+/// As for `read`.
+pub fn read_text(text: &str) -> Result<String> {
+  read(&BitGrid::<u64>::parse(text, |c| matches!(c, '#' | '█')))
+}
+
+/// Draws `text` in a font, letters separated by the font's spacing.
+///
+/// # Errors
+/// If some letter is not in the font.
+pub fn draw<W: Word>(text: &str, font: Font) -> Result<BitGrid<W>> {
+  let glyphs = text
+    .chars()
+    .map(|letter| font.glyph(letter).ok_or(Error::Letter { letter, font }))
+    .collect::<Result<Vec<_>>>()?;
+  let width = glyphs
+    .iter()
+    .map(|rows| rows[0].len() + font.spacing())
+    .sum::<usize>()
+    .saturating_sub(font.spacing());
+
+  let mut pixels = BitGrid::new(width, font.height());
+  let mut left = 0;
+  for rows in glyphs {
+    for (y, row) in rows.iter().enumerate() {
+      for (x, c) in row.chars().enumerate() {
+        pixels.set(left + x, y, c == '#');
+      }
+    }
+    left += rows[0].len() + font.spacing();
+  }
+  Ok(pixels)
+}
+
+/// The moment moving points line up into a message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Alignment {
+  /// Steps taken to get there.
+  pub time: u64,
+  /// Where the points are at that moment.
+  pub points: Vec<[i64; 2]>,
+}
+
+impl Alignment {
+  /// Finds when points moving at constant velocities, given as
+  /// `(position, velocity)`, come closest together vertically: 2018/10's
+  /// stars spelling out a message.
+  ///
+  /// The height of the points' bounding box only shrinks and then grows,
+  /// so the search stops at the first step after which it would grow.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use grid::prelude::*;
+  ///
+  /// let message: BitGrid = ocr::draw("HELP", Font::Large).unwrap();
+  /// let stars = message
+  ///   .ones()
+  ///   .map(|(x, y)| {
+  ///     let [x, y] = [x, y].map(|c| i64::try_from(c).unwrap());
+  ///     let velocity = [1 - x % 3, if (x + y) % 2 == 0 { 1 } else { -1 }];
+  ///     ([x - 10 * velocity[0], y - 10 * velocity[1]], velocity)
+  ///   })
+  ///   .collect::<Vec<_>>();
+  ///
+  /// let alignment = Alignment::find(&stars);
+  /// assert_eq!(alignment.time, 10);
+  /// assert_eq!(alignment.read().unwrap(), "HELP");
+  /// ```
+  #[must_use]
+  pub fn find(points: &[([i64; 2], [i64; 2])]) -> Self {
+    let at = |time: i64| {
+      points
+        .iter()
+        .map(move |&([x, y], [dx, dy])| [x + time * dx, y + time * dy])
+    };
+    let height = |time: i64| {
+      let (top, bottom) = at(time).fold((i64::MAX, i64::MIN), |(top, bottom), [_, y]| {
+        (top.min(y), bottom.max(y))
+      });
+      bottom.saturating_sub(top)
+    };
+
+    let mut time = 0;
+    let mut current = height(time);
+    loop {
+      let next = height(time + 1);
+      if next >= current {
+        break;
+      }
+      time += 1;
+      current = next;
+    }
+    Self {
+      time: time.unsigned_abs(),
+      points: at(time).collect(),
+    }
+  }
+
+  /// Reads the message the points spell.
+  ///
+  /// # Errors
+  /// As for `read`.
+  pub fn read(&self) -> Result<String> {
+    read_points(self.points.iter().copied())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const FONTS: [Font; 2] = [Font::Small, Font::Large];
+
+  fn letters(font: Font) -> Vec<char> {
+    font
+      .glyphs()
+      .into_iter()
+      .map(|(letter, _)| letter)
+      .collect()
+  }
+
+  #[test]
+  fn glyph_tables_are_rectangular() {
+    for font in FONTS {
+      for (letter, rows) in font.glyphs() {
+        assert_eq!(rows.len(), font.height(), "{letter}");
+        assert!(
+          rows.iter().all(|row| row.len() == rows[0].len()),
+          "{letter}"
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn every_glyph_round_trips() {
+    for font in FONTS {
+      for letter in letters(font) {
+        let text = letter.to_string();
+        let pixels = draw::<u64>(&text, font).unwrap();
+        assert_eq!(read(&pixels).unwrap(), text, "{font:?}");
+        assert_eq!(read(&draw::<u128>(&text, font).unwrap()).unwrap(), text);
+      }
+    }
+  }
+
+  #[test]
+  fn every_pair_of_glyphs_round_trips() {
+    for font in FONTS {
+      let letters = letters(font);
+      for &first in &letters {
+        for &second in &letters {
+          let text = format!("{first}{second}");
+          let pixels = draw::<u64>(&text, font).unwrap();
+          assert_eq!(read(&pixels).unwrap(), text, "{font:?}");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn whole_fonts_round_trip_through_every_input_form() {
+    for font in FONTS {
+      let text = letters(font).into_iter().collect::<String>();
+      let pixels = draw::<u128>(&text, font).unwrap();
+      assert_eq!(read(&pixels).unwrap(), text);
+      assert_eq!(
+        read_text(&pixels.render(|on| if on { '#' } else { '.' })).unwrap(),
+        text
+      );
+
+      let points = pixels
+        .ones()
+        .map(|(x, y)| <[usize; 2]>::from((x, y)).map(|c| i64::try_from(c).unwrap() - 1000));
+      assert_eq!(read_points(points).unwrap(), text);
+    }
+  }
+
+  #[test]
+  fn unknown_input_is_reported() {
+    assert!(matches!(
+      draw::<u64>("AQ", Font::Small),
+      Err(Error::Letter { letter: 'Q', .. })
+    ));
+    assert!(matches!(read_text("...\n..."), Err(Error::Empty)));
+    assert!(matches!(
+      read_text("#\n#\n#"),
+      Err(Error::Height { height: 3 })
+    ));
+
+    let mut pixels = draw::<u64>("A", Font::Small).unwrap();
+    pixels.set(1, 1, true);
+    assert!(matches!(read(&pixels), Err(Error::Glyph { column: 0, .. })));
+  }
+}
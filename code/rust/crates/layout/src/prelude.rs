@@ -0,0 +1,29 @@
+pub use {
+  crate::{
+    automaton::*,
+    bits::*,
+    error::{
+      Error as GridError,
+      Result as GridResult,
+    },
+    ocr::{
+      self,
+      Alignment,
+      Font,
+    },
+  },
+  miette::Diagnostic,
+  std::{
+    array,
+    collections::{
+      HashMap,
+      HashSet,
+    },
+    fmt::Debug,
+    hash::Hash,
+    iter,
+    mem,
+    result::Result as StdResult,
+  },
+  thiserror::Error as ThisError,
+};
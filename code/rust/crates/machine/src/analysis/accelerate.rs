@@ -0,0 +1,124 @@
+//! Running machines with recognized loops skipped.
+//!
+//! The accelerator hooks into `Machine::run_with` and, whenever the program
+//! counter reaches the head of a recognized loop, jumps the registers
+//! forward to the loop's final pass. Skipped instructions are not counted in
+//! `Machine::steps` or `Machine::profile`.
+//!
+//! Self-modifying programs are handled by remembering what every accelerated
+//! loop looked like at analysis time: once an instruction inside it has been
+//! rewritten (assembunny's `tgl`), that loop is interpreted normally.
+//!
+//! # Examples
+//!
+//! 2018/19 sums the divisors of a number by trying every product:
+//!
+//! ```
+//! use machine::{
+//!   analysis::Accelerator,
+//!   dialect::ElfCode,
+//!   prelude::*,
+//! };
+//!
+//! let source = "#ip 5
+//! seti 1 0 3
+//! seti 1 0 1
+//! mulr 3 1 4
+//! eqrr 4 2 4
+//! addr 4 5 5
+//! addi 5 1 5
+//! addr 3 0 0
+//! addi 1 1 1
+//! gtrr 1 2 4
+//! addr 5 4 5
+//! seti 1 0 5
+//! addi 3 1 3
+//! gtrr 3 2 4
+//! addr 4 5 5
+//! seti 0 0 5";
+//!
+//! let mut machine = Machine::<ElfCode>::parse(source)
+//!   .unwrap()
+//!   .with_register("2", 10_551_236);
+//! let mut accelerator = Accelerator::new(&machine);
+//! assert_eq!(accelerator.idioms().count(), 2);
+//!
+//! assert_eq!(accelerator.run(&mut machine), Halt::Finished);
+//! assert_eq!(machine.register("0"), Some(18_514_860));
+//! assert!(machine.steps < 100);
+//! ```
+
+use {
+  super::{
+    Analysis,
+    Idiom,
+  },
+  crate::_prelude::*,
+};
+
+/// Skips recognized loops while a machine runs.
+#[derive(Debug, Clone)]
+pub struct Accelerator<I> {
+  loops: BTreeMap<i64, (Idiom, Vec<I>)>,
+  /// Number of times a loop was skipped.
+  pub skips: u64,
+}
+
+impl<I: Instruction + PartialEq> Accelerator<I> {
+  /// Analyzes the machine's program and prepares every recognized loop.
+  #[must_use]
+  pub fn new(machine: &Machine<I>) -> Self {
+    Self::from_analysis(Analysis::of(machine), &machine.program)
+  }
+
+  /// Uses an existing analysis of `instructions`.
+  #[must_use]
+  pub fn from_analysis(analysis: Analysis, instructions: &[I]) -> Self {
+    let loops = analysis
+      .idioms
+      .into_iter()
+      .filter_map(|(head, idiom)| {
+        let body = Self::body(instructions, &idiom)?.to_vec();
+        Some((head, (idiom, body)))
+      })
+      .collect();
+    Self { loops, skips: 0 }
+  }
+
+  /// The idioms being accelerated, by head address.
+  pub fn idioms(&self) -> impl Iterator<Item = &Idiom> {
+    self.loops.values().map(|(idiom, _)| idiom)
+  }
+
+  /// Skips the loop starting at the machine's program counter, if there is
+  /// one and its body is unchanged.
+  ///
+  /// Returns whether the registers were changed.
+  pub fn apply(&mut self, machine: &mut Machine<I>) -> bool {
+    let Some((idiom, body)) = self.loops.get(&machine.pc) else {
+      return false;
+    };
+    if Self::body(&machine.program, idiom) != Some(body.as_slice()) {
+      return false;
+    }
+
+    let applied = idiom.apply(&mut machine.registers);
+    self.skips += u64::from(applied);
+    applied
+  }
+
+  /// Runs the machine like `Machine::run`, skipping recognized loops.
+  pub fn run(&mut self, machine: &mut Machine<I>) -> Halt {
+    machine.run_with(|machine| {
+      self.apply(machine);
+      None
+    })
+  }
+
+  fn body<'a>(instructions: &'a [I], idiom: &Idiom) -> Option<&'a [I]> {
+    let range = idiom.range();
+    let head = usize::try_from(range.head).ok()?;
+    let tail = usize::try_from(range.tail).ok()?;
+    instructions.get(head..=tail)
+  }
+}
@@ -0,0 +1,165 @@
+//! Basic blocks and loops of a lowered program.
+//!
+//! Blocks start at address 0, at every statically known jump target and
+//! after every jump. Loops are found from back edges (a jump to an earlier or
+//! the same address) and are described by the address range they span, which
+//! is exact for the structured code puzzle inputs are written in.
+
+use {
+  super::ir::Lowered,
+  crate::_prelude::*,
+};
+
+/// A maximal straight-line run of instructions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Block {
+  /// Address of the first instruction.
+  pub start: i64,
+  /// Address of the last instruction, inclusive.
+  pub end: i64,
+  /// Statically known successor addresses, possibly outside the program.
+  pub successors: Vec<i64>,
+  /// Whether the block ends in a jump whose target is only known at runtime.
+  pub computed: bool,
+}
+
+/// A loop spanning `head..=tail`, entered at `head`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Loop {
+  /// Target of the back edge(s).
+  pub head: i64,
+  /// Address of the last back edge to `head`.
+  pub tail: i64,
+}
+
+impl Loop {
+  /// Whether `pc` lies inside the loop.
+  #[must_use]
+  pub const fn contains(self, pc: i64) -> bool {
+    self.head <= pc && pc <= self.tail
+  }
+
+  /// Whether `other` is nested strictly inside this loop.
+  #[must_use]
+  pub const fn encloses(self, other: Self) -> bool {
+    self.contains(other.head) && self.contains(other.tail) && self.head != other.head
+  }
+}
+
+/// Control-flow graph of a lowered program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cfg {
+  /// Blocks in address order.
+  pub blocks: Vec<Block>,
+  /// Loops, innermost (shortest) first.
+  pub loops: Vec<Loop>,
+}
+
+impl Cfg {
+  /// Builds the graph of a lowered program.
+  #[must_use]
+  pub fn build(lowered: &[Lowered]) -> Self {
+    let len = i64::try_from(lowered.len()).unwrap_or(i64::MAX);
+    let in_range = |pc: i64| (0..len).contains(&pc);
+
+    let mut leaders = BTreeSet::from([0]);
+    let mut tails = BTreeMap::new();
+    for (pc, instruction) in (0..).zip(lowered) {
+      match *instruction {
+        Lowered::Jump(_, target) => {
+          leaders.insert(pc + 1);
+          if in_range(target) {
+            leaders.insert(target);
+            if target <= pc {
+              tails.insert(target, pc);
+            }
+          }
+        }
+        Lowered::Computed => {
+          leaders.insert(pc + 1);
+        }
+        Lowered::Assign(..) | Lowered::Nop | Lowered::Opaque => {}
+      }
+    }
+    leaders.retain(|&pc| in_range(pc));
+
+    let starts = leaders.iter().copied().collect::<Vec<_>>();
+    let blocks = starts
+      .iter()
+      .enumerate()
+      .map(|(index, &start)| {
+        let end = starts.get(index + 1).map_or(len - 1, |next| next - 1);
+        let last = lowered[usize::try_from(end).unwrap_or_default()];
+        Block {
+          start,
+          end,
+          successors: last.successors(end).unwrap_or_default(),
+          computed: last == Lowered::Computed,
+        }
+      })
+      .collect();
+
+    let mut loops = tails
+      .into_iter()
+      .map(|(head, tail)| Loop { head, tail })
+      .collect::<Vec<_>>();
+    loops.sort_by_key(|lp| (lp.tail - lp.head, lp.head));
+
+    Self { blocks, loops }
+  }
+
+  /// The block containing `pc`.
+  #[must_use]
+  pub fn block_at(&self, pc: i64) -> Option<&Block> {
+    self
+      .blocks
+      .iter()
+      .find(|block| block.start <= pc && pc <= block.end)
+  }
+
+  /// Renders the graph in Graphviz DOT format, one node per block.
+  #[must_use]
+  pub fn to_dot<I: Display>(&self, instructions: &[I]) -> String {
+    let mut lines = vec![
+      "digraph cfg {".to_owned(),
+      "  node [shape=box, fontname=monospace];".to_owned(),
+    ];
+    for block in &self.blocks {
+      let body = (block.start..=block.end)
+        .filter_map(|pc| {
+          let instruction = instructions.get(usize::try_from(pc).ok()?)?;
+          Some(format!("{pc:>4}: {instruction}\\l"))
+        })
+        .collect::<String>();
+      lines.push(format!("  b{} [label=\"{body}\"];", block.start));
+      for successor in &block.successors {
+        if self.blocks.iter().any(|other| other.start == *successor) {
+          lines.push(format!("  b{} -> b{successor};", block.start));
+        } else {
+          lines.push(format!("  b{} -> exit;", block.start));
+        }
+      }
+    }
+    lines.push("}\n".to_owned());
+    lines.join("\n")
+  }
+}
+
+impl Display for Cfg {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    for block in &self.blocks {
+      write!(f, "{:>4}..={:<4} ->", block.start, block.end)?;
+      for successor in &block.successors {
+        write!(f, " {successor}")?;
+      }
+      if block.computed {
+        write!(f, " ?")?;
+      }
+      writeln!(f)?;
+    }
+    for lp in &self.loops {
+      writeln!(f, "loop {}..={}", lp.head, lp.tail)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,202 @@
+//! Breakpoints and register watches.
+//!
+//! `Debugger` wraps a machine and runs it until something interesting
+//! happens: a breakpoint is reached, a watched register changes, or the
+//! machine stops on its own. Loop acceleration can be switched on, so even
+//! the slow puzzle programs can be stepped through at the points that
+//! matter.
+//!
+//! # Examples
+//!
+//! ```
+//! use machine::{
+//!   analysis::{
+//!     Debugger,
+//!     Event,
+//!   },
+//!   dialect::Assembunny,
+//!   prelude::*,
+//! };
+//!
+//! let source = "cpy 3 c\ninc a\ndec c\njnz c -2\ncpy a b";
+//! let machine = Machine::<Assembunny>::parse(source).unwrap();
+//! let mut debugger = Debugger::new(machine).with_breakpoint(3);
+//! debugger.watch("b").unwrap();
+//!
+//! assert_eq!(debugger.resume(), Event::Breakpoint { pc: 3 });
+//! assert_eq!(debugger.machine.register("a"), Some(1));
+//! assert_eq!(debugger.resume(), Event::Breakpoint { pc: 3 });
+//!
+//! debugger.clear_breakpoint(3);
+//! let event = debugger.resume();
+//! assert_eq!(event.to_string(), "b changed 0 -> 3 at 4");
+//! assert_eq!(debugger.resume(), Event::Stopped(Halt::Finished));
+//! ```
+
+use {
+  super::Accelerator,
+  crate::_prelude::*,
+};
+
+/// Why `Debugger::resume` or `Debugger::step` returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+  /// The instruction at `pc` is about to run.
+  Breakpoint { pc: i64 },
+  /// The instruction at `pc` changed a watched register.
+  Watch {
+    register: &'static str,
+    old: i64,
+    new: i64,
+    pc: i64,
+  },
+  /// `Debugger::step` executed the instruction at `pc`.
+  Stepped { pc: i64 },
+  /// The machine stopped on its own.
+  Stopped(Halt),
+}
+
+impl Display for Event {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Breakpoint { pc } => write!(f, "breakpoint at {pc}"),
+      Self::Watch {
+        register,
+        old,
+        new,
+        pc,
+      } => write!(f, "{register} changed {old} -> {new} at {pc}"),
+      Self::Stepped { pc } => write!(f, "stepped {pc}"),
+      Self::Stopped(halt) => write!(f, "stopped: {halt:?}"),
+    }
+  }
+}
+
+/// A machine under the debugger's control.
+#[derive(Debug, Clone)]
+pub struct Debugger<I: Instruction> {
+  /// The debugged machine; free to inspect or modify between events.
+  pub machine: Machine<I>,
+  breakpoints: BTreeSet<i64>,
+  watches: Vec<(Reg, i64)>,
+  accelerator: Option<Accelerator<I>>,
+  resume_at: Option<i64>,
+}
+
+impl<I: Instruction + PartialEq> Debugger<I> {
+  /// Takes control of a machine.
+  #[must_use]
+  pub const fn new(machine: Machine<I>) -> Self {
+    Self {
+      machine,
+      breakpoints: BTreeSet::new(),
+      watches: Vec::new(),
+      accelerator: None,
+      resume_at: None,
+    }
+  }
+
+  /// Stops before the instruction at `pc` runs.
+  #[must_use]
+  pub fn with_breakpoint(mut self, pc: i64) -> Self {
+    self.breakpoints.insert(pc);
+    self
+  }
+
+  /// Skips recognized loops while resuming (see `Accelerator`).
+  ///
+  /// Watches only see the net effect of a skipped loop, attributed to its
+  /// head.
+  #[must_use]
+  pub fn with_acceleration(mut self, enabled: bool) -> Self {
+    self.accelerator = enabled.then(|| Accelerator::new(&self.machine));
+    self
+  }
+
+  /// Adds a breakpoint.
+  pub fn break_at(&mut self, pc: i64) {
+    self.breakpoints.insert(pc);
+  }
+
+  /// Removes a breakpoint, returning whether it existed.
+  pub fn clear_breakpoint(&mut self, pc: i64) -> bool {
+    self.breakpoints.remove(&pc)
+  }
+
+  /// Reports every change to the named register.
+  ///
+  /// # Errors
+  /// Returns `Error::UnknownRegister` if `name` is not part of the dialect.
+  pub fn watch(&mut self, name: &str) -> Result<()> {
+    let reg =
+      Reg::parse(name, I::REGISTERS).ok_or_else(|| Error::UnknownRegister(name.to_owned()))?;
+    self.watches.push((reg, self.machine.registers[reg]));
+    Ok(())
+  }
+
+  /// Executes a single instruction, ignoring breakpoints.
+  pub fn step(&mut self) -> Event {
+    self.resume_at = None;
+    let pc = self.machine.pc;
+    if let Some(halt) = self.machine.step() {
+      return Event::Stopped(halt);
+    }
+    Self::changed(&mut self.watches, &self.machine.registers, pc).unwrap_or(Event::Stepped { pc })
+  }
+
+  /// Runs until the next event.
+  pub fn resume(&mut self) -> Event {
+    let Self {
+      machine,
+      breakpoints,
+      watches,
+      accelerator,
+      resume_at,
+    } = self;
+    let mut skip = resume_at.take();
+    let mut previous = None;
+    let mut event = None;
+
+    let halt = machine.run_with(|machine| {
+      if let Some(pc) = previous {
+        event = Self::changed(watches, &machine.registers, pc);
+        if event.is_some() {
+          return Some(Halt::Paused);
+        }
+      }
+      if breakpoints.contains(&machine.pc) && skip.take() != Some(machine.pc) {
+        event = Some(Event::Breakpoint { pc: machine.pc });
+        *resume_at = Some(machine.pc);
+        return Some(Halt::Paused);
+      }
+      skip = None;
+      if let Some(accelerator) = accelerator {
+        accelerator.apply(machine);
+      }
+      previous = Some(machine.pc);
+      None
+    });
+
+    if halt == Halt::Paused {
+      return event.unwrap_or(Event::Stopped(halt));
+    }
+    previous
+      .and_then(|pc| Self::changed(watches, &machine.registers, pc))
+      .unwrap_or(Event::Stopped(halt))
+  }
+
+  /// Reports the first watched register that changed, updating its value.
+  fn changed(watches: &mut [(Reg, i64)], registers: &Registers, pc: i64) -> Option<Event> {
+    watches.iter_mut().find_map(|(reg, seen)| {
+      let old = *seen;
+      let new = registers[*reg];
+      *seen = new;
+      (old != new).then(|| Event::Watch {
+        register: registers.names()[reg.0],
+        old,
+        new,
+        pc,
+      })
+    })
+  }
+}
@@ -0,0 +1,1195 @@
+//! Loop idioms the accelerator can skip.
+//!
+//! | Idiom | Shape | Puzzles |
+//! |-------|-------|---------|
+//! | `AddLoop` | Counter stepped by one towards zero, other registers incremented by loop-invariant amounts | 2016/12, 2016/23 (multiplication) |
+//! | `DivisorProbe` | Counter `y` stepped up to a bound, something happens when `x * y == n` | 2017/23, 2018/19 (inner loop) |
+//! | `DivisorSweep` | A `DivisorProbe` run for every `x` in a range | 2017/23, 2018/19 (outer loop) |
+//! | `CompositeCount` | Counter stepped by a constant, a tally bumped whenever a `DivisorSweep` finds a factor pair | 2017/23 (outermost loop) |
+//!
+//! Every idiom skips all but the final pass of its loop and leaves that one
+//! to the interpreter. The final pass leaves scratch registers and the exit
+//! address exactly as the real loop would, so recognizers only have to prove
+//! what the skipped passes do to the registers that matter.
+
+use {
+  super::{
+    cfg::Loop,
+    ir::Lowered,
+    name,
+    symbolic::{
+      End,
+      Guard,
+      Hook,
+      Path,
+      Poly,
+      Test,
+      Value,
+      Walk,
+      explore,
+    },
+  },
+  crate::_prelude::*,
+  std::ops::RangeInclusive,
+};
+
+/// How a skipped stretch of a loop changes a register.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Update {
+  /// Adds the polynomial, once per pass or per hit.
+  Add(Poly),
+  /// Overwrites the register with the polynomial.
+  Set(Poly),
+}
+
+impl Update {
+  /// Classifies the value a pass leaves in `reg`.
+  fn classify(reg: Reg, value: &Poly) -> Option<Self> {
+    let delta = value.checked_sub(&Poly::reg(reg))?;
+    if !delta.mentions(reg) {
+      Some(Self::Add(delta))
+    } else if value.mentions(reg) {
+      None
+    } else {
+      Some(Self::Set(value.clone()))
+    }
+  }
+
+  const fn poly(&self) -> &Poly {
+    match self {
+      Self::Add(poly) | Self::Set(poly) => poly,
+    }
+  }
+
+  fn try_map(&self, f: impl FnOnce(&Poly) -> Option<Poly>) -> Option<Self> {
+    match self {
+      Self::Add(poly) => f(poly).map(Self::Add),
+      Self::Set(poly) => f(poly).map(Self::Set),
+    }
+  }
+
+  /// The register's value after `times` applications.
+  fn resolve(&self, reg: Reg, times: i64, value: impl Fn(Reg) -> i64) -> Option<i64> {
+    match self {
+      Self::Add(delta) => delta
+        .eval(&value)?
+        .checked_mul(times)?
+        .checked_add(value(reg)),
+      Self::Set(poly) => poly.eval(value),
+    }
+  }
+
+  fn describe(&self, reg: Reg, names: &[&str]) -> String {
+    match self {
+      Self::Add(delta) => format!("{} += {}", name(reg, names), delta.display(names)),
+      Self::Set(poly) => format!("{} = {}", name(reg, names), poly.display(names)),
+    }
+  }
+}
+
+/// A recognized loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Idiom {
+  AddLoop(AddLoop),
+  DivisorProbe(DivisorProbe),
+  DivisorSweep(DivisorSweep),
+  CompositeCount(Box<CompositeCount>),
+}
+
+impl Idiom {
+  /// The loop the idiom was recognized in.
+  #[must_use]
+  pub const fn range(&self) -> Loop {
+    match self {
+      Self::AddLoop(idiom) => idiom.range,
+      Self::DivisorProbe(idiom) => idiom.range,
+      Self::DivisorSweep(idiom) => idiom.range,
+      Self::CompositeCount(idiom) => idiom.range,
+    }
+  }
+
+  /// Skips all but the final pass, if the registers allow it.
+  ///
+  /// Must only be called with the program counter at the loop head. Returns
+  /// whether the registers were changed.
+  pub fn apply(&self, registers: &mut Registers) -> bool {
+    match self {
+      Self::AddLoop(idiom) => idiom.apply(registers),
+      Self::DivisorProbe(idiom) => idiom.apply(registers),
+      Self::DivisorSweep(idiom) => idiom.apply(registers),
+      Self::CompositeCount(idiom) => idiom.apply(registers),
+    }
+  }
+
+  /// One-line summary using the dialect's register names.
+  #[must_use]
+  pub fn describe(&self, names: &[&str]) -> String {
+    match self {
+      Self::AddLoop(idiom) => idiom.describe(names),
+      Self::DivisorProbe(idiom) => idiom.describe(names),
+      Self::DivisorSweep(idiom) => idiom.describe(names),
+      Self::CompositeCount(idiom) => idiom.describe(names),
+    }
+  }
+
+  /// Recognizes the loop `range`, given the idioms already found in the
+  /// loops nested inside it.
+  #[must_use]
+  pub fn recognize(
+    lowered: &[Lowered],
+    range: Loop,
+    loops: &[Loop],
+    known: &BTreeMap<i64, Self>,
+    registers: usize,
+  ) -> Option<Self> {
+    let nested = |pc: i64| {
+      loops
+        .iter()
+        .any(|inner| inner.head == pc && range.encloses(*inner))
+    };
+
+    let paths = explore(
+      lowered,
+      range.head,
+      range.tail,
+      registers,
+      |pc, walk| match known.get(&pc) {
+        Some(Self::AddLoop(inner)) if nested(pc) => inner
+          .summarize(walk)
+          .map_or(Hook::Abort, |()| Hook::Resume(inner.exit)),
+        _ if nested(pc) => Hook::Abort,
+        _ => Hook::Proceed,
+      },
+    );
+    if let Some(paths) = &paths {
+      if let Some(idiom) = AddLoop::recognize(range, paths, registers) {
+        return Some(Self::AddLoop(idiom));
+      }
+      if let Some(idiom) = DivisorProbe::recognize(range, paths, registers) {
+        return Some(Self::DivisorProbe(idiom));
+      }
+    }
+
+    Self::recognize_sweep(lowered, range, &nested, known, registers)
+      .or_else(|| Self::recognize_count(lowered, range, &nested, known, registers))
+  }
+
+  /// Recognizes a `DivisorSweep` around a nested `DivisorProbe`.
+  fn recognize_sweep(
+    lowered: &[Lowered],
+    range: Loop,
+    nested: &impl Fn(i64) -> bool,
+    known: &BTreeMap<i64, Self>,
+    registers: usize,
+  ) -> Option<Self> {
+    let mut reached = None;
+    let paths = explore(
+      lowered,
+      range.head,
+      range.tail,
+      registers,
+      |pc, walk| match known.get(&pc) {
+        Some(Self::DivisorProbe(probe)) if nested(pc) => {
+          let seen = (probe.clone(), walk.clone());
+          if *reached.get_or_insert_with(|| seen.clone()) != seen {
+            return Hook::Abort;
+          }
+          probe.summarize(walk);
+          Hook::Resume(probe.exit)
+        }
+        _ if nested(pc) => Hook::Abort,
+        _ => Hook::Proceed,
+      },
+    )?;
+    let (probe, at_probe) = reached?;
+    DivisorSweep::recognize(range, &paths, registers, &probe, &at_probe).map(Self::DivisorSweep)
+  }
+
+  /// Recognizes a `CompositeCount` around a nested `DivisorSweep`.
+  ///
+  /// The sweep is summarized with its outcome as a virtual register just
+  /// past the real ones, so the paths fork on whether it found a factor.
+  fn recognize_count(
+    lowered: &[Lowered],
+    range: Loop,
+    nested: &impl Fn(i64) -> bool,
+    known: &BTreeMap<i64, Self>,
+    registers: usize,
+  ) -> Option<Self> {
+    let found = Reg(registers);
+    let mut reached = None;
+    let paths = explore(
+      lowered,
+      range.head,
+      range.tail,
+      registers,
+      |pc, walk| match known.get(&pc) {
+        Some(Self::DivisorSweep(sweep)) if nested(pc) => {
+          let seen = (sweep.clone(), walk.clone());
+          if *reached.get_or_insert_with(|| seen.clone()) != seen {
+            return Hook::Abort;
+          }
+          sweep
+            .summarize(walk, found)
+            .map_or(Hook::Abort, |()| Hook::Resume(sweep.exit))
+        }
+        _ if nested(pc) => Hook::Abort,
+        _ => Hook::Proceed,
+      },
+    )?;
+    let (sweep, at_sweep) = reached?;
+    CompositeCount::recognize(range, &paths, registers, &sweep, &at_sweep)
+      .map(|idiom| Self::CompositeCount(Box::new(idiom)))
+  }
+}
+
+/// Registers no path changes.
+fn invariants(paths: &[Path], registers: usize) -> BTreeSet<Reg> {
+  (0..registers)
+    .map(Reg)
+    .filter(|&reg| {
+      paths
+        .iter()
+        .all(|path| path.walk.poly(reg) == Some(&Poly::reg(reg)))
+    })
+    .collect()
+}
+
+fn within(poly: &Poly, allowed: &BTreeSet<Reg>) -> bool {
+  poly.registers().is_subset(allowed)
+}
+
+/// Splits paths into the ones that go around again and the ones that leave,
+/// requiring a single exit address.
+fn partition(paths: &[Path]) -> Option<(Vec<&Path>, Vec<&Path>, i64)> {
+  let (passes, leaves): (Vec<_>, Vec<_>) = paths.iter().partition(|path| path.end == End::Continue);
+  let exits = leaves.iter().map(|path| path.end).collect::<HashSet<_>>();
+  match exits.into_iter().collect::<Vec<_>>()[..] {
+    [End::Exit(exit)] => Some((passes, leaves, exit)),
+    _ => None,
+  }
+}
+
+/// The register every path increments by exactly `step`.
+fn counter(paths: &[Path], registers: usize, step: i64) -> Option<Reg> {
+  (0..registers).map(Reg).find(|&reg| {
+    let next = Poly::reg(reg).checked_add(&Poly::constant(step));
+    paths
+      .iter()
+      .all(|path| path.walk.poly(reg) == next.as_ref())
+  })
+}
+
+/// Counter value on the final pass of a loop that increments `counter` by
+/// one and goes around again while `guard` is satisfied.
+fn last_pass(guard: &Guard, counter: Reg) -> Option<Poly> {
+  let (coefficient, rest) = guard.test.poly().split(counter)?;
+  let sign = coefficient.as_constant()?;
+  match (&guard.test, guard.holds, sign) {
+    // counter + rest <= 0
+    (Test::Positive(_), false, 1) => Poly::constant(1).checked_sub(&rest),
+    // rest - counter > 0
+    (Test::Positive(_), true, -1) | (Test::Zero(_), false, -1) => Some(rest),
+    // counter + rest != 0
+    (Test::Zero(_), false, 1) => rest.checked_neg(),
+    _ => None,
+  }
+}
+
+/// A loop that steps a counter towards zero while adding loop-invariant
+/// amounts to other registers: repeated addition, i.e. multiplication.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddLoop {
+  pub range: Loop,
+  /// Where the loop exits to.
+  pub exit: i64,
+  pub counter: Reg,
+  /// `1` or `-1`; the loop stops when the counter reaches zero.
+  pub step: i64,
+  /// Per-pass changes to the other registers.
+  pub updates: Vec<(Reg, Update)>,
+  /// Registers the loop only uses as scratch space.
+  pub temporaries: Vec<Reg>,
+  /// Loop-invariant branch decisions every pass takes.
+  pub guards: Vec<Guard>,
+  /// Polynomials that must be positive (inner loops run at least once).
+  pub preconditions: Vec<Poly>,
+}
+
+impl AddLoop {
+  /// Recognizes the idiom from the paths through one pass of `range`.
+  #[must_use]
+  pub fn recognize(range: Loop, paths: &[Path], registers: usize) -> Option<Self> {
+    let (passes, leaves, exit) = partition(paths)?;
+    let ([pass], [leave]) = (&passes[..], &leaves[..]) else {
+      return None;
+    };
+    if pass.walk.values != leave.walk.values || pass.walk.preconditions != leave.walk.preconditions
+    {
+      return None;
+    }
+
+    let candidates = (0..registers)
+      .map(Reg)
+      .flat_map(|reg| [(reg, -1), (reg, 1)]);
+    let (counter, step, decision) = candidates.into_iter().find_map(|(counter, step)| {
+      let next = Poly::reg(counter).checked_add(&Poly::constant(step))?;
+      let next = pass.walk.poly(counter).filter(|value| **value == next)?;
+      let decision = pass.walk.guards.iter().find(|guard| {
+        !guard.holds
+          && matches!(&guard.test, Test::Zero(poly)
+            if poly == next || poly.checked_neg().as_ref() == Some(next))
+      })?;
+      Some((counter, step, decision.clone()))
+    })?;
+
+    let guards = pass
+      .walk
+      .guards
+      .iter()
+      .filter(|guard| **guard != decision)
+      .cloned()
+      .collect::<Vec<_>>();
+    let leaving = guards
+      .iter()
+      .cloned()
+      .chain([decision.negated()])
+      .collect::<HashSet<_>>();
+    if leave.walk.guards.len() != leaving.len()
+      || !leave
+        .walk
+        .guards
+        .iter()
+        .all(|guard| leaving.contains(guard))
+    {
+      return None;
+    }
+
+    let invariants = invariants(paths, registers);
+    let mut updates = Vec::new();
+    let mut temporaries = Vec::new();
+    for reg in (0..registers).map(Reg) {
+      if reg == counter || invariants.contains(&reg) {
+        continue;
+      }
+      match &pass.walk.values[reg.0] {
+        Value::Poly(poly) => match Update::classify(reg, poly) {
+          Some(update) if within(update.poly(), &invariants) => updates.push((reg, update)),
+          _ if !poly.mentions(reg) => temporaries.push(reg),
+          _ => return None,
+        },
+        Value::Test(test) if !test.poly().mentions(reg) => temporaries.push(reg),
+        Value::Unknown => temporaries.push(reg),
+        Value::Test(_) => return None,
+      }
+    }
+
+    let scratch_read = temporaries
+      .iter()
+      .any(|&reg| pass.walk.values.iter().any(|value| value.mentions(reg)));
+    let invariant = guards
+      .iter()
+      .map(|guard| guard.test.poly())
+      .chain(&pass.walk.preconditions)
+      .all(|poly| within(poly, &invariants));
+    if scratch_read || !invariant {
+      return None;
+    }
+
+    Some(Self {
+      range,
+      exit,
+      counter,
+      step,
+      updates,
+      temporaries,
+      guards,
+      preconditions: pass.walk.preconditions.clone(),
+    })
+  }
+
+  /// Skips all but the final pass.
+  pub fn apply(&self, registers: &mut Registers) -> bool {
+    let value = |reg: Reg| registers[reg];
+    let Some(passes) = value(self.counter)
+      .checked_mul(-self.step)
+      .filter(|&passes| passes >= 2)
+    else {
+      return false;
+    };
+    let allowed = self
+      .guards
+      .iter()
+      .all(|guard| guard.eval(value) == Some(true))
+      && self
+        .preconditions
+        .iter()
+        .all(|poly| poly.eval(value).is_some_and(|value| value > 0));
+    let changes = self
+      .updates
+      .iter()
+      .map(|(reg, update)| Some((*reg, update.resolve(*reg, passes - 1, value)?)))
+      .collect::<Option<Vec<_>>>();
+    let Some(changes) = changes.filter(|_| allowed) else {
+      return false;
+    };
+
+    registers[self.counter] = -self.step;
+    for (reg, value) in changes {
+      registers[reg] = value;
+    }
+    true
+  }
+
+  /// Applies the whole loop to a walk through an enclosing loop.
+  ///
+  /// Returns `None` if the result cannot be expressed symbolically.
+  pub fn summarize(&self, walk: &mut Walk) -> Option<()> {
+    let entry = walk.clone();
+    let passes = entry
+      .poly(self.counter)?
+      .checked_mul(&Poly::constant(-self.step))?;
+
+    for guard in &self.guards {
+      let test = guard.test.try_map(|poly| entry.substitute(poly))?;
+      if !walk.assume(Guard {
+        test,
+        holds: guard.holds,
+      }) {
+        return None;
+      }
+    }
+    for precondition in &self.preconditions {
+      walk.preconditions.push(entry.substitute(precondition)?);
+    }
+    walk.preconditions.push(passes.clone());
+
+    for (reg, update) in &self.updates {
+      let value = match update {
+        Update::Add(delta) => entry
+          .poly(*reg)?
+          .checked_add(&passes.checked_mul(&entry.substitute(delta)?)?)?,
+        Update::Set(poly) => entry.substitute(poly)?,
+      };
+      walk.values[reg.0] = Value::Poly(value);
+    }
+    walk.values[self.counter.0] = Value::Poly(Poly::default());
+    for reg in &self.temporaries {
+      walk.values[reg.0] = Value::Unknown;
+    }
+    Some(())
+  }
+
+  fn describe(&self, names: &[&str]) -> String {
+    let passes = Poly::reg(self.counter)
+      .checked_mul(&Poly::constant(-self.step))
+      .unwrap_or_default();
+    let mut parts = self
+      .updates
+      .iter()
+      .map(|(reg, update)| match update {
+        Update::Add(delta) => {
+          let total = passes.checked_mul(delta).unwrap_or_default();
+          Update::Add(total).describe(*reg, names)
+        }
+        Update::Set(_) => update.describe(*reg, names),
+      })
+      .collect::<Vec<_>>();
+    parts.push(format!("{} = 0", name(self.counter, names)));
+    format!("add loop: {}", parts.join(", "))
+  }
+}
+
+/// A loop that steps `counter` up to `last` and does something once when
+/// `factor * counter == product`: a slow divisibility test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivisorProbe {
+  pub range: Loop,
+  /// Where the loop exits to.
+  pub exit: i64,
+  pub counter: Reg,
+  /// Counter value on the final pass.
+  pub last: Poly,
+  pub factor: Reg,
+  pub product: Poly,
+  /// What a hit does.
+  pub action: (Reg, Update),
+  /// Registers the loop only uses as scratch space.
+  pub temporaries: Vec<Reg>,
+}
+
+impl DivisorProbe {
+  /// Recognizes the idiom from the paths through one pass of `range`.
+  #[must_use]
+  pub fn recognize(range: Loop, paths: &[Path], registers: usize) -> Option<Self> {
+    let (passes, leaves, exit) = partition(paths)?;
+    if passes.len() != 2 || leaves.len() != 2 {
+      return None;
+    }
+    let counter = counter(paths, registers, 1)?;
+    let (bound, (probe, factor, product)) = Self::tests(&paths[0].walk.guards, counter)?;
+
+    let decision = |path: &Path, test: &Test| {
+      let [first, second] = &path.walk.guards[..] else {
+        return None;
+      };
+      [first, second]
+        .into_iter()
+        .find(|guard| guard.test == *test)
+        .map(|guard| guard.holds)
+    };
+    let going_on = decision(passes[0], &bound)?;
+    let consistent = paths.iter().all(|path| {
+      decision(path, &bound) == Some((path.end == End::Continue) == going_on)
+        && decision(path, &probe).is_some()
+    });
+    if !consistent {
+      return None;
+    }
+    let last = last_pass(
+      &Guard {
+        test: bound,
+        holds: going_on,
+      },
+      counter,
+    )?;
+
+    let invariants = invariants(paths, registers);
+    let (hits, misses): (Vec<_>, Vec<_>) = paths
+      .iter()
+      .partition(|path| decision(path, &probe) == Some(true));
+    if hits.len() != 2 || misses.len() != 2 {
+      return None;
+    }
+    let mut action = None;
+    let mut temporaries = Vec::new();
+    for reg in (0..registers).map(Reg) {
+      if reg == counter || invariants.contains(&reg) {
+        continue;
+      }
+      let untouched = misses
+        .iter()
+        .all(|path| path.walk.poly(reg) == Some(&Poly::reg(reg)));
+      let hit = hits[0].walk.poly(reg).filter(|_| {
+        hits
+          .iter()
+          .all(|path| path.walk.values[reg.0] == hits[0].walk.values[reg.0])
+      });
+      match (untouched, hit.and_then(|hit| Update::classify(reg, hit))) {
+        (true, Some(update)) if action.is_none() && within(update.poly(), &invariants) => {
+          action = Some((reg, update));
+        }
+        (false, _)
+          if paths
+            .iter()
+            .all(|path| !path.walk.values[reg.0].mentions(reg)) =>
+        {
+          temporaries.push(reg);
+        }
+        _ => return None,
+      }
+    }
+
+    let scratch_read = temporaries.iter().any(|&reg| {
+      paths
+        .iter()
+        .any(|path| path.walk.values.iter().any(|value| value.mentions(reg)))
+    });
+    let invariant =
+      invariants.contains(&factor) && within(&last, &invariants) && within(&product, &invariants);
+    if scratch_read || !invariant {
+      return None;
+    }
+
+    Some(Self {
+      range,
+      exit,
+      counter,
+      last,
+      factor,
+      product,
+      action: action?,
+      temporaries,
+    })
+  }
+
+  /// Finds the loop bound test and the `factor * counter == product` test.
+  fn tests(guards: &[Guard], counter: Reg) -> Option<(Test, (Test, Reg, Poly))> {
+    let mut bound = None;
+    let mut probe = None;
+    for guard in guards {
+      let (coefficient, rest) = guard.test.poly().split(counter)?;
+      if coefficient.as_constant().is_some_and(|c| c.abs() == 1) {
+        bound = Some(guard.test.clone());
+      } else if let (Test::Zero(_), [factor]) = (
+        &guard.test,
+        &coefficient.registers().into_iter().collect::<Vec<_>>()[..],
+      ) {
+        let sign = coefficient
+          .split(*factor)?
+          .0
+          .as_constant()
+          .filter(|c| c.abs() == 1)?;
+        let product = if sign == 1 { rest.checked_neg()? } else { rest };
+        probe = Some((guard.test.clone(), *factor, product));
+      }
+    }
+    bound.zip(probe)
+  }
+
+  /// Skips to the final pass, applying the action if a skipped pass hit.
+  pub fn apply(&self, registers: &mut Registers) -> bool {
+    let value = |reg: Reg| registers[reg];
+    let first = value(self.counter);
+    let (Some(last), Some(product)) = (self.last.eval(value), self.product.eval(value)) else {
+      return false;
+    };
+    let factor = value(self.factor);
+    if first >= last || factor == 0 {
+      return false;
+    }
+
+    let hit = product.checked_rem(factor) == Some(0)
+      && product
+        .checked_div(factor)
+        .is_some_and(|quotient| (first..last).contains(&quotient));
+    let (reg, update) = &self.action;
+    let change = if hit {
+      match update.resolve(*reg, 1, value) {
+        Some(value) => Some(value),
+        None => return false,
+      }
+    } else {
+      None
+    };
+
+    registers[self.counter] = last;
+    if let Some(value) = change {
+      registers[*reg] = value;
+    }
+    true
+  }
+
+  /// Walk-level effect of the whole loop, for recognizing a `DivisorSweep`
+  /// around it: everything the loop touches becomes unknown.
+  pub fn summarize(&self, walk: &mut Walk) {
+    for reg in self
+      .temporaries
+      .iter()
+      .chain([&self.counter, &self.action.0])
+    {
+      walk.values[reg.0] = Value::Unknown;
+    }
+  }
+
+  fn describe(&self, names: &[&str]) -> String {
+    let (reg, update) = &self.action;
+    format!(
+      "divisor probe: {} if {} * k == {} for some k in {}..={}",
+      update.describe(*reg, names),
+      name(self.factor, names),
+      self.product.display(names),
+      name(self.counter, names),
+      self.last.display(names),
+    )
+  }
+}
+
+/// A loop that runs a `DivisorProbe` for every value of its factor in a
+/// range: a slow divisor sum or primality test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivisorSweep {
+  pub range: Loop,
+  /// Where the loop exits to.
+  pub exit: i64,
+  /// The probe's factor, stepped by one.
+  pub counter: Reg,
+  /// Counter value on the final pass.
+  pub last: Poly,
+  /// Probe counter range on every pass.
+  pub start: Poly,
+  pub finish: Poly,
+  pub product: Poly,
+  /// What a hit does, in terms of the sweep's registers.
+  pub action: (Reg, Update),
+  /// Registers the loop only uses as scratch space, including the probe's.
+  pub temporaries: Vec<Reg>,
+  pub probe: DivisorProbe,
+}
+
+impl DivisorSweep {
+  /// Recognizes the idiom from the paths through one pass of `range`, where
+  /// `at_probe` is the walk as it reached `probe`'s head.
+  #[must_use]
+  pub fn recognize(
+    range: Loop,
+    paths: &[Path],
+    registers: usize,
+    probe: &DivisorProbe,
+    at_probe: &Walk,
+  ) -> Option<Self> {
+    let (passes, leaves, exit) = partition(paths)?;
+    let ([pass], [leave]) = (&passes[..], &leaves[..]) else {
+      return None;
+    };
+    let counter = counter(paths, registers, 1)?;
+    let ([going_on], [leaving]) = (&pass.walk.guards[..], &leave.walk.guards[..]) else {
+      return None;
+    };
+    if *leaving != going_on.negated()
+      || pass.walk.values != leave.walk.values
+      || !pass.walk.preconditions.is_empty()
+      || counter != probe.factor
+      || at_probe.poly(counter) != Some(&Poly::reg(counter))
+    {
+      return None;
+    }
+    let last = last_pass(going_on, counter)?;
+
+    let (reg, update) = &probe.action;
+    let start = at_probe.poly(probe.counter)?.clone();
+    let finish = at_probe.substitute(&probe.last)?;
+    let product = at_probe.substitute(&probe.product)?;
+    let action = update.try_map(|poly| at_probe.substitute(poly))?;
+    if at_probe.poly(*reg) != Some(&Poly::reg(*reg)) {
+      return None;
+    }
+
+    let invariants = invariants(paths, registers);
+    let mut factors = invariants.clone();
+    factors.insert(counter);
+    let invariant = [&last, &start, &finish, &product]
+      .into_iter()
+      .all(|poly| within(poly, &invariants))
+      && match &action {
+        Update::Add(delta) => within(delta, &factors),
+        Update::Set(poly) => within(poly, &invariants),
+      };
+    let scratch_read = (0..registers)
+      .map(Reg)
+      .filter(|reg| !factors.contains(reg))
+      .any(|reg| pass.walk.values.iter().any(|value| value.mentions(reg)));
+    if !invariant || scratch_read {
+      return None;
+    }
+    let temporaries = (0..registers)
+      .map(Reg)
+      .filter(|&other| other != counter && other != *reg)
+      .filter(|other| pass.walk.poly(*other) != Some(&Poly::reg(*other)))
+      .collect();
+
+    Some(Self {
+      range,
+      exit,
+      counter,
+      last,
+      start,
+      finish,
+      product,
+      action: (*reg, action),
+      temporaries,
+      probe: probe.clone(),
+    })
+  }
+
+  /// Skips to the final pass, applying the action for every hit on the way.
+  pub fn apply(&self, registers: &mut Registers) -> bool {
+    let value = |reg: Reg| registers[reg];
+    let first = value(self.counter);
+    let evaluated =
+      [&self.last, &self.start, &self.finish, &self.product].map(|poly| poly.eval(value));
+    let [Some(last), Some(start), Some(finish), Some(product)] = evaluated else {
+      return false;
+    };
+    if first >= last || start < 1 || start > finish || product < 1 {
+      return false;
+    }
+
+    let mut hits = (1..=product.isqrt())
+      .filter(|divisor| product % divisor == 0)
+      .flat_map(|divisor| [divisor, product / divisor])
+      .filter(|&factor| (first..last).contains(&factor))
+      .filter(|&factor| (start..=finish).contains(&(product / factor)))
+      .collect::<Vec<_>>();
+    hits.sort_unstable();
+    hits.dedup();
+
+    let (reg, update) = &self.action;
+    let mut result = value(*reg);
+    for &factor in &hits {
+      let value = |r: Reg| {
+        if r == *reg {
+          result
+        } else if r == self.counter {
+          factor
+        } else {
+          value(r)
+        }
+      };
+      match update.resolve(*reg, 1, value) {
+        Some(next) => result = next,
+        None => return false,
+      }
+    }
+
+    registers[self.counter] = last;
+    registers[*reg] = result;
+    true
+  }
+
+  /// Walk-level effect of the whole loop, for recognizing a
+  /// `CompositeCount` around it.
+  ///
+  /// Only sweeps that set a flag to a constant on a hit qualify. The flag
+  /// becomes a polynomial in `found`, a virtual register that is `1` if the
+  /// sweep finds a factor pair and `0` otherwise; everything else the loop
+  /// touches becomes unknown.
+  pub fn summarize(&self, walk: &mut Walk, found: Reg) -> Option<()> {
+    let (flag, Update::Set(value)) = &self.action else {
+      return None;
+    };
+    value.as_constant()?;
+    let before = walk.poly(*flag)?.clone();
+    let after = value
+      .checked_sub(&before)?
+      .checked_mul(&Poly::reg(found))?
+      .checked_add(&before)?;
+
+    walk.values[flag.0] = Value::Poly(after);
+    for reg in self.temporaries.iter().chain([&self.counter]) {
+      walk.values[reg.0] = Value::Unknown;
+    }
+    Some(())
+  }
+
+  fn describe(&self, names: &[&str]) -> String {
+    let (reg, update) = &self.action;
+    let counter = name(self.counter, names);
+    format!(
+      "divisor sweep: {} for every {counter} in {counter}..={} with {counter} * k == {} for \
+       some k in {}..={}",
+      update.describe(*reg, names),
+      self.last.display(names),
+      self.product.display(names),
+      self.start.display(names),
+      self.finish.display(names),
+    )
+  }
+}
+
+/// Counting the composite numbers in an arithmetic progression.
+///
+/// The loop steps `counter` by a constant up to `last` and bumps a tally
+/// whenever a nested `DivisorSweep` finds a factor pair of `product`.
+///
+/// # Examples
+///
+/// 2017/23 counts the composites among 1001 numbers with three nested loops:
+///
+/// ```
+/// use machine::{
+///   analysis::{
+///     Accelerator,
+///     Idiom,
+///   },
+///   dialect::Duet,
+///   prelude::*,
+/// };
+///
+/// let source = "set b 57\nset c b\njnz a 2\njnz 1 5\nmul b 100\nsub b -100000\nset c b
+/// sub c -17000\nset f 1\nset d 2\nset e 2\nset g d\nmul g e\nsub g b\njnz g 2\nset f 0
+/// sub e -1\nset g e\nsub g b\njnz g -8\nsub d -1\nset g d\nsub g b\njnz g -13\njnz f 2
+/// sub h -1\nset g b\nsub g c\njnz g 2\njnz 1 3\nsub b -17\njnz 1 -23";
+///
+/// let mut machine = Machine::<Duet>::parse(source).unwrap().with_register("a", 1);
+/// let mut accelerator = Accelerator::new(&machine);
+/// assert!(
+///   accelerator
+///     .idioms()
+///     .any(|idiom| matches!(idiom, Idiom::CompositeCount(_)))
+/// );
+///
+/// assert_eq!(accelerator.run(&mut machine), Halt::Finished);
+/// assert_eq!(machine.register("h"), Some(915));
+/// assert!(machine.steps < 100);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeCount {
+  pub range: Loop,
+  /// Where the loop exits to.
+  pub exit: i64,
+  pub counter: Reg,
+  pub step: i64,
+  /// Counter value on the final pass, which leaves the loop.
+  pub last: Poly,
+  /// The sweep's factor range on every pass.
+  pub factors: (Poly, Poly),
+  /// The probe's cofactor range on every pass.
+  pub cofactors: (Poly, Poly),
+  pub product: Poly,
+  /// What a pass with a factor pair does.
+  pub action: (Reg, Update),
+  pub sweep: DivisorSweep,
+}
+
+impl CompositeCount {
+  /// Recognizes the idiom from the paths through one pass of `range`, where
+  /// `at_sweep` is the walk as it reached `sweep`'s head and the sweep's
+  /// outcome is the virtual register just past the real ones.
+  #[must_use]
+  pub fn recognize(
+    range: Loop,
+    paths: &[Path],
+    registers: usize,
+    sweep: &DivisorSweep,
+    at_sweep: &Walk,
+  ) -> Option<Self> {
+    let found = Reg(registers);
+    let (passes, leaves, exit) = partition(paths)?;
+    if passes.len() != 2 || leaves.len() != 2 {
+      return None;
+    }
+
+    // Every path decides once on the sweep's outcome and once on the bound.
+    let outcome = |path: &Path| {
+      let [first, second] = &path.walk.guards[..] else {
+        return None;
+      };
+      let (decision, bound) = if first.test.poly().mentions(found) {
+        (first, second)
+      } else {
+        (second, first)
+      };
+      if decision.test.poly().registers() != BTreeSet::from([found]) {
+        return None;
+      }
+      let with = |hit| decision.eval(|reg| i64::from(reg == found && hit));
+      let hit = with(true)?;
+      (hit != with(false)?).then(|| (hit, bound.clone()))
+    };
+    let outcomes = paths.iter().map(outcome).collect::<Option<Vec<_>>>()?;
+    let hits = outcomes.iter().filter(|(hit, _)| *hit).count();
+    let bound = &outcomes[0].1;
+    if hits != 2 || bound.test.poly().mentions(found) {
+      return None;
+    }
+
+    let invariants = invariants(paths, registers);
+    let (counter, step) = (0..registers).map(Reg).find_map(|reg| {
+      let step = passes[0]
+        .walk
+        .poly(reg)?
+        .checked_sub(&Poly::reg(reg))?
+        .as_constant()?;
+      let next = Poly::reg(reg).checked_add(&Poly::constant(step))?;
+      let stepped = passes.iter().all(|path| path.walk.poly(reg) == Some(&next));
+      let kept = leaves
+        .iter()
+        .all(|path| path.walk.poly(reg) == Some(&Poly::reg(reg)));
+      (step != 0 && stepped && kept).then_some((reg, step))
+    })?;
+    // The loop leaves on the pass where the counter reaches `last`.
+    let consistent = paths.iter().zip(&outcomes).all(|(path, (_, guard))| {
+      guard.test == bound.test && guard.holds == (path.end != End::Continue)
+    });
+    let (coefficient, rest) = bound.test.poly().split(counter)?;
+    let last = match (&bound.test, coefficient.as_constant()) {
+      (Test::Zero(_), Some(1)) => rest.checked_neg()?,
+      (Test::Zero(_), Some(-1)) => rest,
+      _ => return None,
+    };
+    if !consistent || !within(&last, &invariants) {
+      return None;
+    }
+
+    let hits = outcomes.iter().map(|(hit, _)| *hit).collect::<Vec<_>>();
+    let action = Self::action(paths, &hits, counter, &invariants, registers)?;
+
+    let factors = (
+      at_sweep.poly(sweep.counter)?.clone(),
+      at_sweep.substitute(&sweep.last)?,
+    );
+    let cofactors = (
+      at_sweep.substitute(&sweep.start)?,
+      at_sweep.substitute(&sweep.finish)?,
+    );
+    let product = at_sweep.substitute(&sweep.product)?;
+    let mut allowed = invariants;
+    allowed.insert(counter);
+    let invariant = [&factors.0, &factors.1, &cofactors.0, &cofactors.1, &product]
+      .into_iter()
+      .all(|poly| within(poly, &allowed));
+    if !invariant {
+      return None;
+    }
+
+    Some(Self {
+      range,
+      exit,
+      counter,
+      step,
+      last,
+      factors,
+      cofactors,
+      product,
+      action,
+      sweep: sweep.clone(),
+    })
+  }
+
+  /// Finds the single register a hit bumps by an invariant amount; every
+  /// other changing register must be scratch space the loop never reads
+  /// back.
+  fn action(
+    paths: &[Path],
+    hits: &[bool],
+    counter: Reg,
+    invariants: &BTreeSet<Reg>,
+    registers: usize,
+  ) -> Option<(Reg, Update)> {
+    let mut action = None;
+    let mut temporaries = Vec::new();
+    for reg in (0..registers).map(Reg) {
+      if reg == counter || invariants.contains(&reg) {
+        continue;
+      }
+      let unchanged = paths
+        .iter()
+        .zip(hits)
+        .all(|(path, hit)| *hit || path.walk.poly(reg) == Some(&Poly::reg(reg)));
+      let updates = paths
+        .iter()
+        .zip(hits)
+        .filter(|(_, hit)| **hit)
+        .map(|(path, _)| {
+          path
+            .walk
+            .poly(reg)
+            .and_then(|poly| Update::classify(reg, poly))
+        })
+        .collect::<HashSet<_>>();
+      match (unchanged, &updates.into_iter().collect::<Vec<_>>()[..]) {
+        (true, [Some(update @ Update::Add(delta))])
+          if action.is_none() && within(delta, invariants) =>
+        {
+          action = Some((reg, update.clone()));
+        }
+        _ if paths
+          .iter()
+          .all(|path| !path.walk.values[reg.0].mentions(reg)) =>
+        {
+          temporaries.push(reg);
+        }
+        _ => return None,
+      }
+    }
+    let scratch_read = temporaries.iter().any(|&reg| {
+      paths
+        .iter()
+        .any(|path| path.walk.values.iter().any(|value| value.mentions(reg)))
+    });
+    if scratch_read {
+      return None;
+    }
+    action
+  }
+
+  /// Skips to the final pass, applying the action once for every skipped
+  /// counter value that has a factor pair.
+  pub fn apply(&self, registers: &mut Registers) -> bool {
+    let value = |reg: Reg| registers[reg];
+    let first = value(self.counter);
+    let Some(last) = self.last.eval(value) else {
+      return false;
+    };
+    let Some(passes) = last
+      .checked_sub(first)
+      .filter(|span| span % self.step == 0)
+      .map(|span| span / self.step)
+      .filter(|&passes| passes >= 1)
+    else {
+      return false;
+    };
+
+    let mut hits = 0;
+    for pass in 0..passes {
+      let Some(current) = pass
+        .checked_mul(self.step)
+        .and_then(|offset| first.checked_add(offset))
+      else {
+        return false;
+      };
+      let value = |reg: Reg| {
+        if reg == self.counter {
+          current
+        } else {
+          value(reg)
+        }
+      };
+      let evaluated = [
+        &self.factors.0,
+        &self.factors.1,
+        &self.cofactors.0,
+        &self.cofactors.1,
+        &self.product,
+      ]
+      .map(|poly| poly.eval(value));
+      let [
+        Some(low),
+        Some(high),
+        Some(start),
+        Some(finish),
+        Some(product),
+      ] = evaluated
+      else {
+        return false;
+      };
+      // The real loops run at least once, which ranges cannot express.
+      if low > high || start > finish || product < 1 {
+        return false;
+      }
+      hits += i64::from(has_factor_pair(product, low..=high, start..=finish));
+    }
+
+    let (reg, update) = &self.action;
+    let Some(result) = update.resolve(*reg, hits, value) else {
+      return false;
+    };
+    registers[self.counter] = last;
+    registers[*reg] = result;
+    true
+  }
+
+  fn describe(&self, names: &[&str]) -> String {
+    let (reg, update) = &self.action;
+    let counter = name(self.counter, names);
+    format!(
+      "composite count: {} for every {counter} in {counter}..={} by {} with d * k == {} for \
+       some d in {}..={}, k in {}..={}",
+      update.describe(*reg, names),
+      self.last.display(names),
+      self.step,
+      self.product.display(names),
+      self.factors.0.display(names),
+      self.factors.1.display(names),
+      self.cofactors.0.display(names),
+      self.cofactors.1.display(names),
+    )
+  }
+}
+
+/// Whether `product` is `factor * cofactor` with `factor` in `factors` and
+/// `cofactor` in `cofactors`.
+fn has_factor_pair(
+  product: i64,
+  factors: RangeInclusive<i64>,
+  cofactors: RangeInclusive<i64>,
+) -> bool {
+  (1..=product.isqrt())
+    .filter(|divisor| product % divisor == 0)
+    .any(|divisor| {
+      let pair = product / divisor;
+      (factors.contains(&divisor) && cofactors.contains(&pair))
+        || (factors.contains(&pair) && cofactors.contains(&divisor))
+    })
+}
@@ -0,0 +1,195 @@
+//! Dialect-independent view of what an instruction does.
+//!
+//! Dialects opt into analysis by implementing `Instruction::effect`, mapping
+//! each instruction onto one of a handful of `Effect`s. Anything the analyzer
+//! should not reason about (I/O, self-modification) stays `Effect::Opaque`.
+//!
+//! Dialects that bind the instruction pointer to a register express jumps as
+//! plain register writes; `lower` turns those writes back into jumps once the
+//! binding and the instruction's address are known.
+
+use crate::_prelude::*;
+
+/// Binary operators understood by the analyzer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Mod,
+  And,
+  Or,
+  Xor,
+  Shr,
+  /// `1` if the left operand is greater, `0` otherwise.
+  Gt,
+  /// `1` if both operands are equal, `0` otherwise.
+  Eq,
+}
+
+impl BinOp {
+  /// Evaluates the operator on concrete values.
+  #[must_use]
+  pub fn eval(self, lhs: i64, rhs: i64) -> Option<i64> {
+    match self {
+      Self::Add => lhs.checked_add(rhs),
+      Self::Sub => lhs.checked_sub(rhs),
+      Self::Mul => lhs.checked_mul(rhs),
+      Self::Div => lhs.checked_div(rhs),
+      Self::Mod => lhs.checked_rem(rhs),
+      Self::And => Some(lhs & rhs),
+      Self::Or => Some(lhs | rhs),
+      Self::Xor => Some(lhs ^ rhs),
+      Self::Shr => u32::try_from(rhs)
+        .ok()
+        .map(|by| lhs.checked_shr(by).unwrap_or(0)),
+      Self::Gt => Some(i64::from(lhs > rhs)),
+      Self::Eq => Some(i64::from(lhs == rhs)),
+    }
+  }
+
+  /// Infix symbol used by the pseudo-code printer.
+  #[must_use]
+  pub const fn symbol(self) -> &'static str {
+    match self {
+      Self::Add => "+",
+      Self::Sub => "-",
+      Self::Mul => "*",
+      Self::Div => "/",
+      Self::Mod => "%",
+      Self::And => "&",
+      Self::Or => "|",
+      Self::Xor => "^",
+      Self::Shr => ">>",
+      Self::Gt => ">",
+      Self::Eq => "==",
+    }
+  }
+}
+
+/// Right-hand side of an assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expr {
+  Value(Operand),
+  Binary(BinOp, Operand, Operand),
+}
+
+/// When a jump is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Condition {
+  Always,
+  NonZero(Operand),
+  Positive(Operand),
+}
+
+/// Where a jump goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+  /// Offset from the jumping instruction.
+  Relative(Operand),
+  /// Instruction index.
+  Absolute(i64),
+}
+
+/// What a single instruction does, as far as the analyzer is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Effect {
+  /// Writes the expression's value to the register.
+  Assign(Reg, Expr),
+  /// Transfers control when the condition holds.
+  Jump(Condition, Target),
+  /// Does nothing.
+  Nop,
+  /// Does something the analyzer does not model.
+  Opaque,
+}
+
+/// An effect after instruction-pointer lowering, tied to its address.
+///
+/// Jumps carry resolved successor addresses where they are statically known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lowered {
+  Assign(Reg, Expr),
+  /// Conditional or unconditional jump to a known address.
+  Jump(Condition, i64),
+  /// Jump whose target depends on runtime values.
+  Computed,
+  Nop,
+  Opaque,
+}
+
+impl Lowered {
+  /// Statically known successor addresses of the instruction at `pc`.
+  ///
+  /// `None` means the successors cannot be determined.
+  #[must_use]
+  pub fn successors(self, pc: i64) -> Option<Vec<i64>> {
+    match self {
+      Self::Jump(Condition::Always, target) => Some(vec![target]),
+      Self::Jump(_, target) => Some(vec![pc + 1, target]),
+      Self::Computed => None,
+      Self::Assign(..) | Self::Nop | Self::Opaque => Some(vec![pc + 1]),
+    }
+  }
+}
+
+/// Lowers the effect of the instruction at `pc`.
+///
+/// With an instruction-pointer binding, writes to the bound register become
+/// jumps: constant expressions (the register holds `pc` while executing) are
+/// folded into direct jumps, and `ip = ip + flag` becomes a conditional skip
+/// of the next instruction. The latter assumes `flag` is a comparison result,
+/// as it is in every puzzle input.
+#[must_use]
+pub fn lower(effect: Effect, pc: i64, ip_binding: Option<Reg>) -> Lowered {
+  let resolve = |operand: Operand| match operand {
+    Operand::Reg(reg) if Some(reg) == ip_binding => Operand::Imm(pc),
+    other => other,
+  };
+
+  match effect {
+    Effect::Assign(dst, expr) if Some(dst) == ip_binding => match expr {
+      Expr::Value(operand) => match resolve(operand) {
+        Operand::Imm(value) => Lowered::Jump(Condition::Always, value + 1),
+        Operand::Reg(_) => Lowered::Computed,
+      },
+      Expr::Binary(op, lhs, rhs) => match (op, resolve(lhs), resolve(rhs)) {
+        (op, Operand::Imm(lhs), Operand::Imm(rhs)) => {
+          op.eval(lhs, rhs).map_or(Lowered::Computed, |value| {
+            Lowered::Jump(Condition::Always, value + 1)
+          })
+        }
+        (BinOp::Add, Operand::Imm(base), flag @ Operand::Reg(_))
+        | (BinOp::Add, flag @ Operand::Reg(_), Operand::Imm(base))
+          if base == pc =>
+        {
+          Lowered::Jump(Condition::NonZero(flag), pc + 2)
+        }
+        _ => Lowered::Computed,
+      },
+    },
+    Effect::Assign(dst, Expr::Value(operand)) => {
+      Lowered::Assign(dst, Expr::Value(resolve(operand)))
+    }
+    Effect::Assign(dst, Expr::Binary(op, lhs, rhs)) => {
+      Lowered::Assign(dst, Expr::Binary(op, resolve(lhs), resolve(rhs)))
+    }
+    Effect::Jump(condition, Target::Absolute(target)) => Lowered::Jump(condition, target),
+    Effect::Jump(condition, Target::Relative(Operand::Imm(offset))) => {
+      Lowered::Jump(condition, pc + offset)
+    }
+    Effect::Jump(_, Target::Relative(Operand::Reg(_))) => Lowered::Computed,
+    Effect::Nop => Lowered::Nop,
+    Effect::Opaque => Lowered::Opaque,
+  }
+}
+
+/// Lowers every instruction of a program.
+#[must_use]
+pub fn lower_program<I: Instruction>(program: &[I], ip_binding: Option<Reg>) -> Vec<Lowered> {
+  (0_i64..)
+    .zip(program)
+    .map(|(pc, instruction)| lower(instruction.effect(), pc, ip_binding))
+    .collect()
+}
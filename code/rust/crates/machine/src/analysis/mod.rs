@@ -0,0 +1,122 @@
+//! Static analysis, loop acceleration and debugging.
+//!
+//! Several puzzles hand over a program that is hopeless to interpret
+//! naively: 2016/23 multiplies by repeated increment, 2017/23 and 2018/19
+//! test divisibility by trying every candidate product, and 2017/23 counts
+//! the composites it finds that way. The analysis pipeline turns such
+//! programs back into something readable and fast:
+//!
+//! 1. `Instruction::effect` describes each instruction in a small IR, which
+//!    `lower` specializes per address (folding `#ip` writes into jumps).
+//! 2. `Cfg` splits the result into basic blocks and finds loops.
+//! 3. `explore` walks each loop body symbolically; `Idiom` recognizers match
+//!    the resulting paths against known shapes.
+//! 4. `Accelerator` skips recognized loops at runtime, `Analysis::pseudocode`
+//!    prints the program with the idioms spelled out, and `Debugger` adds
+//!    breakpoints and register watches on top.
+//!
+//! 2024/17 needs a search rather than a faster run; see
+//! `Chronospatial::quine`.
+//!
+//! # Examples
+//!
+//! ```
+//! use machine::{
+//!   analysis::{
+//!     Accelerator,
+//!     Analysis,
+//!   },
+//!   dialect::Assembunny,
+//!   prelude::*,
+//! };
+//!
+//! let source = "cpy 12345 d
+//! cpy 6789 b
+//! cpy b c
+//! inc a
+//! dec c
+//! jnz c -2
+//! dec d
+//! jnz d -5";
+//!
+//! let mut machine = Machine::<Assembunny>::parse(source).unwrap();
+//! let analysis = Analysis::of(&machine);
+//! assert_eq!(analysis.idioms.len(), 2);
+//!
+//! let mut accelerator = Accelerator::new(&machine);
+//! assert_eq!(accelerator.run(&mut machine), Halt::Finished);
+//! assert_eq!(machine.register("a"), Some(12345 * 6789));
+//! assert!(machine.steps < 100);
+//! ```
+
+mod accelerate;
+mod cfg;
+mod debug;
+mod idiom;
+mod ir;
+mod pseudo;
+mod symbolic;
+
+use crate::_prelude::*;
+pub use {
+  accelerate::*,
+  cfg::*,
+  debug::*,
+  idiom::*,
+  ir::*,
+  pseudo::*,
+  symbolic::*,
+};
+
+/// Formats a register for humans; numeric names (`ElfCode`) get an `r`
+/// prefix so they cannot be mistaken for literals.
+fn name(reg: Reg, names: &[&str]) -> String {
+  match names.get(reg.0) {
+    Some(name) if name.starts_with(|c: char| c.is_ascii_digit()) => format!("r{name}"),
+    Some(name) => (*name).to_owned(),
+    None => format!("r{}", reg.0),
+  }
+}
+
+/// Everything the analyzer knows about a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+  /// Register names of the analyzed dialect.
+  pub names: &'static [&'static str],
+  /// Lowered effect of every instruction.
+  pub lowered: Vec<Lowered>,
+  /// Blocks and loops.
+  pub cfg: Cfg,
+  /// Recognized loops, by head address.
+  pub idioms: BTreeMap<i64, Idiom>,
+}
+
+impl Analysis {
+  /// Analyzes a list of instructions.
+  #[must_use]
+  pub fn new<I: Instruction>(instructions: &[I], ip_binding: Option<Reg>) -> Self {
+    let lowered = lower_program(instructions, ip_binding);
+    let cfg = Cfg::build(&lowered);
+
+    let mut idioms = BTreeMap::new();
+    for &range in &cfg.loops {
+      let idiom = Idiom::recognize(&lowered, range, &cfg.loops, &idioms, I::REGISTERS.len());
+      if let Some(idiom) = idiom {
+        idioms.insert(range.head, idiom);
+      }
+    }
+
+    Self {
+      names: I::REGISTERS,
+      lowered,
+      cfg,
+      idioms,
+    }
+  }
+
+  /// Analyzes the program currently loaded into a machine.
+  #[must_use]
+  pub fn of<I: Instruction>(machine: &Machine<I>) -> Self {
+    Self::new(&machine.program, machine.ip_binding)
+  }
+}
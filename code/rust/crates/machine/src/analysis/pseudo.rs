@@ -0,0 +1,145 @@
+//! C-like pseudo-code for reading puzzle programs.
+//!
+//! Every instruction becomes one statement; jump targets get `L<pc>:`
+//! labels, the original instruction is kept as a trailing comment, and
+//! recognized loops are announced by a comment above their head.
+//!
+//! # Examples
+//!
+//! ```
+//! use machine::{
+//!   analysis::Analysis,
+//!   dialect::Assembunny,
+//!   prelude::*,
+//! };
+//!
+//! let machine = Machine::<Assembunny>::parse("cpy 5 c\ninc a\ndec c\njnz c -2\nout a").unwrap();
+//! let analysis = Analysis::of(&machine);
+//!
+//! assert_eq!(
+//!   analysis.pseudocode(&machine.program).to_string(),
+//!   "      c = 5                        // cpy 5 c
+//!       // add loop: a += c, c = 0
+//! L1:   a += 1                       // inc a
+//!       c -= 1                       // dec c
+//!       if c != 0 goto L1            // jnz c -2
+//!       out a
+//! "
+//! );
+//! ```
+
+use {
+  super::{
+    Analysis,
+    Lowered,
+    name,
+  },
+  crate::_prelude::*,
+};
+
+/// Display adapter returned by `Analysis::pseudocode`.
+#[derive(Debug, Clone, Copy)]
+pub struct Pseudocode<'a, I> {
+  analysis: &'a Analysis,
+  instructions: &'a [I],
+}
+
+impl Analysis {
+  /// Renders the analyzed instructions as pseudo-code.
+  #[must_use]
+  pub const fn pseudocode<'a, I>(&'a self, instructions: &'a [I]) -> Pseudocode<'a, I> {
+    Pseudocode {
+      analysis: self,
+      instructions,
+    }
+  }
+}
+
+impl<I: Display> Pseudocode<'_, I> {
+  fn statement(&self, lowered: Lowered, instruction: &I) -> String {
+    let names = self.analysis.names;
+    let len = self.analysis.lowered.len();
+    let reg = |reg: Reg| name(reg, names);
+    let op = |operand: Operand| match operand {
+      Operand::Reg(r) => reg(r),
+      Operand::Imm(value) => value.to_string(),
+    };
+    let goto = |target: i64| {
+      if usize::try_from(target).is_ok_and(|target| target < len) {
+        format!("goto L{target}")
+      } else {
+        "halt".to_owned()
+      }
+    };
+
+    match lowered {
+      Lowered::Assign(dst, Expr::Value(value)) => format!("{} = {}", reg(dst), op(value)),
+      Lowered::Assign(dst, Expr::Binary(binop, Operand::Reg(lhs), rhs))
+        if lhs == dst && !matches!(binop, BinOp::Gt | BinOp::Eq) =>
+      {
+        match (binop, rhs) {
+          (BinOp::Add, Operand::Imm(value)) if value < 0 => {
+            format!("{} -= {}", reg(dst), value.unsigned_abs())
+          }
+          (BinOp::Sub, Operand::Imm(value)) if value < 0 => {
+            format!("{} += {}", reg(dst), value.unsigned_abs())
+          }
+          _ => format!("{} {}= {}", reg(dst), binop.symbol(), op(rhs)),
+        }
+      }
+      Lowered::Assign(dst, Expr::Binary(binop, lhs, rhs)) => {
+        format!("{} = {} {} {}", reg(dst), op(lhs), binop.symbol(), op(rhs))
+      }
+      Lowered::Jump(Condition::Always, target) => goto(target),
+      Lowered::Jump(Condition::NonZero(Operand::Imm(value)), target) => {
+        if value == 0 {
+          "nop".to_owned()
+        } else {
+          goto(target)
+        }
+      }
+      Lowered::Jump(Condition::NonZero(value), target) => {
+        format!("if {} != 0 {}", op(value), goto(target))
+      }
+      Lowered::Jump(Condition::Positive(value), target) => {
+        format!("if {} > 0 {}", op(value), goto(target))
+      }
+      Lowered::Computed => "goto ?".to_owned(),
+      Lowered::Nop => "nop".to_owned(),
+      Lowered::Opaque => instruction.to_string(),
+    }
+  }
+}
+
+impl<I: Display> Display for Pseudocode<'_, I> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let labels = self
+      .analysis
+      .lowered
+      .iter()
+      .filter_map(|lowered| match lowered {
+        Lowered::Jump(_, target) => Some(*target),
+        _ => None,
+      })
+      .collect::<BTreeSet<_>>();
+
+    let lines = self.analysis.lowered.iter().zip(self.instructions);
+    for (pc, (&lowered, instruction)) in (0..).zip(lines) {
+      if let Some(idiom) = self.analysis.idioms.get(&pc) {
+        writeln!(f, "      // {}", idiom.describe(self.analysis.names))?;
+      }
+      let label = if labels.contains(&pc) {
+        format!("L{pc}:")
+      } else {
+        String::new()
+      };
+      let statement = self.statement(lowered, instruction);
+      if lowered == Lowered::Opaque {
+        writeln!(f, "{label:<6}{statement}")?;
+      } else {
+        writeln!(f, "{label:<6}{statement:<28} // {instruction}")?;
+      }
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,517 @@
+//! Symbolic execution of loop bodies.
+//!
+//! One pass through a loop is walked with every register holding a
+//! polynomial over the register values at the loop head. Conditional jumps
+//! fork the walk and record the branch taken as a `Guard`, so the result is
+//! a small set of `Path`s, each describing one way through the body and the
+//! register values it produces. Idiom recognizers work entirely on those.
+
+use {
+  super::ir::{
+    BinOp,
+    Condition,
+    Expr,
+    Lowered,
+  },
+  crate::_prelude::*,
+};
+
+/// Upper bound on instructions walked per loop, including all forks.
+const MAX_STEPS: usize = 512;
+
+/// Upper bound on paths through one loop body.
+const MAX_PATHS: usize = 16;
+
+/// Polynomial with integer coefficients over register values at loop entry.
+///
+/// Each monomial is the sorted list of registers it multiplies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Poly {
+  terms: BTreeMap<Vec<Reg>, i64>,
+}
+
+impl Poly {
+  /// The constant polynomial `value`.
+  #[must_use]
+  pub fn constant(value: i64) -> Self {
+    let mut terms = BTreeMap::new();
+    if value != 0 {
+      terms.insert(Vec::new(), value);
+    }
+    Self { terms }
+  }
+
+  /// The entry value of `reg`.
+  #[must_use]
+  pub fn reg(reg: Reg) -> Self {
+    Self {
+      terms: BTreeMap::from([(vec![reg], 1)]),
+    }
+  }
+
+  /// The polynomial's value if it does not depend on any register.
+  #[must_use]
+  pub fn as_constant(&self) -> Option<i64> {
+    match self.terms.iter().collect::<Vec<_>>()[..] {
+      [] => Some(0),
+      [(monomial, &value)] if monomial.is_empty() => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Every register the polynomial depends on.
+  #[must_use]
+  pub fn registers(&self) -> BTreeSet<Reg> {
+    self.terms.keys().flatten().copied().collect()
+  }
+
+  /// Whether the polynomial depends on `reg`.
+  #[must_use]
+  pub fn mentions(&self, reg: Reg) -> bool {
+    self.terms.keys().any(|monomial| monomial.contains(&reg))
+  }
+
+  /// Splits `self` into `coefficient * reg + rest`, if `reg` appears at
+  /// most linearly.  Returns `(coefficient, rest)`.
+  #[must_use]
+  pub fn split(&self, reg: Reg) -> Option<(Self, Self)> {
+    let mut coefficient = Self::default();
+    let mut rest = Self::default();
+    for (monomial, &value) in &self.terms {
+      match monomial.iter().filter(|&&r| r == reg).count() {
+        0 => rest.insert(monomial.clone(), value)?,
+        1 => coefficient.insert(
+          monomial.iter().copied().filter(|&r| r != reg).collect(),
+          value,
+        )?,
+        _ => return None,
+      }
+    }
+    Some((coefficient, rest))
+  }
+
+  /// Replaces every register with a polynomial, or `None` on overflow.
+  #[must_use]
+  pub fn substitute(&self, value: impl Fn(Reg) -> Self) -> Option<Self> {
+    self
+      .terms
+      .iter()
+      .try_fold(Self::default(), |sum, (monomial, &coefficient)| {
+        let term = monomial
+          .iter()
+          .try_fold(Self::constant(coefficient), |product, &reg| {
+            product.checked_mul(&value(reg))
+          })?;
+        sum.checked_add(&term)
+      })
+  }
+
+  /// Evaluates the polynomial, or `None` on overflow.
+  #[must_use]
+  pub fn eval(&self, value: impl Fn(Reg) -> i64) -> Option<i64> {
+    self
+      .terms
+      .iter()
+      .try_fold(0_i64, |sum, (monomial, &coefficient)| {
+        let term = monomial
+          .iter()
+          .try_fold(coefficient, |product, &reg| product.checked_mul(value(reg)))?;
+        sum.checked_add(term)
+      })
+  }
+
+  /// Formats the polynomial with the dialect's register names.
+  #[must_use]
+  pub fn display(&self, names: &[&str]) -> String {
+    let mut terms = self
+      .terms
+      .iter()
+      .filter(|(monomial, _)| !monomial.is_empty())
+      .chain(
+        self
+          .terms
+          .iter()
+          .filter(|(monomial, _)| monomial.is_empty()),
+      );
+
+    let mut text = String::new();
+    let Some(first) = terms.next() else {
+      return "0".to_owned();
+    };
+    for (index, (monomial, &coefficient)) in std::iter::once(first).chain(terms).enumerate() {
+      let magnitude = coefficient.unsigned_abs();
+      match (index, coefficient < 0) {
+        (0, true) => text.push('-'),
+        (0, false) => {}
+        (_, true) => text.push_str(" - "),
+        (_, false) => text.push_str(" + "),
+      }
+      let factors = monomial
+        .iter()
+        .map(|&reg| super::name(reg, names))
+        .collect::<Vec<_>>();
+      let term = match (magnitude, factors.is_empty()) {
+        (_, true) => magnitude.to_string(),
+        (1, false) => factors.join("*"),
+        (_, false) => format!("{magnitude}*{}", factors.join("*")),
+      };
+      text.push_str(&term);
+    }
+    text
+  }
+
+  /// `self + other`, or `None` on coefficient overflow.
+  #[must_use]
+  pub fn checked_add(&self, other: &Self) -> Option<Self> {
+    let mut sum = self.clone();
+    for (monomial, &value) in &other.terms {
+      sum.insert(monomial.clone(), value)?;
+    }
+    Some(sum)
+  }
+
+  /// `self - other`, or `None` on coefficient overflow.
+  #[must_use]
+  pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+    self.checked_add(&other.checked_neg()?)
+  }
+
+  /// `-self`, or `None` on coefficient overflow.
+  #[must_use]
+  pub fn checked_neg(&self) -> Option<Self> {
+    let terms = self
+      .terms
+      .iter()
+      .map(|(monomial, value)| Some((monomial.clone(), value.checked_neg()?)))
+      .collect::<Option<_>>()?;
+    Some(Self { terms })
+  }
+
+  /// `self * other`, or `None` on coefficient overflow.
+  #[must_use]
+  pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+    let mut product = Self::default();
+    for (left, &a) in &self.terms {
+      for (right, &b) in &other.terms {
+        product.insert([left.as_slice(), right].concat(), a.checked_mul(b)?)?;
+      }
+    }
+    Some(product)
+  }
+
+  fn insert(&mut self, mut monomial: Vec<Reg>, value: i64) -> Option<()> {
+    monomial.sort_unstable();
+    let entry = self.terms.entry(monomial).or_default();
+    *entry = entry.checked_add(value)?;
+    self.terms.retain(|_, value| *value != 0);
+    Some(())
+  }
+}
+
+/// A comparison whose outcome depends on entry values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Test {
+  /// The polynomial is zero.
+  Zero(Poly),
+  /// The polynomial is greater than zero.
+  Positive(Poly),
+}
+
+impl Test {
+  /// The polynomial being compared.
+  #[must_use]
+  pub const fn poly(&self) -> &Poly {
+    match self {
+      Self::Zero(poly) | Self::Positive(poly) => poly,
+    }
+  }
+
+  /// Applies `f` to the compared polynomial.
+  #[must_use]
+  pub fn try_map(&self, f: impl FnOnce(&Poly) -> Option<Poly>) -> Option<Self> {
+    match self {
+      Self::Zero(poly) => f(poly).map(Self::Zero),
+      Self::Positive(poly) => f(poly).map(Self::Positive),
+    }
+  }
+
+  /// The outcome, if it does not depend on any register.
+  #[must_use]
+  pub fn decide(&self) -> Option<bool> {
+    self.poly().as_constant().map(|value| self.eval_with(value))
+  }
+
+  /// Evaluates the test, or `None` on overflow.
+  #[must_use]
+  pub fn eval(&self, value: impl Fn(Reg) -> i64) -> Option<bool> {
+    self.poly().eval(value).map(|value| self.eval_with(value))
+  }
+
+  const fn eval_with(&self, value: i64) -> bool {
+    match self {
+      Self::Zero(_) => value == 0,
+      Self::Positive(_) => value > 0,
+    }
+  }
+}
+
+/// Symbolic register value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+  Poly(Poly),
+  /// The `0`/`1` outcome of a comparison.
+  Test(Test),
+  /// Anything the analyzer cannot express.
+  Unknown,
+}
+
+impl Value {
+  /// Whether the value depends on the entry value of `reg`.
+  #[must_use]
+  pub fn mentions(&self, reg: Reg) -> bool {
+    match self {
+      Self::Poly(poly) => poly.mentions(reg),
+      Self::Test(test) => test.poly().mentions(reg),
+      Self::Unknown => false,
+    }
+  }
+}
+
+/// A branch decision taken on a path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Guard {
+  pub test: Test,
+  /// Whether the path requires the test to hold.
+  pub holds: bool,
+}
+
+impl Guard {
+  /// The opposite decision.
+  #[must_use]
+  pub fn negated(&self) -> Self {
+    Self {
+      test: self.test.clone(),
+      holds: !self.holds,
+    }
+  }
+
+  /// Whether the decision is taken for concrete register values.
+  #[must_use]
+  pub fn eval(&self, value: impl Fn(Reg) -> i64) -> Option<bool> {
+    self.test.eval(value).map(|outcome| outcome == self.holds)
+  }
+}
+
+/// State of a walk through a loop body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walk {
+  /// Symbolic value of every register.
+  pub values: Vec<Value>,
+  /// Branch decisions taken so far.
+  pub guards: Vec<Guard>,
+  /// Polynomials that must be positive for summaries applied on the way
+  /// (an inner loop runs at least once) to be valid.
+  pub preconditions: Vec<Poly>,
+}
+
+impl Walk {
+  fn new(registers: usize) -> Self {
+    Self {
+      values: (0..registers)
+        .map(|index| Value::Poly(Poly::reg(Reg(index))))
+        .collect(),
+      guards: Vec::new(),
+      preconditions: Vec::new(),
+    }
+  }
+
+  /// The polynomial held by `reg`, if it is one.
+  #[must_use]
+  pub fn poly(&self, reg: Reg) -> Option<&Poly> {
+    match &self.values[reg.0] {
+      Value::Poly(poly) => Some(poly),
+      Value::Test(_) | Value::Unknown => None,
+    }
+  }
+
+  /// Rewrites an entry-relative polynomial in terms of this walk's values.
+  #[must_use]
+  pub fn substitute(&self, poly: &Poly) -> Option<Poly> {
+    let registers = poly.registers();
+    if registers.iter().any(|&reg| self.poly(reg).is_none()) {
+      return None;
+    }
+    poly.substitute(|reg| self.poly(reg).cloned().unwrap_or_default())
+  }
+
+  /// Records a branch decision.
+  ///
+  /// Returns `false` if the decision contradicts the path so far.
+  pub fn assume(&mut self, guard: Guard) -> bool {
+    if let Some(outcome) = guard.test.decide() {
+      return outcome == guard.holds;
+    }
+    if let Some(known) = self.guards.iter().find(|known| known.test == guard.test) {
+      return known.holds == guard.holds;
+    }
+    self.guards.push(guard);
+    true
+  }
+
+  fn operand(&self, operand: Operand) -> Value {
+    match operand {
+      Operand::Reg(reg) => self.values[reg.0].clone(),
+      Operand::Imm(value) => Value::Poly(Poly::constant(value)),
+    }
+  }
+
+  fn eval(&self, expr: Expr) -> Value {
+    let (op, lhs, rhs) = match expr {
+      Expr::Value(operand) => return self.operand(operand),
+      Expr::Binary(op, lhs, rhs) => (op, self.operand(lhs), self.operand(rhs)),
+    };
+    let (Value::Poly(lhs), Value::Poly(rhs)) = (lhs, rhs) else {
+      return Value::Unknown;
+    };
+    let value = match op {
+      BinOp::Add => lhs.checked_add(&rhs).map(Value::Poly),
+      BinOp::Sub => lhs.checked_sub(&rhs).map(Value::Poly),
+      BinOp::Mul => lhs.checked_mul(&rhs).map(Value::Poly),
+      BinOp::Eq => lhs
+        .checked_sub(&rhs)
+        .map(|poly| Value::Test(Test::Zero(poly))),
+      BinOp::Gt => lhs
+        .checked_sub(&rhs)
+        .map(|poly| Value::Test(Test::Positive(poly))),
+      op => lhs
+        .as_constant()
+        .zip(rhs.as_constant())
+        .and_then(|(lhs, rhs)| op.eval(lhs, rhs))
+        .map(|value| Value::Poly(Poly::constant(value))),
+    };
+    value.unwrap_or(Value::Unknown)
+  }
+}
+
+/// How a path leaves the loop body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum End {
+  /// Back to the loop head for another pass.
+  Continue,
+  /// Out of the loop, to the given address.
+  Exit(i64),
+}
+
+/// One way through a loop body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+  pub walk: Walk,
+  pub end: End,
+}
+
+/// What `explore` should do at an address inside the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hook {
+  /// Execute the instruction symbolically.
+  Proceed,
+  /// The hook has updated the walk itself; continue at the given address.
+  Resume(i64),
+  /// Give up on the whole loop.
+  Abort,
+}
+
+/// Walks every path through the loop `head..=tail` once.
+///
+/// `hook` is consulted before each instruction other than the head, which
+/// lets callers summarize inner loops instead of walking them. Returns
+/// `None` if the body contains anything that cannot be modelled, or has too
+/// many paths.
+pub fn explore(
+  lowered: &[Lowered],
+  head: i64,
+  tail: i64,
+  registers: usize,
+  mut hook: impl FnMut(i64, &mut Walk) -> Hook,
+) -> Option<Vec<Path>> {
+  let mut paths = Vec::new();
+  let mut pending = vec![(head, Walk::new(registers), false)];
+  let mut steps = 0;
+
+  while let Some((mut pc, mut walk, mut started)) = pending.pop() {
+    loop {
+      if pc == head && started {
+        paths.push(Path {
+          walk,
+          end: End::Continue,
+        });
+        break;
+      }
+      if pc < head || pc > tail {
+        paths.push(Path {
+          walk,
+          end: End::Exit(pc),
+        });
+        break;
+      }
+
+      steps += 1;
+      if steps > MAX_STEPS || paths.len() + pending.len() > MAX_PATHS {
+        return None;
+      }
+      if pc != head {
+        match hook(pc, &mut walk) {
+          Hook::Proceed => {}
+          Hook::Resume(next) => {
+            pc = next;
+            continue;
+          }
+          Hook::Abort => return None,
+        }
+      }
+
+      started = true;
+      match lowered[usize::try_from(pc).ok()?] {
+        Lowered::Assign(dst, expr) => {
+          walk.values[dst.0] = walk.eval(expr);
+          pc += 1;
+        }
+        Lowered::Nop => pc += 1,
+        Lowered::Jump(condition, target) => {
+          let taken = match condition {
+            Condition::Always => {
+              pc = target;
+              continue;
+            }
+            Condition::NonZero(operand) => match walk.operand(operand) {
+              Value::Poly(poly) => Guard {
+                test: Test::Zero(poly),
+                holds: false,
+              },
+              Value::Test(test) => Guard { test, holds: true },
+              Value::Unknown => return None,
+            },
+            Condition::Positive(operand) => match walk.operand(operand) {
+              Value::Poly(poly) => Guard {
+                test: Test::Positive(poly),
+                holds: true,
+              },
+              Value::Test(test) => Guard { test, holds: true },
+              Value::Unknown => return None,
+            },
+          };
+
+          let mut jumped = walk.clone();
+          if jumped.assume(taken.clone()) {
+            pending.push((target, jumped, true));
+          }
+          if !walk.assume(taken.negated()) {
+            break;
+          }
+          pc += 1;
+        }
+        Lowered::Computed | Lowered::Opaque => return None,
+      }
+    }
+  }
+  Some(paths)
+}
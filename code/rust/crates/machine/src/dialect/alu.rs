@@ -0,0 +1,130 @@
+//! The submarine ALU (2021/24).
+//!
+//! `inp` pops from the machine's input queue and blocks when it is empty,
+//! so a MONAD candidate is checked by queueing its fourteen digits.
+//!
+//! ```
+//! use machine::{
+//!   dialect::Alu,
+//!   prelude::*,
+//! };
+//!
+//! let source = "inp w
+//! add z w
+//! mod z 2
+//! div w 2
+//! add y w
+//! mod y 2
+//! div w 2
+//! add x w
+//! mod x 2
+//! div w 2
+//! mod w 2";
+//!
+//! let mut alu = Machine::<Alu>::parse(source).unwrap().with_input([13]);
+//! assert_eq!(alu.run(), Halt::Finished);
+//! assert_eq!(alu.registers.to_string(), "w=1 x=1 y=0 z=1");
+//! ```
+//!
+//! `div` by zero, `mod` of a negative value or by a non-positive one, and
+//! arithmetic that overflows crash the program, which stops with
+//! `Halt::Faulted`:
+//!
+//! ```
+//! use machine::{
+//!   dialect::Alu,
+//!   prelude::*,
+//! };
+//!
+//! let mut alu = Machine::<Alu>::parse("inp w\nmod z w").unwrap().with_input([0]);
+//! assert_eq!(alu.run(), Halt::Faulted);
+//! assert_eq!(alu.pc, 1);
+//!
+//! let mut alu = Machine::<Alu>::parse("inp w\nadd w 1").unwrap().with_input([i64::MAX]);
+//! assert_eq!(alu.run(), Halt::Faulted);
+//! ```
+
+use crate::_prelude::*;
+
+/// One ALU instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alu {
+  Inp(Reg),
+  Add(Reg, Operand),
+  Mul(Reg, Operand),
+  Div(Reg, Operand),
+  Mod(Reg, Operand),
+  Eql(Reg, Operand),
+}
+
+impl Instruction for Alu {
+  const REGISTERS: &'static [&'static str] = &["w", "x", "y", "z"];
+
+  fn parse(line: &str) -> Result<Self> {
+    let op = |token| Self::operand(token, line);
+    let reg = |token| Self::reg(token, line);
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+      ["inp", a] => Ok(Self::Inp(reg(a)?)),
+      ["add", a, b] => Ok(Self::Add(reg(a)?, op(b)?)),
+      ["mul", a, b] => Ok(Self::Mul(reg(a)?, op(b)?)),
+      ["div", a, b] => Ok(Self::Div(reg(a)?, op(b)?)),
+      ["mod", a, b] => Ok(Self::Mod(reg(a)?, op(b)?)),
+      ["eql", a, b] => Ok(Self::Eql(reg(a)?, op(b)?)),
+      _ => Err(Error::unknown(line)),
+    }
+  }
+
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+    match *self {
+      Self::Inp(a) => match machine.input.pop_front() {
+        Some(value) => machine.registers[a] = value,
+        None => return Flow::Block,
+      },
+      Self::Add(a, b) => match machine.registers[a].checked_add(machine.value(b)) {
+        Some(value) => machine.registers[a] = value,
+        None => return Flow::Fault,
+      },
+      Self::Mul(a, b) => match machine.registers[a].checked_mul(machine.value(b)) {
+        Some(value) => machine.registers[a] = value,
+        None => return Flow::Fault,
+      },
+      Self::Div(a, b) => match machine.registers[a].checked_div(machine.value(b)) {
+        Some(value) => machine.registers[a] = value,
+        None => return Flow::Fault,
+      },
+      Self::Mod(a, b) => match (machine.registers[a], machine.value(b)) {
+        (value, by) if value >= 0 && by > 0 => machine.registers[a] = value % by,
+        _ => return Flow::Fault,
+      },
+      Self::Eql(a, b) => machine.registers[a] = i64::from(machine.registers[a] == machine.value(b)),
+    }
+    Flow::Next
+  }
+
+  fn effect(&self) -> Effect {
+    let binary = |op, a, b| Effect::Assign(a, Expr::Binary(op, Operand::Reg(a), b));
+    match *self {
+      Self::Inp(_) => Effect::Opaque,
+      Self::Add(a, b) => binary(BinOp::Add, a, b),
+      Self::Mul(a, b) => binary(BinOp::Mul, a, b),
+      Self::Div(a, b) => binary(BinOp::Div, a, b),
+      Self::Mod(a, b) => binary(BinOp::Mod, a, b),
+      Self::Eql(a, b) => binary(BinOp::Eq, a, b),
+    }
+  }
+}
+
+impl Display for Alu {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let op = |operand: Operand| operand.display(Self::REGISTERS);
+    let reg = |reg: Reg| Operand::Reg(reg).display(Self::REGISTERS);
+    match *self {
+      Self::Inp(a) => write!(f, "inp {}", reg(a)),
+      Self::Add(a, b) => write!(f, "add {} {}", reg(a), op(b)),
+      Self::Mul(a, b) => write!(f, "mul {} {}", reg(a), op(b)),
+      Self::Div(a, b) => write!(f, "div {} {}", reg(a), op(b)),
+      Self::Mod(a, b) => write!(f, "mod {} {}", reg(a), op(b)),
+      Self::Eql(a, b) => write!(f, "eql {} {}", reg(a), op(b)),
+    }
+  }
+}
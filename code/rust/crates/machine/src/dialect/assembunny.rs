@@ -0,0 +1,118 @@
+//! Assembunny (2016/12, 2016/23, 2016/25).
+//!
+//! ```
+//! use machine::{
+//!   dialect::Assembunny,
+//!   prelude::*,
+//! };
+//!
+//! let source = "cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a";
+//! let mut machine = Machine::<Assembunny>::parse(source).unwrap();
+//! assert_eq!(machine.run(), Halt::Finished);
+//! assert_eq!(machine.register("a"), Some(3));
+//! ```
+
+use crate::_prelude::*;
+
+/// One assembunny instruction.
+///
+/// Operands are kept as `Operand` even where only a register makes sense,
+/// because `tgl` can turn any instruction into any other; invalid results
+/// (such as `cpy 1 2`) are skipped at execution time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Assembunny {
+  Cpy(Operand, Operand),
+  Inc(Operand),
+  Dec(Operand),
+  Jnz(Operand, Operand),
+  Tgl(Operand),
+  Out(Operand),
+}
+
+impl Assembunny {
+  /// The instruction `tgl` turns this one into.
+  #[must_use]
+  pub const fn toggled(self) -> Self {
+    match self {
+      Self::Inc(x) => Self::Dec(x),
+      Self::Dec(x) | Self::Tgl(x) | Self::Out(x) => Self::Inc(x),
+      Self::Jnz(x, y) => Self::Cpy(x, y),
+      Self::Cpy(x, y) => Self::Jnz(x, y),
+    }
+  }
+}
+
+impl Instruction for Assembunny {
+  const REGISTERS: &'static [&'static str] = &["a", "b", "c", "d"];
+
+  fn parse(line: &str) -> Result<Self> {
+    let op = |token| Self::operand(token, line);
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+      ["cpy", x, y] => Ok(Self::Cpy(op(x)?, op(y)?)),
+      ["inc", x] => Ok(Self::Inc(op(x)?)),
+      ["dec", x] => Ok(Self::Dec(op(x)?)),
+      ["jnz", x, y] => Ok(Self::Jnz(op(x)?, op(y)?)),
+      ["tgl", x] => Ok(Self::Tgl(op(x)?)),
+      ["out", x] => Ok(Self::Out(op(x)?)),
+      _ => Err(Error::unknown(line)),
+    }
+  }
+
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+    match *self {
+      Self::Cpy(x, Operand::Reg(y)) => machine.registers[y] = machine.value(x),
+      Self::Inc(Operand::Reg(x)) => match machine.registers[x].checked_add(1) {
+        Some(value) => machine.registers[x] = value,
+        None => return Flow::Fault,
+      },
+      Self::Dec(Operand::Reg(x)) => match machine.registers[x].checked_sub(1) {
+        Some(value) => machine.registers[x] = value,
+        None => return Flow::Fault,
+      },
+      Self::Jnz(x, y) if machine.value(x) != 0 => return Flow::Jump(machine.value(y)),
+      Self::Tgl(x) => {
+        let target = machine
+          .pc
+          .checked_add(machine.value(x))
+          .and_then(|target| usize::try_from(target).ok());
+        if let Some(instruction) = target.and_then(|index| machine.program.get_mut(index)) {
+          *instruction = instruction.toggled();
+        }
+      }
+      Self::Out(x) => machine.output.push(machine.value(x)),
+      _ => {}
+    }
+    Flow::Next
+  }
+
+  fn effect(&self) -> Effect {
+    match *self {
+      Self::Cpy(x, Operand::Reg(y)) => Effect::Assign(y, Expr::Value(x)),
+      Self::Inc(Operand::Reg(x)) => Effect::Assign(
+        x,
+        Expr::Binary(BinOp::Add, Operand::Reg(x), Operand::Imm(1)),
+      ),
+      Self::Dec(Operand::Reg(x)) => Effect::Assign(
+        x,
+        Expr::Binary(BinOp::Sub, Operand::Reg(x), Operand::Imm(1)),
+      ),
+      Self::Jnz(x, y) => Effect::Jump(Condition::NonZero(x), Target::Relative(y)),
+      Self::Tgl(_) | Self::Out(_) => Effect::Opaque,
+      Self::Cpy(..) | Self::Inc(_) | Self::Dec(_) => Effect::Nop,
+    }
+  }
+}
+
+impl Display for Assembunny {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let op = |operand: Operand| operand.display(Self::REGISTERS);
+    match *self {
+      Self::Cpy(x, y) => write!(f, "cpy {} {}", op(x), op(y)),
+      Self::Inc(x) => write!(f, "inc {}", op(x)),
+      Self::Dec(x) => write!(f, "dec {}", op(x)),
+      Self::Jnz(x, y) => write!(f, "jnz {} {}", op(x), op(y)),
+      Self::Tgl(x) => write!(f, "tgl {}", op(x)),
+      Self::Out(x) => write!(f, "out {}", op(x)),
+    }
+  }
+}
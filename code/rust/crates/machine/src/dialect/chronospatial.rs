@@ -0,0 +1,257 @@
+//! The 3-bit chronospatial computer (2024/17).
+//!
+//! Programs are a comma-separated list of 3-bit words read in
+//! (opcode, operand) pairs, so the machine's program counter counts pairs
+//! rather than words. `jnz` targets are word addresses and are halved;
+//! every known puzzle input only jumps to even addresses.
+//!
+//! ```
+//! use machine::{
+//!   dialect::Chronospatial,
+//!   prelude::*,
+//! };
+//!
+//! let source = "Register A: 729
+//! Register B: 0
+//! Register C: 0
+//!
+//! Program: 0,1,5,4,3,0";
+//!
+//! let mut computer = Machine::<Chronospatial>::parse(source).unwrap();
+//! assert_eq!(computer.run(), Halt::Finished);
+//! assert_eq!(Chronospatial::join(&computer.output), "4,6,3,5,6,3,5,2,1,0");
+//! ```
+//!
+//! Part two asks for the register A that makes the program print itself,
+//! far beyond brute force. `Chronospatial::quine` searches for it backwards,
+//! three bits of A per output word:
+//!
+//! ```
+//! use machine::{
+//!   dialect::Chronospatial,
+//!   prelude::*,
+//! };
+//!
+//! let source = "Register A: 2024
+//! Register B: 0
+//! Register C: 0
+//!
+//! Program: 0,3,5,4,3,0";
+//!
+//! let program = Chronospatial::parse_program(source).unwrap();
+//! assert_eq!(Chronospatial::quine(&program), Some(117_440));
+//! assert_eq!(
+//!   Chronospatial::output(&program, 117_440),
+//!   Chronospatial::words(&program)
+//! );
+//! ```
+
+use crate::_prelude::*;
+
+/// One (opcode, operand) pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chronospatial {
+  pub opcode: u8,
+  pub operand: u8,
+}
+
+impl Chronospatial {
+  const A: Reg = Reg(0);
+  const B: Reg = Reg(1);
+  const C: Reg = Reg(2);
+  const MNEMONICS: [&'static str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+  /// The program as the flat word list it was written as.
+  #[must_use]
+  pub fn words(program: &Program<Self>) -> Vec<i64> {
+    program
+      .instructions
+      .iter()
+      .flat_map(|pair| [i64::from(pair.opcode), i64::from(pair.operand)])
+      .collect()
+  }
+
+  /// What the program outputs when started with `a` in register A and the
+  /// other registers as given.
+  ///
+  /// Runs are capped at a million steps, so a program that never leaves its
+  /// loop returns what it printed until then.
+  #[must_use]
+  pub fn output(program: &Program<Self>, a: i64) -> Vec<i64> {
+    let mut machine = Machine::new(program.clone())
+      .with_register("A", a)
+      .with_step_limit(1_000_000);
+    machine.run();
+    machine.output
+  }
+
+  /// The lowest positive register A for which the program outputs its own
+  /// words (2024/17 part two).
+  ///
+  /// Every puzzle input is a single loop that prints one word, shifts A
+  /// right by three bits with the only `adv 3` and jumps back while A is
+  /// not zero. The last word printed then depends only on the highest three
+  /// bits of A, the one before it on the next three, and so on, so A is
+  /// built up from the end of the program, keeping every three-bit
+  /// extension that reproduces the words seen so far.
+  ///
+  /// Returns `None` if the program does not have that shape or no value
+  /// of A works.
+  #[must_use]
+  pub fn quine(program: &Program<Self>) -> Option<i64> {
+    let shifts = program
+      .instructions
+      .iter()
+      .filter(|pair| pair.opcode == 0)
+      .map(|pair| pair.operand)
+      .collect::<Vec<_>>();
+    if shifts != [3] {
+      return None;
+    }
+
+    let words = Self::words(program);
+    let mut candidates = vec![0_i64];
+    for start in (0..words.len()).rev() {
+      candidates = candidates
+        .iter()
+        .flat_map(|&high| (0..8).filter_map(move |bits| high.checked_mul(8)?.checked_add(bits)))
+        .filter(|&a| Self::output(program, a) == words[start..])
+        .collect();
+    }
+    candidates.into_iter().filter(|&a| a > 0).min()
+  }
+
+  /// Formats output the way the puzzle expects it.
+  #[must_use]
+  pub fn join(values: &[i64]) -> String {
+    values
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>()
+      .join(",")
+  }
+
+  fn combo(self, machine: &Machine<Self>) -> i64 {
+    match self.operand {
+      4 => machine.registers[Self::A],
+      5 => machine.registers[Self::B],
+      6 => machine.registers[Self::C],
+      literal => i64::from(literal),
+    }
+  }
+
+  fn shifted(machine: &Machine<Self>, by: i64) -> i64 {
+    u32::try_from(by)
+      .ok()
+      .and_then(|by| machine.registers[Self::A].checked_shr(by))
+      .unwrap_or(0)
+  }
+
+  fn word(token: &str, line: &str) -> Result<u8> {
+    token
+      .trim()
+      .parse()
+      .ok()
+      .filter(|word| *word < 8)
+      .ok_or_else(|| Error::operand(token, line))
+  }
+}
+
+impl Instruction for Chronospatial {
+  const REGISTERS: &'static [&'static str] = &["A", "B", "C"];
+
+  /// Parses the mnemonic form used by `Display`, e.g. `adv 3`.
+  fn parse(line: &str) -> Result<Self> {
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+      [mnemonic, operand] => Ok(Self {
+        opcode: Self::MNEMONICS
+          .iter()
+          .position(|name| *name == mnemonic)
+          .and_then(|opcode| u8::try_from(opcode).ok())
+          .ok_or_else(|| Error::unknown(line))?,
+        operand: Self::word(operand, line)?,
+      }),
+      _ => Err(Error::arity(1, line)),
+    }
+  }
+
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+    let literal = i64::from(self.operand);
+    match self.opcode {
+      0 => machine.registers[Self::A] = Self::shifted(machine, self.combo(machine)),
+      1 => machine.registers[Self::B] ^= literal,
+      2 => machine.registers[Self::B] = self.combo(machine) & 7,
+      3 if machine.registers[Self::A] != 0 => return Flow::Goto(literal / 2),
+      4 => machine.registers[Self::B] ^= machine.registers[Self::C],
+      5 => machine.output.push(self.combo(machine) & 7),
+      6 => machine.registers[Self::B] = Self::shifted(machine, self.combo(machine)),
+      7 => machine.registers[Self::C] = Self::shifted(machine, self.combo(machine)),
+      _ => {}
+    }
+    Flow::Next
+  }
+
+  fn effect(&self) -> Effect {
+    let literal = Operand::Imm(i64::from(self.operand));
+    let combo = match self.operand {
+      4 => Operand::Reg(Self::A),
+      5 => Operand::Reg(Self::B),
+      6 => Operand::Reg(Self::C),
+      _ => literal,
+    };
+    let shift = |dst| Effect::Assign(dst, Expr::Binary(BinOp::Shr, Operand::Reg(Self::A), combo));
+    let b = Operand::Reg(Self::B);
+    match self.opcode {
+      0 => shift(Self::A),
+      1 => Effect::Assign(Self::B, Expr::Binary(BinOp::Xor, b, literal)),
+      2 => Effect::Assign(Self::B, Expr::Binary(BinOp::And, combo, Operand::Imm(7))),
+      3 => Effect::Jump(
+        Condition::NonZero(Operand::Reg(Self::A)),
+        Target::Absolute(i64::from(self.operand / 2)),
+      ),
+      4 => Effect::Assign(Self::B, Expr::Binary(BinOp::Xor, b, Operand::Reg(Self::C))),
+      6 => shift(Self::B),
+      7 => shift(Self::C),
+      _ => Effect::Opaque,
+    }
+  }
+
+  /// Parses the puzzle input: `Register X: n` lines and a `Program:` line.
+  fn parse_program(source: &str) -> Result<Program<Self>> {
+    let mut program = Program::new(Vec::new());
+    for (index, line) in source.lines().enumerate() {
+      let at = |error: Error| error.at_line(index + 1);
+      if let Some(rest) = line.trim().strip_prefix("Register ") {
+        let (name, value) = rest
+          .split_once(':')
+          .ok_or_else(|| at(Error::unknown(line)))?;
+        let reg = Self::reg(name.trim(), line).map_err(at)?;
+        let value = value
+          .trim()
+          .parse()
+          .map_err(|_| at(Error::operand(value, line)))?;
+        program.presets.push((reg, value));
+      } else if let Some(words) = line.trim().strip_prefix("Program:") {
+        let words = words
+          .split(',')
+          .map(|word| Self::word(word, line).map_err(at))
+          .collect::<Result<Vec<_>>>()?;
+        program.instructions = words
+          .chunks(2)
+          .map(|pair| Self {
+            opcode: pair[0],
+            operand: pair.get(1).copied().unwrap_or(0),
+          })
+          .collect();
+      }
+    }
+    Ok(program)
+  }
+}
+
+impl Display for Chronospatial {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let mnemonic = Self::MNEMONICS[usize::from(self.opcode & 7)];
+    write!(f, "{mnemonic} {}", self.operand)
+  }
+}
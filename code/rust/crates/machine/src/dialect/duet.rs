@@ -0,0 +1,179 @@
+//! Duet (2017/18, 2017/23).
+//!
+//! `snd` pushes to the machine's output and `rcv` pops from its input,
+//! blocking when the queue is empty. That is the part-two reading of 2017/18;
+//! `Duet::recover` runs the part-one reading, where `snd` plays a sound and
+//! `rcv X` recovers the last one unless X is 0.
+//!
+//! ```
+//! use machine::{
+//!   dialect::Duet,
+//!   prelude::*,
+//! };
+//!
+//! let source =
+//!   "set a 1\nadd a 2\nmul a a\nmod a 5\nsnd a\nset a 0\nrcv a\njgz a -1\nset a 1\njgz a -2";
+//! let program = Duet::parse_program(source).unwrap();
+//! assert_eq!(Duet::recover(&program), Some(4));
+//!
+//! // `rcv a` is skipped while `a` is 0.
+//! let program = Duet::parse_program("snd 7\nrcv a\nset a 1\nsnd 9\nrcv a").unwrap();
+//! assert_eq!(Duet::recover(&program), Some(9));
+//!
+//! let program = Duet::parse_program("snd 1\nsnd 2\nsnd p\nrcv a\nrcv b\nrcv c\nrcv d").unwrap();
+//! assert_eq!(Duet::run_pair(&program), 3);
+//! ```
+
+use crate::_prelude::*;
+
+/// One Duet instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Duet {
+  Snd(Operand),
+  Set(Reg, Operand),
+  Add(Reg, Operand),
+  Sub(Reg, Operand),
+  Mul(Reg, Operand),
+  Mod(Reg, Operand),
+  Rcv(Reg),
+  Jgz(Operand, Operand),
+  Jnz(Operand, Operand),
+}
+
+impl Duet {
+  /// Runs `program` with the part-one reading of 2017/18: `rcv X` does
+  /// nothing while X is 0, and otherwise recovers the last sound played.
+  ///
+  /// Returns the first recovered frequency, or `None` if the program stops
+  /// before recovering one.
+  #[must_use]
+  pub fn recover(program: &Program<Self>) -> Option<i64> {
+    let mut machine = Machine::new(program.clone());
+    let halt = machine.run_with(|machine| match machine.current().copied() {
+      Some(Self::Rcv(x)) if machine.registers[x] != 0 => Some(Halt::Paused),
+      Some(Self::Rcv(_)) => {
+        // Receiving the 0 it already holds leaves the register unchanged.
+        machine.input.push_back(0);
+        None
+      }
+      _ => None,
+    });
+    if halt == Halt::Paused {
+      machine.output.last().copied()
+    } else {
+      None
+    }
+  }
+
+  /// Runs two copies of `program` (with `p` set to 0 and 1) against each
+  /// other until both are finished or deadlocked.
+  ///
+  /// Returns how many values program 1 sent.
+  #[must_use]
+  pub fn run_pair(program: &Program<Self>) -> usize {
+    let mut machines = [0, 1].map(|id| Machine::new(program.clone()).with_register("p", id));
+    let mut sent = 0;
+
+    loop {
+      let mut progressed = false;
+      for id in 0..2 {
+        let before = machines[id].steps;
+        machines[id].run();
+        progressed |= machines[id].steps > before;
+
+        let values = machines[id].drain_output();
+        if id == 1 {
+          sent += values.len();
+        }
+        machines[1 - id].input.extend(values);
+      }
+      if !progressed {
+        return sent;
+      }
+    }
+  }
+}
+
+impl Instruction for Duet {
+  const REGISTERS: &'static [&'static str] = &[
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s",
+    "t", "u", "v", "w", "x", "y", "z",
+  ];
+
+  fn parse(line: &str) -> Result<Self> {
+    let op = |token| Self::operand(token, line);
+    let reg = |token| Self::reg(token, line);
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+      ["snd", x] => Ok(Self::Snd(op(x)?)),
+      ["set", x, y] => Ok(Self::Set(reg(x)?, op(y)?)),
+      ["add", x, y] => Ok(Self::Add(reg(x)?, op(y)?)),
+      ["sub", x, y] => Ok(Self::Sub(reg(x)?, op(y)?)),
+      ["mul", x, y] => Ok(Self::Mul(reg(x)?, op(y)?)),
+      ["mod", x, y] => Ok(Self::Mod(reg(x)?, op(y)?)),
+      ["rcv", x] => Ok(Self::Rcv(reg(x)?)),
+      ["jgz", x, y] => Ok(Self::Jgz(op(x)?, op(y)?)),
+      ["jnz", x, y] => Ok(Self::Jnz(op(x)?, op(y)?)),
+      _ => Err(Error::unknown(line)),
+    }
+  }
+
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+    match *self {
+      Self::Snd(x) => machine.output.push(machine.value(x)),
+      Self::Set(x, y) => machine.registers[x] = machine.value(y),
+      Self::Add(x, y) | Self::Sub(x, y) | Self::Mul(x, y) | Self::Mod(x, y) => {
+        let (lhs, rhs) = (machine.registers[x], machine.value(y));
+        let value = match *self {
+          Self::Add(..) => lhs.checked_add(rhs),
+          Self::Sub(..) => lhs.checked_sub(rhs),
+          Self::Mul(..) => lhs.checked_mul(rhs),
+          _ => lhs.checked_rem_euclid(rhs),
+        };
+        match value {
+          Some(value) => machine.registers[x] = value,
+          None => return Flow::Fault,
+        }
+      }
+      Self::Rcv(x) => match machine.input.pop_front() {
+        Some(value) => machine.registers[x] = value,
+        None => return Flow::Block,
+      },
+      Self::Jgz(x, y) if machine.value(x) > 0 => return Flow::Jump(machine.value(y)),
+      Self::Jnz(x, y) if machine.value(x) != 0 => return Flow::Jump(machine.value(y)),
+      Self::Jgz(..) | Self::Jnz(..) => {}
+    }
+    Flow::Next
+  }
+
+  fn effect(&self) -> Effect {
+    let binary = |op, x, y| Effect::Assign(x, Expr::Binary(op, Operand::Reg(x), y));
+    match *self {
+      Self::Set(x, y) => Effect::Assign(x, Expr::Value(y)),
+      Self::Add(x, y) => binary(BinOp::Add, x, y),
+      Self::Sub(x, y) => binary(BinOp::Sub, x, y),
+      Self::Mul(x, y) => binary(BinOp::Mul, x, y),
+      Self::Jgz(x, y) => Effect::Jump(Condition::Positive(x), Target::Relative(y)),
+      Self::Jnz(x, y) => Effect::Jump(Condition::NonZero(x), Target::Relative(y)),
+      // `mod` is Euclidean here, unlike `BinOp::Mod`.
+      Self::Snd(_) | Self::Rcv(_) | Self::Mod(..) => Effect::Opaque,
+    }
+  }
+}
+
+impl Display for Duet {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let op = |operand: Operand| operand.display(Self::REGISTERS);
+    let reg = |reg: Reg| Operand::Reg(reg).display(Self::REGISTERS);
+    match *self {
+      Self::Snd(x) => write!(f, "snd {}", op(x)),
+      Self::Set(x, y) => write!(f, "set {} {}", reg(x), op(y)),
+      Self::Add(x, y) => write!(f, "add {} {}", reg(x), op(y)),
+      Self::Sub(x, y) => write!(f, "sub {} {}", reg(x), op(y)),
+      Self::Mul(x, y) => write!(f, "mul {} {}", reg(x), op(y)),
+      Self::Mod(x, y) => write!(f, "mod {} {}", reg(x), op(y)),
+      Self::Rcv(x) => write!(f, "rcv {}", reg(x)),
+      Self::Jgz(x, y) => write!(f, "jgz {} {}", op(x), op(y)),
+      Self::Jnz(x, y) => write!(f, "jnz {} {}", op(x), op(y)),
+    }
+  }
+}
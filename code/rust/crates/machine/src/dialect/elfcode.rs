@@ -0,0 +1,258 @@
+//! `ElfCode` with a bindable instruction pointer (2018/16, 2018/19, 2018/21).
+//!
+//! ```
+//! use machine::{
+//!   dialect::ElfCode,
+//!   prelude::*,
+//! };
+//!
+//! let source = "#ip 0
+//! seti 5 0 1
+//! seti 6 0 2
+//! addi 0 1 0
+//! addr 1 2 3
+//! setr 1 0 0
+//! seti 8 0 4
+//! seti 9 0 5";
+//!
+//! let mut machine = Machine::<ElfCode>::parse(source).unwrap();
+//! assert_eq!(machine.run(), Halt::Finished);
+//! assert_eq!(machine.registers.values(), &[6, 5, 6, 0, 0, 9]);
+//! ```
+//!
+//! 2018/16 works on bare opcodes instead of programs:
+//!
+//! ```
+//! use machine::dialect::Opcode;
+//!
+//! let before = [3, 2, 1, 1];
+//! let matching = Opcode::ALL
+//!   .iter()
+//!   .filter(|opcode| {
+//!     let mut registers = before;
+//!     opcode.apply(&mut registers, 2, 1, 2);
+//!     registers == [3, 2, 2, 1]
+//!   })
+//!   .count();
+//! assert_eq!(matching, 3);
+//! ```
+
+use crate::_prelude::*;
+
+/// The sixteen `ElfCode` opcodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Opcode {
+  Addr,
+  Addi,
+  Mulr,
+  Muli,
+  Banr,
+  Bani,
+  Borr,
+  Bori,
+  Setr,
+  Seti,
+  Gtir,
+  Gtri,
+  Gtrr,
+  Eqir,
+  Eqri,
+  Eqrr,
+}
+
+impl Opcode {
+  /// Every opcode, in declaration order.
+  pub const ALL: [Self; 16] = [
+    Self::Addr,
+    Self::Addi,
+    Self::Mulr,
+    Self::Muli,
+    Self::Banr,
+    Self::Bani,
+    Self::Borr,
+    Self::Bori,
+    Self::Setr,
+    Self::Seti,
+    Self::Gtir,
+    Self::Gtri,
+    Self::Gtrr,
+    Self::Eqir,
+    Self::Eqri,
+    Self::Eqrr,
+  ];
+
+  /// Source mnemonic.
+  #[must_use]
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::Addr => "addr",
+      Self::Addi => "addi",
+      Self::Mulr => "mulr",
+      Self::Muli => "muli",
+      Self::Banr => "banr",
+      Self::Bani => "bani",
+      Self::Borr => "borr",
+      Self::Bori => "bori",
+      Self::Setr => "setr",
+      Self::Seti => "seti",
+      Self::Gtir => "gtir",
+      Self::Gtri => "gtri",
+      Self::Gtrr => "gtrr",
+      Self::Eqir => "eqir",
+      Self::Eqri => "eqri",
+      Self::Eqrr => "eqrr",
+    }
+  }
+
+  /// Applies the opcode to a raw register slice.
+  ///
+  /// Out-of-range register operands leave the registers untouched, which
+  /// makes this safe to use when probing opcodes against 2018/16 samples.
+  ///
+  /// Returns `false` if the result overflowed, also leaving the registers
+  /// untouched.
+  pub fn apply(self, registers: &mut [i64], a: i64, b: i64, c: i64) -> bool {
+    let reg = |index: i64| {
+      usize::try_from(index)
+        .ok()
+        .and_then(|index| registers.get(index).copied())
+    };
+    let value = match self {
+      Self::Addr => reg(a).zip(reg(b)).map(|(a, b)| a.checked_add(b)),
+      Self::Addi => reg(a).map(|a| a.checked_add(b)),
+      Self::Mulr => reg(a).zip(reg(b)).map(|(a, b)| a.checked_mul(b)),
+      Self::Muli => reg(a).map(|a| a.checked_mul(b)),
+      Self::Banr => reg(a).zip(reg(b)).map(|(a, b)| Some(a & b)),
+      Self::Bani => reg(a).map(|a| Some(a & b)),
+      Self::Borr => reg(a).zip(reg(b)).map(|(a, b)| Some(a | b)),
+      Self::Bori => reg(a).map(|a| Some(a | b)),
+      Self::Setr => reg(a).map(Some),
+      Self::Seti => Some(Some(a)),
+      Self::Gtir => reg(b).map(|b| Some(i64::from(a > b))),
+      Self::Gtri => reg(a).map(|a| Some(i64::from(a > b))),
+      Self::Gtrr => reg(a).zip(reg(b)).map(|(a, b)| Some(i64::from(a > b))),
+      Self::Eqir => reg(b).map(|b| Some(i64::from(a == b))),
+      Self::Eqri => reg(a).map(|a| Some(i64::from(a == b))),
+      Self::Eqrr => reg(a).zip(reg(b)).map(|(a, b)| Some(i64::from(a == b))),
+    };
+    let target = usize::try_from(c)
+      .ok()
+      .and_then(|index| registers.get_mut(index));
+    match (value, target) {
+      (Some(None), _) => return false,
+      (Some(Some(value)), Some(target)) => *target = value,
+      _ => {}
+    }
+    true
+  }
+}
+
+impl FromStr for Opcode {
+  type Err = Error;
+
+  fn from_str(name: &str) -> Result<Self> {
+    Self::ALL
+      .into_iter()
+      .find(|opcode| opcode.name() == name)
+      .ok_or_else(|| Error::unknown(name))
+  }
+}
+
+/// One `ElfCode` instruction: an opcode and three integer operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ElfCode {
+  pub opcode: Opcode,
+  pub a: i64,
+  pub b: i64,
+  pub c: i64,
+}
+
+impl Instruction for ElfCode {
+  const REGISTERS: &'static [&'static str] = &["0", "1", "2", "3", "4", "5"];
+
+  fn parse(line: &str) -> Result<Self> {
+    let int = |token: &str| token.parse().map_err(|_| Error::operand(token, line));
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+      [opcode, a, b, c] => Ok(Self {
+        opcode: opcode.parse()?,
+        a: int(a)?,
+        b: int(b)?,
+        c: int(c)?,
+      }),
+      _ => Err(Error::arity(3, line)),
+    }
+  }
+
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+    if self
+      .opcode
+      .apply(machine.registers.values_mut(), self.a, self.b, self.c)
+    {
+      Flow::Next
+    } else {
+      Flow::Fault
+    }
+  }
+
+  fn effect(&self) -> Effect {
+    let reg = |value: i64| {
+      usize::try_from(value)
+        .ok()
+        .filter(|&index| index < Self::REGISTERS.len())
+        .map(|index| Operand::Reg(Reg(index)))
+    };
+    let imm = |value| Some(Operand::Imm(value));
+    let Some(Operand::Reg(dst)) = reg(self.c) else {
+      return Effect::Opaque;
+    };
+
+    let (op, a, b) = match self.opcode {
+      Opcode::Setr => {
+        return reg(self.a).map_or(Effect::Opaque, |a| Effect::Assign(dst, Expr::Value(a)));
+      }
+      Opcode::Seti => return Effect::Assign(dst, Expr::Value(Operand::Imm(self.a))),
+      Opcode::Addr => (BinOp::Add, reg(self.a), reg(self.b)),
+      Opcode::Addi => (BinOp::Add, reg(self.a), imm(self.b)),
+      Opcode::Mulr => (BinOp::Mul, reg(self.a), reg(self.b)),
+      Opcode::Muli => (BinOp::Mul, reg(self.a), imm(self.b)),
+      Opcode::Banr => (BinOp::And, reg(self.a), reg(self.b)),
+      Opcode::Bani => (BinOp::And, reg(self.a), imm(self.b)),
+      Opcode::Borr => (BinOp::Or, reg(self.a), reg(self.b)),
+      Opcode::Bori => (BinOp::Or, reg(self.a), imm(self.b)),
+      Opcode::Gtir => (BinOp::Gt, imm(self.a), reg(self.b)),
+      Opcode::Gtri => (BinOp::Gt, reg(self.a), imm(self.b)),
+      Opcode::Gtrr => (BinOp::Gt, reg(self.a), reg(self.b)),
+      Opcode::Eqir => (BinOp::Eq, imm(self.a), reg(self.b)),
+      Opcode::Eqri => (BinOp::Eq, reg(self.a), imm(self.b)),
+      Opcode::Eqrr => (BinOp::Eq, reg(self.a), reg(self.b)),
+    };
+    a.zip(b).map_or(Effect::Opaque, |(a, b)| {
+      Effect::Assign(dst, Expr::Binary(op, a, b))
+    })
+  }
+
+  /// Parses a program, honouring `#ip <register>` directives.
+  fn parse_program(source: &str) -> Result<Program<Self>> {
+    let mut ip_binding = None;
+    let mut body = String::with_capacity(source.len());
+    for (index, line) in source.lines().enumerate() {
+      if let Some(register) = line.trim().strip_prefix("#ip") {
+        let reg = Self::reg(register.trim(), line).map_err(|error| error.at_line(index + 1))?;
+        ip_binding = Some(reg);
+      } else {
+        body.push_str(line);
+      }
+      body.push('\n');
+    }
+
+    let mut program = Program::parse_lines(&body)?;
+    program.ip_binding = ip_binding;
+    Ok(program)
+  }
+}
+
+impl Display for ElfCode {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "{} {} {} {}", self.opcode.name(), self.a, self.b, self.c)
+  }
+}
@@ -0,0 +1,90 @@
+//! The handheld game console (2020/08).
+//!
+//! ```
+//! use machine::{
+//!   dialect::Handheld,
+//!   prelude::*,
+//! };
+//!
+//! let source = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+//! let program = Handheld::parse_program(source).unwrap();
+//!
+//! let repaired = (0..program.len()).find_map(|index| {
+//!   let mut patched = program.clone();
+//!   patched.instructions[index] = patched.instructions[index].flipped();
+//!   let mut console = Machine::new(patched).with_loop_detection(LoopDetection::Pc);
+//!   (console.run() == Halt::Finished).then(|| console.register("acc"))?
+//! });
+//! assert_eq!(repaired, Some(8));
+//! ```
+
+use crate::_prelude::*;
+
+/// One boot-code instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handheld {
+  Acc(i64),
+  Jmp(i64),
+  Nop(i64),
+}
+
+impl Handheld {
+  const ACC: Reg = Reg(0);
+
+  /// Swaps `jmp` and `nop`, leaving `acc` alone.
+  #[must_use]
+  pub const fn flipped(self) -> Self {
+    match self {
+      Self::Jmp(n) => Self::Nop(n),
+      Self::Nop(n) => Self::Jmp(n),
+      Self::Acc(n) => Self::Acc(n),
+    }
+  }
+}
+
+impl Instruction for Handheld {
+  const REGISTERS: &'static [&'static str] = &["acc"];
+
+  fn parse(line: &str) -> Result<Self> {
+    let int = |token: &str| token.parse().map_err(|_| Error::operand(token, line));
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+      ["acc", n] => Ok(Self::Acc(int(n)?)),
+      ["jmp", n] => Ok(Self::Jmp(int(n)?)),
+      ["nop", n] => Ok(Self::Nop(int(n)?)),
+      _ => Err(Error::unknown(line)),
+    }
+  }
+
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+    match *self {
+      Self::Acc(n) => match machine.registers[Self::ACC].checked_add(n) {
+        Some(value) => machine.registers[Self::ACC] = value,
+        None => return Flow::Fault,
+      },
+      Self::Jmp(n) => return Flow::Jump(n),
+      Self::Nop(_) => {}
+    }
+    Flow::Next
+  }
+
+  fn effect(&self) -> Effect {
+    match *self {
+      Self::Acc(n) => Effect::Assign(
+        Self::ACC,
+        Expr::Binary(BinOp::Add, Operand::Reg(Self::ACC), Operand::Imm(n)),
+      ),
+      Self::Jmp(n) => Effect::Jump(Condition::Always, Target::Relative(Operand::Imm(n))),
+      Self::Nop(_) => Effect::Nop,
+    }
+  }
+}
+
+impl Display for Handheld {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    match *self {
+      Self::Acc(n) => write!(f, "acc {n:+}"),
+      Self::Jmp(n) => write!(f, "jmp {n:+}"),
+      Self::Nop(n) => write!(f, "nop {n:+}"),
+    }
+  }
+}
@@ -0,0 +1,29 @@
+//! Ready-made dialects for the puzzle assembly languages.
+//!
+//! | Dialect | Puzzles |
+//! |---------|---------|
+//! | `Assembunny` | 2016/12, 2016/23, 2016/25 |
+//! | `ElfCode` | 2018/16, 2018/19, 2018/21 |
+//! | `Duet` | 2017/18, 2017/23 |
+//! | `Handheld` | 2020/08 |
+//! | `Alu` | 2021/24 |
+//! | `Chronospatial` | 2024/17 |
+//!
+//! Each one is an `Instruction` implementation of a few dozen lines; the
+//! framework in `machine::prelude` does the rest.
+
+mod alu;
+mod assembunny;
+mod chronospatial;
+mod duet;
+mod elfcode;
+mod handheld;
+
+pub use {
+  alu::*,
+  assembunny::*,
+  chronospatial::*,
+  duet::*,
+  elfcode::*,
+  handheld::*,
+};
@@ -0,0 +1,96 @@
+use crate::_prelude::*;
+
+pub type Result<T> = StdResult<T, Error>;
+
+#[derive(Debug, ThisError, Diagnostic)]
+pub enum Error {
+  #[error("line {line}: unknown instruction `{text}`")]
+  #[diagnostic(
+    code(machine::unknown_instruction),
+    help("Check the mnemonic against the dialect's instruction set.")
+  )]
+  UnknownInstruction { line: usize, text: String },
+
+  #[error("line {line}: invalid operand `{operand}` in `{text}`")]
+  #[diagnostic(
+    code(machine::invalid_operand),
+    help("Operands are either a register name or an integer literal.")
+  )]
+  InvalidOperand {
+    line: usize,
+    operand: String,
+    text: String,
+  },
+
+  #[error("line {line}: expected {expected} operand(s) in `{text}`")]
+  #[diagnostic(code(machine::arity))]
+  Arity {
+    line: usize,
+    expected: usize,
+    text: String,
+  },
+
+  #[error("unknown register `{0}`")]
+  #[diagnostic(
+    code(machine::unknown_register),
+    help("Register names are defined by the dialect's `REGISTERS` constant.")
+  )]
+  UnknownRegister(String),
+
+  #[error("{0}")]
+  #[diagnostic(code(machine::generic))]
+  Generic(String),
+}
+
+impl Error {
+  /// Attaches a 1-based source line number to a parse error.
+  ///
+  /// Dialects report errors with `line: 0` from `Instruction::parse`; the
+  /// program parser fills in the real line once it knows where it is.
+  #[must_use]
+  pub fn at_line(self, number: usize) -> Self {
+    match self {
+      Self::UnknownInstruction { text, .. } => Self::UnknownInstruction { line: number, text },
+      Self::InvalidOperand { operand, text, .. } => Self::InvalidOperand {
+        line: number,
+        operand,
+        text,
+      },
+      Self::Arity { expected, text, .. } => Self::Arity {
+        line: number,
+        expected,
+        text,
+      },
+      other => other,
+    }
+  }
+
+  /// Shorthand for an unknown mnemonic in `text`.
+  #[must_use]
+  pub fn unknown(text: &str) -> Self {
+    Self::UnknownInstruction {
+      line: 0,
+      text: text.to_owned(),
+    }
+  }
+
+  /// Shorthand for an operand that could not be parsed.
+  #[must_use]
+  pub fn operand(operand: &str, text: &str) -> Self {
+    Self::InvalidOperand {
+      line: 0,
+      operand: operand.to_owned(),
+      text: text.to_owned(),
+    }
+  }
+
+  /// Shorthand for a wrong number of operands.
+  #[must_use]
+  pub fn arity(expected: usize, text: &str) -> Self {
+    Self::Arity {
+      line: 0,
+      expected,
+      text: text.to_owned(),
+    }
+  }
+}
@@ -0,0 +1,17 @@
+pub mod analysis;
+pub mod dialect;
+mod error;
+mod machine;
+pub mod prelude;
+mod program;
+mod register;
+
+mod _prelude {
+  pub use crate::{
+    error::{
+      Error,
+      Result,
+    },
+    prelude::*,
+  };
+}
@@ -0,0 +1,333 @@
+//! The fetch/execute loop shared by every dialect.
+//!
+//! `Machine` owns the program, the register file, the program counter and
+//! input/output queues. `run` keeps stepping until something stops it:
+//!
+//! | Stop | Reason |
+//! |------|--------|
+//! | `Halt::Finished` | Program counter left the program |
+//! | `Halt::Halted` | An instruction returned `Flow::Halt` |
+//! | `Halt::Blocked` | An instruction is waiting for input |
+//! | `Halt::Faulted` | An instruction returned `Flow::Fault` |
+//! | `Halt::StepLimit` | The configured step limit was reached |
+//! | `Halt::Loop` | Loop detection saw a repeated program counter or state |
+//! | `Halt::Paused` | A `run_with` hook asked to stop |
+//!
+//! # Examples
+//!
+//! ```
+//! use machine::{
+//!   dialect::Handheld,
+//!   prelude::*,
+//! };
+//!
+//! let source = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+//! let mut console = Machine::<Handheld>::parse(source)
+//!   .unwrap()
+//!   .with_loop_detection(LoopDetection::Pc)
+//!   .with_trace(true);
+//!
+//! assert_eq!(console.run(), Halt::Loop { pc: 1 });
+//! assert_eq!(console.register("acc"), Some(5));
+//! assert_eq!(console.trace().len(), 7);
+//! ```
+
+use crate::_prelude::*;
+
+/// Why `Machine::run` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Halt {
+  /// The program counter moved outside the program.
+  Finished,
+  /// An instruction explicitly halted the machine.
+  Halted,
+  /// An instruction is waiting for input; supply some and run again.
+  Blocked,
+  /// An instruction faulted, e.g. divided by zero or overflowed; `pc` still
+  /// points at it.
+  Faulted,
+  /// The configured step limit was reached.
+  StepLimit,
+  /// The machine was about to repeat itself at `pc`.
+  Loop { pc: i64 },
+  /// A `run_with` hook paused execution before the next instruction.
+  Paused,
+}
+
+/// How `Machine::run` decides that the program is looping.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoopDetection {
+  /// No detection; rely on a step limit for non-terminating programs.
+  #[default]
+  Off,
+  /// Stop before any instruction executes a second time.
+  Pc,
+  /// Stop before the (program counter, registers) state repeats.
+  State,
+}
+
+/// Register state recorded after one executed instruction.
+#[derive(Debug, Clone)]
+pub struct Snapshot<I> {
+  /// Number of instructions executed before this one.
+  pub step: u64,
+  /// Program counter of the executed instruction.
+  pub pc: i64,
+  /// The executed instruction.
+  pub instruction: I,
+  /// Register values after execution.
+  pub registers: Registers,
+}
+
+impl<I: Display> Display for Snapshot<I> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let instruction = self.instruction.to_string();
+    write!(
+      f,
+      "{:>8} | {:>4} | {instruction:<16} | {}",
+      self.step, self.pc, self.registers
+    )
+  }
+}
+
+/// A register machine running a program written in dialect `I`.
+#[derive(Debug, Clone)]
+pub struct Machine<I: Instruction> {
+  /// Loaded program; instructions may rewrite it while running.
+  pub program: Vec<I>,
+
+  /// Register file.
+  pub registers: Registers,
+
+  /// Index of the next instruction to execute.
+  pub pc: i64,
+
+  /// Register mirroring the instruction pointer, if the dialect binds one.
+  pub ip_binding: Option<Reg>,
+
+  /// Values waiting to be consumed by input instructions.
+  pub input: VecDeque<i64>,
+
+  /// Values produced by output instructions.
+  pub output: Vec<i64>,
+
+  /// Number of instructions executed so far.
+  pub steps: u64,
+
+  /// Execution count per instruction index.
+  pub profile: Vec<u64>,
+
+  step_limit: Option<u64>,
+  loop_detection: LoopDetection,
+  seen_pcs: HashSet<i64>,
+  seen_states: HashSet<(i64, Vec<i64>)>,
+  tracing: bool,
+  trace: Vec<Snapshot<I>>,
+}
+
+impl<I: Instruction> Machine<I> {
+  /// Loads a parsed program into a fresh machine.
+  #[must_use]
+  pub fn new(program: Program<I>) -> Self {
+    let mut registers = Registers::new(I::REGISTERS);
+    for (reg, value) in program.presets {
+      registers[reg] = value;
+    }
+
+    Self {
+      profile: vec![0; program.instructions.len()],
+      program: program.instructions,
+      registers,
+      pc: 0,
+      ip_binding: program.ip_binding,
+      input: VecDeque::new(),
+      output: Vec::new(),
+      steps: 0,
+      step_limit: None,
+      loop_detection: LoopDetection::Off,
+      seen_pcs: HashSet::new(),
+      seen_states: HashSet::new(),
+      tracing: false,
+      trace: Vec::new(),
+    }
+  }
+
+  /// Parses `source` with the dialect's program parser and loads it.
+  ///
+  /// # Errors
+  /// Returns the dialect's parse error.
+  pub fn parse(source: &str) -> Result<Self> {
+    I::parse_program(source).map(Self::new)
+  }
+
+  /// Sets a register by name before running.
+  ///
+  /// # Panics
+  /// Panics if `name` is not one of the dialect's registers; this is a
+  /// programming error rather than bad puzzle input.
+  #[must_use]
+  pub fn with_register(mut self, name: &str, value: i64) -> Self {
+    self
+      .registers
+      .set(name, value)
+      .unwrap_or_else(|error| panic!("{error}"));
+    self
+  }
+
+  /// Queues input values.
+  #[must_use]
+  pub fn with_input(mut self, values: impl IntoIterator<Item = i64>) -> Self {
+    self.input.extend(values);
+    self
+  }
+
+  /// Stops `run` after `limit` executed instructions in total.
+  #[must_use]
+  pub const fn with_step_limit(mut self, limit: u64) -> Self {
+    self.step_limit = Some(limit);
+    self
+  }
+
+  /// Selects the loop detection strategy used by `run`.
+  #[must_use]
+  pub const fn with_loop_detection(mut self, detection: LoopDetection) -> Self {
+    self.loop_detection = detection;
+    self
+  }
+
+  /// Records a `Snapshot` after every executed instruction.
+  #[must_use]
+  pub const fn with_trace(mut self, enabled: bool) -> Self {
+    self.tracing = enabled;
+    self
+  }
+
+  /// Reads a register by name.
+  #[must_use]
+  pub fn register(&self, name: &str) -> Option<i64> {
+    self.registers.get(name)
+  }
+
+  /// Resolves an operand against the current registers.
+  #[must_use]
+  pub fn value(&self, operand: Operand) -> i64 {
+    match operand {
+      Operand::Reg(reg) => self.registers[reg],
+      Operand::Imm(value) => value,
+    }
+  }
+
+  /// Index of the next instruction, if the program counter is in range.
+  #[must_use]
+  pub fn index(&self) -> Option<usize> {
+    usize::try_from(self.pc)
+      .ok()
+      .filter(|&index| index < self.program.len())
+  }
+
+  /// The next instruction to execute, if any.
+  #[must_use]
+  pub fn current(&self) -> Option<&I> {
+    self.index().map(|index| &self.program[index])
+  }
+
+  /// Recorded snapshots, oldest first.
+  #[must_use]
+  pub fn trace(&self) -> &[Snapshot<I>] {
+    &self.trace
+  }
+
+  /// Executes exactly one instruction, ignoring limits and loop detection.
+  ///
+  /// Returns `Some(Halt)` if the machine cannot or should not continue.
+  pub fn step(&mut self) -> Option<Halt> {
+    let Some(index) = self.index() else {
+      return Some(Halt::Finished);
+    };
+    if let Some(ip) = self.ip_binding {
+      self.registers[ip] = self.pc;
+    }
+
+    let pc = self.pc;
+    let instruction = self.program[index].clone();
+    let flow = instruction.execute(self);
+    let base = self.ip_binding.map_or(pc, |ip| self.registers[ip]);
+
+    let next = match flow {
+      Flow::Next => base.checked_add(1),
+      Flow::Jump(offset) => base.checked_add(offset),
+      Flow::Goto(target) => Some(target),
+      Flow::Halt => return Some(Halt::Halted),
+      Flow::Block => return Some(Halt::Blocked),
+      Flow::Fault => return Some(Halt::Faulted),
+    };
+    let Some(next) = next else {
+      return Some(Halt::Faulted);
+    };
+    self.pc = next;
+
+    if self.tracing {
+      self.trace.push(Snapshot {
+        step: self.steps,
+        pc,
+        instruction,
+        registers: self.registers.clone(),
+      });
+    }
+    if let Some(count) = self.profile.get_mut(index) {
+      *count += 1;
+    }
+    self.steps += 1;
+    None
+  }
+
+  /// Runs until the program stops, blocks, loops or exceeds the step limit.
+  pub fn run(&mut self) -> Halt {
+    self.run_with(|_| None)
+  }
+
+  /// Like `run`, but calls `hook` before every instruction.
+  ///
+  /// The hook may inspect or modify the machine (the analysis module's loop
+  /// accelerator rewrites registers and the program counter this way), and
+  /// stops the run by returning a `Halt`, usually `Halt::Paused`.
+  pub fn run_with(&mut self, mut hook: impl FnMut(&mut Self) -> Option<Halt>) -> Halt {
+    loop {
+      if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+        return Halt::StepLimit;
+      }
+      if let Some(halt) = hook(self) {
+        return halt;
+      }
+
+      let repeated = match self.loop_detection {
+        LoopDetection::Off => false,
+        LoopDetection::Pc => !self.seen_pcs.insert(self.pc),
+        LoopDetection::State => !self
+          .seen_states
+          .insert((self.pc, self.registers.values().to_vec())),
+      };
+      if repeated {
+        return Halt::Loop { pc: self.pc };
+      }
+
+      match self.step() {
+        None => {}
+        Some(Halt::Blocked) => {
+          // Retrying the blocked instruction later is not a repeat.
+          self.seen_pcs.remove(&self.pc);
+          self
+            .seen_states
+            .remove(&(self.pc, self.registers.values().to_vec()));
+          return Halt::Blocked;
+        }
+        Some(halt) => return halt,
+      }
+    }
+  }
+
+  /// Takes every output produced so far.
+  pub fn drain_output(&mut self) -> Vec<i64> {
+    std::mem::take(&mut self.output)
+  }
+}
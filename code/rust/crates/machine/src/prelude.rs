@@ -0,0 +1,41 @@
+pub use {
+  crate::{
+    analysis::{
+      BinOp,
+      Condition,
+      Effect,
+      Expr,
+      Target,
+    },
+    error::{
+      Error as MachineError,
+      Result as MachineResult,
+    },
+    machine::*,
+    program::*,
+    register::*,
+  },
+  miette::Diagnostic,
+  std::{
+    collections::{
+      BTreeMap,
+      BTreeSet,
+      HashMap,
+      HashSet,
+      VecDeque,
+    },
+    fmt::{
+      Debug,
+      Display,
+      Formatter,
+      Result as FmtResult,
+    },
+    ops::{
+      Index,
+      IndexMut,
+    },
+    result::Result as StdResult,
+    str::FromStr,
+  },
+  thiserror::Error as ThisError,
+};
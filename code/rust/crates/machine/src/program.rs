@@ -0,0 +1,201 @@
+//! Instruction sets and parsed programs.
+//!
+//! A dialect is a single type implementing `Instruction`: it names its
+//! registers, parses one source line, and executes itself against a
+//! `Machine`. Everything else (the fetch/execute loop, step limits, loop
+//! detection, tracing) is provided by the framework.
+//!
+//! # Examples
+//!
+//! A two-instruction dialect in a handful of lines:
+//!
+//! ```
+//! use machine::prelude::*;
+//!
+//! #[derive(Debug, Clone)]
+//! enum Toy {
+//!   Add(Reg, i64),
+//!   Jump(i64),
+//! }
+//!
+//! impl Instruction for Toy {
+//!   const REGISTERS: &'static [&'static str] = &["x"];
+//!
+//!   fn parse(line: &str) -> MachineResult<Self> {
+//!     match line.split_whitespace().collect::<Vec<_>>()[..] {
+//!       ["add", r, n] => Ok(Self::Add(
+//!         Self::reg(r, line)?,
+//!         n.parse().map_err(|_| MachineError::operand(n, line))?,
+//!       )),
+//!       ["jmp", n] => Ok(Self::Jump(
+//!         n.parse().map_err(|_| MachineError::operand(n, line))?,
+//!       )),
+//!       _ => Err(MachineError::unknown(line)),
+//!     }
+//!   }
+//!
+//!   fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+//!     match *self {
+//!       Self::Add(reg, n) => {
+//!         machine.registers[reg] += n;
+//!         Flow::Next
+//!       }
+//!       Self::Jump(offset) => Flow::Jump(offset),
+//!     }
+//!   }
+//! }
+//!
+//! impl std::fmt::Display for Toy {
+//!   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!     match self {
+//!       Self::Add(_, n) => write!(f, "add x {n}"),
+//!       Self::Jump(n) => write!(f, "jmp {n}"),
+//!     }
+//!   }
+//! }
+//!
+//! let mut machine = Machine::<Toy>::parse("add x 5\njmp 2\nadd x 100\nadd x 1").unwrap();
+//! assert_eq!(machine.run(), Halt::Finished);
+//! assert_eq!(machine.register("x"), Some(6));
+//! ```
+
+use crate::_prelude::*;
+
+/// Control-flow outcome of executing a single instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flow {
+  /// Continue with the following instruction.
+  Next,
+  /// Move the program counter by a relative offset.
+  Jump(i64),
+  /// Move the program counter to an absolute instruction index.
+  Goto(i64),
+  /// Stop execution; the program asked to halt.
+  Halt,
+  /// The instruction needs input that is not available yet.
+  ///
+  /// The program counter is left untouched so the instruction is retried on
+  /// the next `run` after input has been supplied.
+  Block,
+  /// The instruction is invalid for the current values, such as a division
+  /// by zero; the program crashes.
+  ///
+  /// The program counter is left at the faulting instruction.
+  Fault,
+}
+
+/// A dialect's instruction set.
+///
+/// Implementors describe how one line of source becomes an instruction and
+/// what executing it does. Instructions are cloned before execution, so they
+/// are free to rewrite the program they belong to (see assembunny's `tgl`).
+pub trait Instruction: Clone + Debug + Display + Sized {
+  /// Register names, in index order.
+  const REGISTERS: &'static [&'static str];
+
+  /// Parses a single, non-empty, trimmed source line.
+  ///
+  /// # Errors
+  /// Returns a parse error when the line is not a valid instruction.
+  fn parse(line: &str) -> Result<Self>;
+
+  /// Executes the instruction against the machine.
+  fn execute(&self, machine: &mut Machine<Self>) -> Flow;
+
+  /// Describes the instruction for static analysis.
+  ///
+  /// The default treats every instruction as `Effect::Opaque`, which keeps
+  /// the analyzer from reasoning about (or accelerating) any of it.
+  fn effect(&self) -> Effect {
+    Effect::Opaque
+  }
+
+  /// Parses a whole program.
+  ///
+  /// The default implementation parses every non-blank line with
+  /// `Instruction::parse`. Dialects with directives (`#ip`) or a
+  /// non-line-based format override this.
+  ///
+  /// # Errors
+  /// Returns the first parse error, annotated with its line number.
+  fn parse_program(source: &str) -> Result<Program<Self>> {
+    Program::parse_lines(source)
+  }
+
+  /// Resolves a register name, for use inside `parse`.
+  ///
+  /// # Errors
+  /// Returns `Error::InvalidOperand` if `token` is not a register.
+  fn reg(token: &str, line: &str) -> Result<Reg> {
+    Reg::parse(token, Self::REGISTERS).ok_or_else(|| Error::operand(token, line))
+  }
+
+  /// Parses a register-or-literal operand, for use inside `parse`.
+  ///
+  /// # Errors
+  /// Returns `Error::InvalidOperand` if `token` is neither.
+  fn operand(token: &str, line: &str) -> Result<Operand> {
+    Operand::parse(token, Self::REGISTERS).ok_or_else(|| Error::operand(token, line))
+  }
+}
+
+/// A parsed program, ready to be loaded into a `Machine`.
+#[derive(Debug, Clone)]
+pub struct Program<I> {
+  /// Instructions, indexed by program counter.
+  pub instructions: Vec<I>,
+
+  /// Register bound to the instruction pointer (`ElfCode`'s `#ip`).
+  pub ip_binding: Option<Reg>,
+
+  /// Initial register values declared by the source.
+  pub presets: Vec<(Reg, i64)>,
+}
+
+impl<I: Instruction> Program<I> {
+  /// Creates a program from already-parsed instructions.
+  #[must_use]
+  pub const fn new(instructions: Vec<I>) -> Self {
+    Self {
+      instructions,
+      ip_binding: None,
+      presets: Vec::new(),
+    }
+  }
+
+  /// Parses every non-blank line with `I::parse`.
+  ///
+  /// # Errors
+  /// Returns the first parse error, annotated with its 1-based line number.
+  pub fn parse_lines(source: &str) -> Result<Self> {
+    source
+      .lines()
+      .enumerate()
+      .map(|(index, line)| (index + 1, line.trim()))
+      .filter(|(_, line)| !line.is_empty())
+      .map(|(number, line)| I::parse(line).map_err(|error| error.at_line(number)))
+      .collect::<Result<Vec<_>>>()
+      .map(Self::new)
+  }
+
+  /// Number of instructions.
+  #[must_use]
+  pub const fn len(&self) -> usize {
+    self.instructions.len()
+  }
+
+  /// Whether the program has no instructions.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.instructions.is_empty()
+  }
+}
+
+impl<I: Display> Display for Program<I> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    for (index, instruction) in self.instructions.iter().enumerate() {
+      writeln!(f, "{index:>4}: {instruction}")?;
+    }
+    Ok(())
+  }
+}
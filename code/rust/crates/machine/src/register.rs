@@ -0,0 +1,153 @@
+//! Registers and operands shared by every dialect.
+//!
+//! A dialect names its registers once through `Instruction::REGISTERS`; the
+//! machine allocates one `i64` slot per name and instructions address them by
+//! index through `Reg`. Source operands that may be either a register or a
+//! literal are parsed into an `Operand`.
+//!
+//! # Examples
+//!
+//! ```
+//! use machine::prelude::*;
+//!
+//! const NAMES: &[&str] = &["a", "b"];
+//!
+//! let mut registers = Registers::new(NAMES);
+//! let b = Reg::parse("b", NAMES).unwrap();
+//! registers[b] = 42;
+//!
+//! assert_eq!(registers.get("b"), Some(42));
+//! assert_eq!(registers.to_string(), "a=0 b=42");
+//! assert_eq!(Operand::parse("-7", NAMES), Some(Operand::Imm(-7)));
+//! ```
+
+use crate::_prelude::*;
+
+/// Index of a register within a dialect's register file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Reg(pub usize);
+
+impl Reg {
+  /// Looks up a register by name in `names`.
+  #[must_use]
+  pub fn parse(token: &str, names: &[&str]) -> Option<Self> {
+    names.iter().position(|name| *name == token).map(Self)
+  }
+}
+
+/// Either a register reference or an immediate value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+  Reg(Reg),
+  Imm(i64),
+}
+
+impl Operand {
+  /// Parses a token as an integer literal, falling back to a register name.
+  #[must_use]
+  pub fn parse(token: &str, names: &[&str]) -> Option<Self> {
+    token
+      .parse()
+      .ok()
+      .map(Self::Imm)
+      .or_else(|| Reg::parse(token, names).map(Self::Reg))
+  }
+
+  /// The register this operand refers to, if any.
+  #[must_use]
+  pub const fn reg(self) -> Option<Reg> {
+    match self {
+      Self::Reg(reg) => Some(reg),
+      Self::Imm(_) => None,
+    }
+  }
+
+  /// Formats the operand using the dialect's register names.
+  #[must_use]
+  pub fn display(self, names: &[&str]) -> String {
+    match self {
+      Self::Reg(Reg(index)) => names
+        .get(index)
+        .map_or_else(|| format!("r{index}"), |n| (*n).to_owned()),
+      Self::Imm(value) => value.to_string(),
+    }
+  }
+}
+
+/// Register file: one `i64` per name in the dialect's `REGISTERS`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Registers {
+  names: &'static [&'static str],
+  values: Vec<i64>,
+}
+
+impl Registers {
+  /// Creates a zeroed register file for the given names.
+  #[must_use]
+  pub fn new(names: &'static [&'static str]) -> Self {
+    Self {
+      names,
+      values: vec![0; names.len()],
+    }
+  }
+
+  /// Register names, in index order.
+  #[must_use]
+  pub const fn names(&self) -> &'static [&'static str] {
+    self.names
+  }
+
+  /// Raw register values, in index order.
+  #[must_use]
+  pub fn values(&self) -> &[i64] {
+    &self.values
+  }
+
+  /// Mutable raw register values, in index order.
+  pub fn values_mut(&mut self) -> &mut [i64] {
+    &mut self.values
+  }
+
+  /// Reads a register by name.
+  #[must_use]
+  pub fn get(&self, name: &str) -> Option<i64> {
+    Reg::parse(name, self.names).map(|reg| self[reg])
+  }
+
+  /// Writes a register by name.
+  ///
+  /// # Errors
+  /// Returns `Error::UnknownRegister` if `name` is not part of the dialect.
+  pub fn set(&mut self, name: &str, value: i64) -> Result<()> {
+    let reg =
+      Reg::parse(name, self.names).ok_or_else(|| Error::UnknownRegister(name.to_owned()))?;
+    self[reg] = value;
+    Ok(())
+  }
+}
+
+impl Index<Reg> for Registers {
+  type Output = i64;
+
+  fn index(&self, reg: Reg) -> &i64 {
+    &self.values[reg.0]
+  }
+}
+
+impl IndexMut<Reg> for Registers {
+  fn index_mut(&mut self, reg: Reg) -> &mut i64 {
+    &mut self.values[reg.0]
+  }
+}
+
+impl Display for Registers {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    for (index, (name, value)) in self.names.iter().zip(&self.values).enumerate() {
+      if index > 0 {
+        write!(f, " ")?;
+      }
+      write!(f, "{name}={value}")?;
+    }
+    Ok(())
+  }
+}
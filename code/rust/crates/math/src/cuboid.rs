@@ -0,0 +1,428 @@
+//! Axis-aligned boxes in `N` dimensions and sets of them.
+//!
+//! `Cuboid` is the `N`-dimensional counterpart of an inclusive interval;
+//! subtracting one from another splits the rest into at most `2 * N`
+//! disjoint pieces. `BoxSet` builds on that to keep a union of boxes as
+//! disjoint pieces under any sequence of additions and removals, which is
+//! exactly 2021/22's reactor reboot.
+//!
+//! # Examples
+//!
+//! ```
+//! use math::prelude::*;
+//!
+//! let cube = |lo: i64, hi: i64| Cuboid::from_ranges([lo..=hi, lo..=hi, lo..=hi]).unwrap();
+//!
+//! let mut reactor = BoxSet::new();
+//! reactor.add(cube(10, 12));
+//! reactor.add(cube(11, 13));
+//! reactor.remove(cube(9, 11));
+//! reactor.add(cube(10, 10));
+//! assert_eq!(reactor.volume(), 39);
+//! assert!(reactor.contains([13, 13, 13]));
+//! assert!(!reactor.contains([11, 11, 11]));
+//! ```
+
+use crate::_prelude::*;
+
+/// An axis-aligned box of integer points, inclusive on every side.
+///
+/// # Examples
+///
+/// 2018/23's nanobot ranges are octahedra, which become boxes once every
+/// point is rotated into the four diagonal coordinates:
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let diagonal = |[x, y, z]: [i64; 3]| [x + y + z, x + y - z, x - y + z, -x + y + z];
+/// let range = |center: [i64; 3], r: i64| {
+///   let u = diagonal(center);
+///   Cuboid::new(u.map(|u| u - r), u.map(|u| u + r)).unwrap()
+/// };
+///
+/// let a = range([10, 12, 12], 2);
+/// let b = range([12, 14, 12], 2);
+/// let both = a.intersection(&b).unwrap();
+/// assert!(both.contains(diagonal([11, 13, 12])));
+/// assert!(!range([20, 20, 20], 1).intersects(&a));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cuboid<const N: usize> {
+  /// Smallest coordinate on every axis.
+  pub min: [i64; N],
+  /// Largest coordinate on every axis.
+  pub max: [i64; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+  /// The box spanning `min..=max`, unless it is empty on some axis.
+  #[must_use]
+  pub fn new(min: [i64; N], max: [i64; N]) -> Option<Self> {
+    (0..N)
+      .all(|axis| min[axis] <= max[axis])
+      .then_some(Self { min, max })
+  }
+
+  /// The box spanning one range per axis.
+  #[must_use]
+  pub fn from_ranges(ranges: [RangeInclusive<i64>; N]) -> Option<Self> {
+    let bounds = ranges.map(RangeInclusive::into_inner);
+    Self::new(bounds.map(|(lo, _)| lo), bounds.map(|(_, hi)| hi))
+  }
+
+  /// The box holding a single point.
+  #[must_use]
+  pub const fn point(point: [i64; N]) -> Self {
+    Self {
+      min: point,
+      max: point,
+    }
+  }
+
+  /// The range covered on every axis.
+  #[must_use]
+  pub fn ranges(&self) -> [RangeInclusive<i64>; N] {
+    array::from_fn(|axis| self.min[axis]..=self.max[axis])
+  }
+
+  /// Number of points in the box.
+  #[must_use]
+  pub fn volume(&self) -> u128 {
+    (0..N)
+      .map(|axis| u128::from(self.max[axis].abs_diff(self.min[axis])) + 1)
+      .product()
+  }
+
+  /// Whether `point` lies in the box.
+  #[must_use]
+  pub fn contains(&self, point: [i64; N]) -> bool {
+    (0..N).all(|axis| self.min[axis] <= point[axis] && point[axis] <= self.max[axis])
+  }
+
+  /// Whether every point of `other` lies in the box.
+  #[must_use]
+  pub fn encloses(&self, other: &Self) -> bool {
+    self.contains(other.min) && self.contains(other.max)
+  }
+
+  /// Whether the two boxes share a point.
+  #[must_use]
+  pub fn intersects(&self, other: &Self) -> bool {
+    (0..N).all(|axis| self.min[axis] <= other.max[axis] && other.min[axis] <= self.max[axis])
+  }
+
+  /// The points both boxes share.
+  #[must_use]
+  pub fn intersection(&self, other: &Self) -> Option<Self> {
+    Self::new(
+      array::from_fn(|axis| self.min[axis].max(other.min[axis])),
+      array::from_fn(|axis| self.max[axis].min(other.max[axis])),
+    )
+  }
+
+  /// The points of this box outside `other`, as at most `2 * N` disjoint
+  /// boxes.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use math::prelude::*;
+  ///
+  /// let square = Cuboid::from_ranges([0..=3, 0..=3]).unwrap();
+  /// let hole = Cuboid::from_ranges([1..=2, 1..=5]).unwrap();
+  ///
+  /// let pieces = square.subtract(&hole);
+  /// assert_eq!(pieces.len(), 3);
+  /// assert_eq!(pieces.iter().map(Cuboid::volume).sum::<u128>(), 10);
+  /// ```
+  #[must_use]
+  pub fn subtract(&self, other: &Self) -> Vec<Self> {
+    let Some(overlap) = self.intersection(other) else {
+      return vec![*self];
+    };
+    let mut pieces = Vec::new();
+    let mut rest = *self;
+    for axis in 0..N {
+      if rest.min[axis] < overlap.min[axis] {
+        let mut below = rest;
+        below.max[axis] = overlap.min[axis] - 1;
+        pieces.push(below);
+      }
+      if overlap.max[axis] < rest.max[axis] {
+        let mut above = rest;
+        above.min[axis] = overlap.max[axis] + 1;
+        pieces.push(above);
+      }
+      rest.min[axis] = overlap.min[axis];
+      rest.max[axis] = overlap.max[axis];
+    }
+    pieces
+  }
+}
+
+/// A union of boxes, kept as disjoint pieces.
+///
+/// Every addition first carves the new box out of the existing pieces, so
+/// the pieces never overlap and the volume is their plain sum.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BoxSet<const N: usize> {
+  pieces: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> BoxSet<N> {
+  /// The empty set.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { pieces: Vec::new() }
+  }
+
+  /// Adds every point of `cuboid`.
+  pub fn add(&mut self, cuboid: Cuboid<N>) {
+    self.remove(cuboid);
+    self.pieces.push(cuboid);
+  }
+
+  /// Removes every point of `cuboid`.
+  pub fn remove(&mut self, cuboid: Cuboid<N>) {
+    self.pieces = self
+      .pieces
+      .iter()
+      .flat_map(|piece| piece.subtract(&cuboid))
+      .collect();
+  }
+
+  /// Number of points in the set.
+  #[must_use]
+  pub fn volume(&self) -> u128 {
+    self.pieces.iter().map(Cuboid::volume).sum()
+  }
+
+  /// Number of points in the set that also lie in `bounds`.
+  #[must_use]
+  pub fn volume_within(&self, bounds: &Cuboid<N>) -> u128 {
+    self
+      .pieces
+      .iter()
+      .filter_map(|piece| piece.intersection(bounds))
+      .map(|piece| piece.volume())
+      .sum()
+  }
+
+  /// Whether `point` is in the set.
+  #[must_use]
+  pub fn contains(&self, point: [i64; N]) -> bool {
+    self.pieces.iter().any(|piece| piece.contains(point))
+  }
+
+  /// The disjoint pieces making up the set.
+  pub fn iter(&self) -> impl Iterator<Item = &Cuboid<N>> {
+    self.pieces.iter()
+  }
+
+  /// Whether the set has no points.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.pieces.is_empty()
+  }
+}
+
+impl<const N: usize> Default for BoxSet<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const N: usize> FromIterator<Cuboid<N>> for BoxSet<N> {
+  fn from_iter<I: IntoIterator<Item = Cuboid<N>>>(cuboids: I) -> Self {
+    let mut set = Self::new();
+    for cuboid in cuboids {
+      set.add(cuboid);
+    }
+    set
+  }
+}
+
+/// Maps a sparse set of coordinates onto consecutive cell indices.
+///
+/// The boundaries of every range passed in split the axis into cells; each
+/// cell is a run of coordinates that every one of those ranges either fully
+/// covers or misses. Puzzles with huge coordinates but few distinct ranges
+/// (2021/22, 2022/18's flood fill) can then work on a small grid of cells,
+/// weighting each by its width.
+///
+/// # Examples
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let axis = Compression::new([0..=9, 3..=4, 20..=20]);
+/// assert_eq!(axis.len(), 5);
+/// assert_eq!(axis.range(1), 3..=4);
+/// assert_eq!(axis.width(3), 10);
+/// assert_eq!(axis.cell(7), Some(2));
+/// assert_eq!(axis.cell(21), None);
+/// assert_eq!(axis.cells(3..=9), 1..3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Compression {
+  boundaries: Vec<i64>,
+}
+
+impl Compression {
+  /// Splits the axis at the start and past the end of every range.
+  #[must_use]
+  pub fn new(ranges: impl IntoIterator<Item = RangeInclusive<i64>>) -> Self {
+    let mut boundaries = ranges
+      .into_iter()
+      .filter(|range| !range.is_empty())
+      .flat_map(|range| [*range.start(), range.end().saturating_add(1)])
+      .collect::<Vec<_>>();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    Self { boundaries }
+  }
+
+  /// Number of cells between the smallest and largest boundary.
+  #[must_use]
+  pub const fn len(&self) -> usize {
+    self.boundaries.len().saturating_sub(1)
+  }
+
+  /// Whether there are no cells.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The cell containing `value`.
+  #[must_use]
+  pub fn cell(&self, value: i64) -> Option<usize> {
+    let index = self
+      .boundaries
+      .partition_point(|&boundary| boundary <= value);
+    (1..=self.len()).contains(&index).then(|| index - 1)
+  }
+
+  /// The coordinates of a cell.
+  ///
+  /// # Panics
+  /// If `index` is not below `len()`.
+  #[must_use]
+  pub fn range(&self, index: usize) -> RangeInclusive<i64> {
+    self.boundaries[index]..=self.boundaries[index + 1] - 1
+  }
+
+  /// Number of coordinates in a cell.
+  ///
+  /// # Panics
+  /// If `index` is not below `len()`.
+  #[must_use]
+  pub fn width(&self, index: usize) -> u64 {
+    self.boundaries[index + 1].abs_diff(self.boundaries[index])
+  }
+
+  /// The cells covering `range`, which should be one of the ranges the
+  /// compression was built from.
+  #[must_use]
+  pub fn cells(&self, range: RangeInclusive<i64>) -> Range<usize> {
+    let start = self.boundaries.partition_point(|&b| b < *range.start());
+    let end = self.boundaries.partition_point(|&b| b <= *range.end());
+    start..end.max(start)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    std::collections::HashSet,
+  };
+
+  /// Every box in `0..SIZE` on both axes.
+  fn squares<const SIZE: i64>() -> Vec<Cuboid<2>> {
+    let spans = (0..SIZE)
+      .flat_map(|lo| (lo..SIZE).map(move |hi| lo..=hi))
+      .collect::<Vec<_>>();
+    spans
+      .iter()
+      .flat_map(|x| {
+        spans
+          .iter()
+          .map(|y| Cuboid::from_ranges([x.clone(), y.clone()]))
+      })
+      .flatten()
+      .collect()
+  }
+
+  fn points(cuboid: &Cuboid<2>) -> HashSet<[i64; 2]> {
+    let [xs, ys] = cuboid.ranges();
+    xs.flat_map(|x| ys.clone().map(move |y| [x, y])).collect()
+  }
+
+  #[test]
+  fn subtract_yields_disjoint_pieces_covering_the_rest() {
+    let squares = squares::<4>();
+    for a in &squares {
+      for b in &squares {
+        let pieces = a.subtract(b);
+        assert!(pieces.len() <= 4);
+
+        for (index, piece) in pieces.iter().enumerate() {
+          assert!(a.encloses(piece));
+          assert!(!piece.intersects(b), "{a:?} - {b:?}");
+          assert!(
+            pieces[index + 1..]
+              .iter()
+              .all(|other| !piece.intersects(other))
+          );
+        }
+
+        let covered = pieces.iter().flat_map(points).collect::<HashSet<_>>();
+        let expected = &points(a) - &points(b);
+        assert_eq!(covered, expected, "{a:?} - {b:?}");
+        assert_eq!(
+          pieces.iter().map(Cuboid::volume).sum::<u128>(),
+          expected.len() as u128
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn subtract_handles_extreme_coordinates() {
+    let everything =
+      Cuboid::from_ranges([i64::MIN..=i64::MAX, -9..=9, i64::MIN..=i64::MIN + 9]).unwrap();
+    let origin = Cuboid::point([0, 0, i64::MIN + 5]);
+    let pieces = everything.subtract(&origin);
+    assert_eq!(pieces.len(), 6);
+    assert_eq!(
+      pieces.iter().map(Cuboid::volume).sum::<u128>(),
+      everything.volume() - 1
+    );
+    assert!(everything.subtract(&everything).is_empty());
+    assert_eq!(origin.subtract(&Cuboid::point([i64::MAX; 3])), [origin]);
+  }
+
+  #[test]
+  fn box_sets_match_plain_sets() {
+    let squares = squares::<5>();
+    let mut set = BoxSet::new();
+    let mut expected = HashSet::new();
+    for (step, square) in squares.iter().enumerate().step_by(7) {
+      if step % 3 == 0 {
+        set.remove(*square);
+        expected = &expected - &points(square);
+      } else {
+        set.add(*square);
+        expected.extend(points(square));
+      }
+      assert_eq!(set.volume(), expected.len() as u128);
+    }
+    for x in -1..=5 {
+      for y in -1..=5 {
+        assert_eq!(set.contains([x, y]), expected.contains(&[x, y]));
+      }
+    }
+  }
+}
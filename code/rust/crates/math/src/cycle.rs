@@ -0,0 +1,457 @@
+//! Cycle detection and skipping ahead in long simulations.
+//!
+//! Puzzles like 2017/16, 2018/18 and 2023/14 ask for the state after a
+//! billion steps of a process that starts repeating after a few hundred.
+//! Every such process is a sequence `x0, f(x0), f(f(x0)), ...` that enters a
+//! cycle after `start` steps and then repeats every `length` steps; once
+//! those two numbers are known, step `n` is the same as step
+//! `Cycle::reduce(n)`.
+//!
+//! - `floyd` and `brent` find the cycle in constant memory, for states that
+//!   are cheap to recompute.
+//! - `Detector` remembers a hashable key per step, for states that can only
+//!   be compared through part of themselves (2022/17's rock tower).
+//! - `simulate_until` runs a process for `n` steps, jumping ahead as soon as
+//!   it repeats.
+//!
+//! # Examples
+//!
+//! ```
+//! use math::prelude::*;
+//!
+//! let rotate = |dancers: &String| format!("{}{}", &dancers[1..], &dancers[..1]);
+//!
+//! let simulation = simulate_until(1_000_000_000, "abcdefg".to_owned(), rotate);
+//! assert_eq!(simulation.state, "gabcdef");
+//! assert_eq!(
+//!   simulation.cycle,
+//!   Some(Cycle {
+//!     start: 0,
+//!     length: 7
+//!   })
+//! );
+//! assert_eq!(simulation.simulated, 7);
+//! ```
+
+use crate::_prelude::*;
+
+/// Where a sequence starts repeating and how often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cycle {
+  /// Index of the first state that is part of the cycle.
+  pub start: usize,
+  /// Number of states in the cycle; never zero.
+  pub length: usize,
+}
+
+impl Cycle {
+  /// The earliest step whose state equals the state at step `n`.
+  #[must_use]
+  pub const fn reduce(self, n: usize) -> usize {
+    if n < self.start {
+      n
+    } else {
+      self.start + (n - self.start) % self.length
+    }
+  }
+
+  /// Number of full cycles between step `reduce(n)` and step `n`.
+  #[must_use]
+  pub const fn repeats(self, n: usize) -> usize {
+    if n < self.start {
+      0
+    } else {
+      (n - self.start) / self.length
+    }
+  }
+
+  /// Extrapolates a quantity that grows by the same amount every cycle,
+  /// like the tower height of 2022/17.
+  ///
+  /// `value(i)` must be known for every `i <= start + length`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use math::prelude::*;
+  ///
+  /// let heights = [0, 4, 5, 9, 10, 14, 15];
+  /// let cycle = Cycle {
+  ///   start: 1,
+  ///   length: 2,
+  /// };
+  /// assert_eq!(cycle.extrapolate(6, |i| heights[i]), 15);
+  /// assert_eq!(cycle.extrapolate(1_000_000, |i| heights[i]), 2_500_000);
+  /// ```
+  pub fn extrapolate(self, n: usize, mut value: impl FnMut(usize) -> i64) -> i64 {
+    let reduced = self.reduce(n);
+    if reduced == n {
+      return value(n);
+    }
+    let growth = value(self.start + self.length) - value(self.start);
+    let repeats = i64::try_from(self.repeats(n)).unwrap_or(i64::MAX);
+    value(reduced) + repeats * growth
+  }
+}
+
+/// Finds the cycle of `x0, step(x0), ...` with Floyd's tortoise and hare.
+///
+/// Needs about `3 * (start + length)` calls to `step` and no memory beyond
+/// three states. Never returns if the sequence does not repeat.
+///
+/// # Examples
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let next = [1, 2, 3, 4, 5, 3];
+/// assert_eq!(
+///   floyd(0, |&x| next[x]),
+///   Cycle {
+///     start: 3,
+///     length: 3
+///   }
+/// );
+/// ```
+pub fn floyd<T: Clone + PartialEq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+  let mut tortoise = step(&initial);
+  let mut hare = step(&tortoise);
+  while tortoise != hare {
+    tortoise = step(&tortoise);
+    let next = step(&hare);
+    hare = step(&next);
+  }
+
+  let mut start = 0;
+  tortoise = initial;
+  while tortoise != hare {
+    tortoise = step(&tortoise);
+    hare = step(&hare);
+    start += 1;
+  }
+
+  let mut length = 1;
+  hare = step(&tortoise);
+  while tortoise != hare {
+    hare = step(&hare);
+    length += 1;
+  }
+
+  Cycle { start, length }
+}
+
+/// Finds the cycle of `x0, step(x0), ...` with Brent's algorithm.
+///
+/// Usually needs fewer calls to `step` than `floyd`, with the same constant
+/// memory. Never returns if the sequence does not repeat.
+///
+/// # Examples
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let next = [1, 2, 3, 4, 5, 3];
+/// assert_eq!(
+///   brent(0, |&x| next[x]),
+///   Cycle {
+///     start: 3,
+///     length: 3
+///   }
+/// );
+/// ```
+pub fn brent<T: Clone + PartialEq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+  let mut power = 1;
+  let mut length = 1;
+  let mut tortoise = initial.clone();
+  let mut hare = step(&initial);
+  while tortoise != hare {
+    if power == length {
+      tortoise = hare.clone();
+      power *= 2;
+      length = 0;
+    }
+    hare = step(&hare);
+    length += 1;
+  }
+
+  tortoise = initial.clone();
+  hare = initial;
+  for _ in 0..length {
+    hare = step(&hare);
+  }
+  let mut start = 0;
+  while tortoise != hare {
+    tortoise = step(&tortoise);
+    hare = step(&hare);
+    start += 1;
+  }
+
+  Cycle { start, length }
+}
+
+/// Detects a cycle from a stream of per-step keys.
+///
+/// Feed it the key of every state in order; the first key seen twice closes
+/// the cycle. Keys only need to identify the part of a state that determines
+/// its future, so accumulating counters can be left out, and the detector
+/// fits inside loops that do other bookkeeping between steps.
+///
+/// # Examples
+///
+/// 2022/17 repeats once the rock shape, the jet position and the top of the
+/// tower line up again, while the height keeps growing:
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let mut detector = Detector::new();
+/// let mut heights = Vec::new();
+/// let (mut rock, mut height) = (0, 0);
+/// let cycle = loop {
+///   heights.push(height);
+///   if let Some(cycle) = detector.observe(rock) {
+///     break cycle;
+///   }
+///   height += [1, 3, 3, 4, 2][rock];
+///   rock = (rock + 1) % 5;
+/// };
+///
+/// assert_eq!(
+///   cycle,
+///   Cycle {
+///     start: 0,
+///     length: 5
+///   }
+/// );
+/// assert_eq!(cycle.extrapolate(2022, |i| heights[i]), 5_256);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Detector<K> {
+  seen: HashMap<K, usize>,
+  steps: usize,
+}
+
+impl<K: Hash + Eq> Detector<K> {
+  /// A detector that has not seen any step yet.
+  #[must_use]
+  pub fn new() -> Self {
+    Self {
+      seen: HashMap::new(),
+      steps: 0,
+    }
+  }
+
+  /// Records the key of the next state, returning the cycle if the key has
+  /// been seen before.
+  pub fn observe(&mut self, key: K) -> Option<Cycle> {
+    let index = self.steps;
+    self.steps += 1;
+    match self.seen.entry(key) {
+      Entry::Occupied(first) => Some(Cycle {
+        start: *first.get(),
+        length: index - first.get(),
+      }),
+      Entry::Vacant(slot) => {
+        slot.insert(index);
+        None
+      }
+    }
+  }
+
+  /// Number of keys observed so far.
+  #[must_use]
+  pub const fn steps(&self) -> usize {
+    self.steps
+  }
+}
+
+impl<K: Hash + Eq> Default for Detector<K> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Result of `simulate_until`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation<T> {
+  /// The state at the requested step.
+  pub state: T,
+  /// The cycle, if one was found before reaching the requested step.
+  pub cycle: Option<Cycle>,
+  /// Number of times the step function was called.
+  pub simulated: usize,
+}
+
+/// The state after `n` applications of `step`, skipping ahead once the
+/// states repeat.
+///
+/// Keeps every state until the cycle closes.
+pub fn simulate_until<T: Clone + Hash + Eq>(
+  n: usize,
+  initial: T,
+  step: impl FnMut(&T) -> T,
+) -> Simulation<T> {
+  simulate_until_by_key(n, initial, step, T::clone)
+}
+
+/// Like `simulate_until`, comparing states through `key`.
+///
+/// The returned state is the recorded one at the equivalent step
+/// `Cycle::reduce(n)`: parts of the state left out of the key keep the
+/// values they had there, and can be corrected with `Cycle::extrapolate`.
+///
+/// # Examples
+///
+/// ```
+/// use math::prelude::*;
+///
+/// // (position on a 4-tile loop, laps completed)
+/// let step = |&(tile, laps): &(u8, u64)| ((tile + 1) % 4, laps + u64::from(tile == 3));
+///
+/// let simulation = simulate_until_by_key(1_000_001, (0, 0), step, |&(tile, _)| tile);
+/// assert_eq!(simulation.state, (1, 0));
+/// assert_eq!(
+///   simulation.cycle,
+///   Some(Cycle {
+///     start: 0,
+///     length: 4
+///   })
+/// );
+/// ```
+pub fn simulate_until_by_key<T, K: Hash + Eq>(
+  n: usize,
+  initial: T,
+  mut step: impl FnMut(&T) -> T,
+  mut key: impl FnMut(&T) -> K,
+) -> Simulation<T> {
+  let mut detector = Detector::new();
+  let mut history = Vec::new();
+  let mut state = initial;
+  for simulated in 0..n {
+    if let Some(cycle) = detector.observe(key(&state)) {
+      history.truncate(cycle.reduce(n) + 1);
+      return Simulation {
+        state: history.pop().unwrap_or(state),
+        cycle: Some(cycle),
+        simulated,
+      };
+    }
+    let next = step(&state);
+    history.push(state);
+    state = next;
+  }
+  Simulation {
+    state,
+    cycle: None,
+    simulated: n,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// `x -> (x * x + c) % m`: a small functional graph with tails and cycles
+  /// of every shape.
+  fn square_plus(c: u64, m: u64) -> impl Fn(&u64) -> u64 {
+    move |&x| (x * x + c) % m
+  }
+
+  /// The cycle found by remembering every state.
+  fn naive(initial: u64, step: impl Fn(&u64) -> u64) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for index in 0.. {
+      if let Some(&start) = seen.get(&state) {
+        return Cycle {
+          start,
+          length: index - start,
+        };
+      }
+      seen.insert(state, index);
+      state = step(&state);
+    }
+    unreachable!()
+  }
+
+  #[test]
+  fn brent_and_floyd_agree() {
+    for m in 1..40 {
+      for c in 0..m {
+        for initial in [0, 1, m / 2, m - 1] {
+          let expected = naive(initial, square_plus(c, m));
+          assert_eq!(
+            floyd(initial, square_plus(c, m)),
+            expected,
+            "c={c} m={m} x0={initial}"
+          );
+          assert_eq!(
+            brent(initial, square_plus(c, m)),
+            expected,
+            "c={c} m={m} x0={initial}"
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn fixed_points_have_length_one() {
+    let cycle = Cycle {
+      start: 0,
+      length: 1,
+    };
+    assert_eq!(floyd(7, |&x: &u64| x), cycle);
+    assert_eq!(brent(7, |&x: &u64| x), cycle);
+
+    let cycle = Cycle {
+      start: 5,
+      length: 1,
+    };
+    assert_eq!(floyd(0, |&x: &u64| (x + 1).min(5)), cycle);
+    assert_eq!(brent(0, |&x: &u64| (x + 1).min(5)), cycle);
+  }
+
+  #[test]
+  fn reduce_keeps_the_tail() {
+    let cycle = Cycle {
+      start: 3,
+      length: 4,
+    };
+    assert_eq!(
+      (0..10).map(|n| cycle.reduce(n)).collect::<Vec<_>>(),
+      [0, 1, 2, 3, 4, 5, 6, 3, 4, 5]
+    );
+    assert_eq!(
+      (0..10).map(|n| cycle.repeats(n)).collect::<Vec<_>>(),
+      [0, 0, 0, 0, 0, 0, 0, 1, 1, 1]
+    );
+  }
+
+  #[test]
+  fn detector_matches_naive() {
+    let step = square_plus(3, 31);
+    let mut detector = Detector::new();
+    let mut state = 2;
+    let cycle = loop {
+      if let Some(cycle) = detector.observe(state) {
+        break cycle;
+      }
+      state = step(&state);
+    };
+    assert_eq!(cycle, naive(2, &step));
+    assert_eq!(detector.steps(), cycle.start + cycle.length + 1);
+  }
+
+  #[test]
+  fn simulate_until_matches_plain_iteration() {
+    let step = square_plus(1, 97);
+    let mut states = vec![5];
+    for _ in 0..200 {
+      states.push(step(states.last().unwrap()));
+    }
+    for (n, expected) in states.iter().enumerate() {
+      assert_eq!(simulate_until(n, 5, &step).state, *expected, "n={n}");
+    }
+  }
+}
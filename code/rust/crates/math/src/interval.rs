@@ -0,0 +1,485 @@
+//! Sets of integers stored as sorted, disjoint intervals.
+//!
+//! `IntervalSet` keeps its intervals normalized: sorted, non-empty, and
+//! merged whenever they overlap or touch, so two sets holding the same
+//! values always compare equal. Intervals are inclusive on both ends, which
+//! lets a set reach `T::MAX` (2016/20 blocks up to `u32::MAX`).
+//!
+//! `RangeMap` is the piecewise offset mapping of 2023/05: every source
+//! interval is moved to a new start, everything else maps to itself.
+//! Applying it to a set splits the set's intervals at the map's boundaries.
+//!
+//! # Examples
+//!
+//! 2016/20, finding the lowest address no rule blocks:
+//!
+//! ```
+//! use math::prelude::*;
+//!
+//! let blocked = IntervalSet::from_iter([5..=8, 0..=2, 4..=7]);
+//! assert_eq!(blocked.to_string(), "{0..=2, 4..=8}");
+//!
+//! let allowed = blocked.complement(0..=9u32);
+//! assert_eq!(allowed.min(), Some(3));
+//! assert_eq!(allowed.count(), 2);
+//! ```
+
+use crate::_prelude::*;
+
+/// An integer type that intervals can be built over.
+///
+/// Arithmetic on bounds happens in `i128`, which holds every implementing
+/// type exactly.
+pub trait Discrete: Copy + Ord + Debug + Display {
+  /// Smallest representable value.
+  const MIN: Self;
+  /// Largest representable value.
+  const MAX: Self;
+
+  /// Widens the value.
+  fn to_i128(self) -> i128;
+
+  /// Narrows a value, if it is representable.
+  fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! discrete {
+  ($($int:ty),*) => {
+    $(
+      impl Discrete for $int {
+        const MIN: Self = <$int>::MIN;
+        const MAX: Self = <$int>::MAX;
+
+        fn to_i128(self) -> i128 {
+          i128::try_from(self).unwrap_or_else(|_| unreachable!())
+        }
+
+        fn from_i128(value: i128) -> Option<Self> {
+          Self::try_from(value).ok()
+        }
+      }
+    )*
+  };
+}
+
+discrete!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Narrows an `i128` interval to the part representable in `T`.
+fn clamp<T: Discrete>(lo: i128, hi: i128) -> Option<(T, T)> {
+  let lo = lo.max(T::MIN.to_i128());
+  let hi = hi.min(T::MAX.to_i128());
+  (lo <= hi)
+    .then(|| (T::from_i128(lo), T::from_i128(hi)))
+    .and_then(|(lo, hi)| lo.zip(hi))
+}
+
+/// A set of integers, stored as normalized inclusive intervals.
+///
+/// # Examples
+///
+/// 2022/15, counting the positions on a row that cannot hold a beacon:
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let mut covered = IntervalSet::from_iter([-2..=2, 12..=12, 2..=14, 16..=24]);
+/// assert_eq!(covered.to_string(), "{-2..=14, 16..=24}");
+///
+/// covered.remove(2..=2);
+/// assert_eq!(covered.count(), 25);
+/// assert!(!covered.contains(15));
+///
+/// let searched = IntervalSet::from_iter([0..=20]);
+/// let gaps = searched.difference(&covered);
+/// assert_eq!(gaps.to_string(), "{2..=2, 15..=15}");
+/// assert_eq!(gaps.union(&covered), covered.union(&searched));
+/// assert_eq!(searched.intersection(&covered).count(), 19);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+  intervals: Vec<(T, T)>,
+}
+
+impl<T: Discrete> IntervalSet<T> {
+  /// The empty set.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      intervals: Vec::new(),
+    }
+  }
+
+  /// Sorts and merges raw `(lo, hi)` pairs, dropping empty ones.
+  fn normalized(mut intervals: Vec<(T, T)>) -> Self {
+    intervals.retain(|(lo, hi)| lo <= hi);
+    intervals.sort_unstable();
+    let mut merged: Vec<(T, T)> = Vec::with_capacity(intervals.len());
+    for (lo, hi) in intervals {
+      match merged.last_mut() {
+        Some(last) if lo.to_i128() <= last.1.to_i128() + 1 => last.1 = last.1.max(hi),
+        _ => merged.push((lo, hi)),
+      }
+    }
+    Self { intervals: merged }
+  }
+
+  /// Adds every value of `range`.
+  pub fn insert(&mut self, range: RangeInclusive<T>) {
+    let mut intervals = mem::take(&mut self.intervals);
+    intervals.push(range.into_inner());
+    *self = Self::normalized(intervals);
+  }
+
+  /// Removes every value of `range`.
+  pub fn remove(&mut self, range: RangeInclusive<T>) {
+    *self = self.difference(&Self::from_iter([range]));
+  }
+
+  /// Whether `value` is in the set.
+  #[must_use]
+  pub fn contains(&self, value: T) -> bool {
+    let index = self.intervals.partition_point(|&(_, hi)| hi < value);
+    self
+      .intervals
+      .get(index)
+      .is_some_and(|&(lo, _)| lo <= value)
+  }
+
+  /// Whether the set has no values.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.intervals.is_empty()
+  }
+
+  /// Number of values in the set.
+  #[must_use]
+  pub fn count(&self) -> u128 {
+    self
+      .intervals
+      .iter()
+      .map(|&(lo, hi)| (hi.to_i128() - lo.to_i128()).unsigned_abs() + 1)
+      .sum()
+  }
+
+  /// Smallest value in the set.
+  #[must_use]
+  pub fn min(&self) -> Option<T> {
+    self.intervals.first().map(|&(lo, _)| lo)
+  }
+
+  /// Largest value in the set.
+  #[must_use]
+  pub fn max(&self) -> Option<T> {
+    self.intervals.last().map(|&(_, hi)| hi)
+  }
+
+  /// The intervals, in increasing order.
+  pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+    self.intervals.iter().map(|&(lo, hi)| lo..=hi)
+  }
+
+  /// Values in either set.
+  #[must_use]
+  pub fn union(&self, other: &Self) -> Self {
+    Self::normalized([self.intervals.as_slice(), &other.intervals].concat())
+  }
+
+  /// Values in both sets.
+  #[must_use]
+  pub fn intersection(&self, other: &Self) -> Self {
+    let (mut i, mut j) = (0, 0);
+    let mut intervals = Vec::new();
+    while let (Some(&(a_lo, a_hi)), Some(&(b_lo, b_hi))) =
+      (self.intervals.get(i), other.intervals.get(j))
+    {
+      let (lo, hi) = (a_lo.max(b_lo), a_hi.min(b_hi));
+      if lo <= hi {
+        intervals.push((lo, hi));
+      }
+      if a_hi < b_hi {
+        i += 1;
+      } else {
+        j += 1;
+      }
+    }
+    Self { intervals }
+  }
+
+  /// Values in this set but not in `other`.
+  #[must_use]
+  pub fn difference(&self, other: &Self) -> Self {
+    match (self.min(), self.max()) {
+      (Some(lo), Some(hi)) => self.intersection(&other.complement(lo..=hi)),
+      _ => Self::new(),
+    }
+  }
+
+  /// Values within `bounds` that are not in the set.
+  #[must_use]
+  pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+    let (lo, hi) = bounds.into_inner();
+    let mut cursor = lo.to_i128();
+    let mut intervals = Vec::new();
+    for &(start, end) in &self.intervals {
+      if start > hi {
+        break;
+      }
+      if start.to_i128() > cursor {
+        intervals.extend(clamp(cursor, start.to_i128() - 1));
+      }
+      cursor = cursor.max(end.to_i128() + 1);
+    }
+    intervals.extend(clamp(cursor, hi.to_i128()));
+    Self { intervals }
+  }
+
+  /// Moves every value by `offset`, dropping values that leave `T`'s range.
+  #[must_use]
+  pub fn shift(&self, offset: i128) -> Self {
+    let intervals = self
+      .intervals
+      .iter()
+      .filter_map(|&(lo, hi)| clamp(lo.to_i128() + offset, hi.to_i128() + offset))
+      .collect();
+    Self { intervals }
+  }
+}
+
+impl<T: Discrete> Default for IntervalSet<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Discrete> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+  fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> Self {
+    Self::normalized(ranges.into_iter().map(RangeInclusive::into_inner).collect())
+  }
+}
+
+impl<T: Discrete> Extend<RangeInclusive<T>> for IntervalSet<T> {
+  fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, ranges: I) {
+    let mut intervals = mem::take(&mut self.intervals);
+    intervals.extend(ranges.into_iter().map(RangeInclusive::into_inner));
+    *self = Self::normalized(intervals);
+  }
+}
+
+impl<T: Discrete> Display for IntervalSet<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "{{")?;
+    for (index, (lo, hi)) in self.intervals.iter().enumerate() {
+      if index > 0 {
+        write!(f, ", ")?;
+      }
+      write!(f, "{lo}..={hi}")?;
+    }
+    write!(f, "}}")
+  }
+}
+
+/// A piecewise offset mapping: source intervals move to new starts, every
+/// other value maps to itself.
+///
+/// Source intervals are expected not to overlap; where they do, the one
+/// inserted first wins.
+///
+/// # Examples
+///
+/// 2023/05's seed-to-soil map, pushing two seed ranges through:
+///
+/// ```
+/// use math::prelude::*;
+///
+/// let mut soil = RangeMap::new();
+/// soil.insert(98..=99, 50);
+/// soil.insert(50..=97, 52);
+///
+/// assert_eq!(soil.map(79), 81);
+/// assert_eq!(soil.map(99), 51);
+/// assert_eq!(soil.map(10), 10);
+///
+/// let seeds = IntervalSet::from_iter([79..=92, 55..=67, 96..=100]);
+/// assert_eq!(
+///   soil.apply(&seeds).to_string(),
+///   "{50..=51, 57..=69, 81..=94, 98..=100}"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeMap<T> {
+  pieces: Vec<(T, T, i128)>,
+}
+
+impl<T: Discrete> RangeMap<T> {
+  /// The identity mapping.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { pieces: Vec::new() }
+  }
+
+  /// Maps `source` onto the interval of the same length starting at
+  /// `destination`.
+  pub fn insert(&mut self, source: RangeInclusive<T>, destination: T) {
+    let (lo, hi) = source.into_inner();
+    if lo <= hi {
+      self
+        .pieces
+        .push((lo, hi, destination.to_i128() - lo.to_i128()));
+    }
+  }
+
+  /// Maps a single value; results outside `T`'s range saturate.
+  #[must_use]
+  pub fn map(&self, value: T) -> T {
+    self
+      .pieces
+      .iter()
+      .find(|&&(lo, hi, _)| lo <= value && value <= hi)
+      .map_or(value, |&(_, _, offset)| {
+        let mapped = value.to_i128() + offset;
+        T::from_i128(mapped).unwrap_or(if offset < 0 { T::MIN } else { T::MAX })
+      })
+  }
+
+  /// Maps every value of a set, splitting its intervals where the pieces
+  /// start and end.
+  ///
+  /// Values mapped outside `T`'s range are dropped.
+  #[must_use]
+  pub fn apply(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+    let mut remaining = set.clone();
+    let mut mapped = IntervalSet::new();
+    for &(lo, hi, offset) in &self.pieces {
+      let source = IntervalSet::from_iter([lo..=hi]);
+      mapped = mapped.union(&remaining.intersection(&source).shift(offset));
+      remaining = remaining.difference(&source);
+    }
+    mapped.union(&remaining)
+  }
+}
+
+impl<T: Discrete> FromIterator<(RangeInclusive<T>, T)> for RangeMap<T> {
+  fn from_iter<I: IntoIterator<Item = (RangeInclusive<T>, T)>>(pieces: I) -> Self {
+    let mut map = Self::new();
+    for (source, destination) in pieces {
+      map.insert(source, destination);
+    }
+    map
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    std::collections::BTreeSet,
+  };
+
+  /// Every `i8` in `set`, for comparing against a plain set of values.
+  fn values(set: &IntervalSet<i8>) -> BTreeSet<i8> {
+    set.iter().flatten().collect()
+  }
+
+  /// Deterministic ranges covering the whole `i8` domain, bounds included.
+  fn ranges(seed: u64) -> Vec<RangeInclusive<i8>> {
+    let mut state = seed;
+    let mut next = move || {
+      state = state
+        .wrapping_mul(6_364_136_223_846_793_005)
+        .wrapping_add(1_442_695_040_888_963_407);
+      i8::from_le_bytes([state.to_le_bytes()[7]])
+    };
+    (0..4)
+      .map(|_| {
+        let (a, b) = (next(), next());
+        match a % 4 {
+          0 => i8::MIN..=b,
+          1 => b..=i8::MAX,
+          _ => a.min(b)..=a.max(b),
+        }
+      })
+      .collect()
+  }
+
+  #[test]
+  fn operations_match_plain_sets() {
+    let all = (i8::MIN..=i8::MAX).collect::<BTreeSet<_>>();
+    for seed in 0..200 {
+      let (a, b) = (ranges(seed), ranges(seed + 1000));
+      let (x, y) = (
+        IntervalSet::from_iter(a.clone()),
+        IntervalSet::from_iter(b.clone()),
+      );
+      let (xs, ys) = (
+        a.into_iter().flatten().collect::<BTreeSet<_>>(),
+        b.into_iter().flatten().collect::<BTreeSet<_>>(),
+      );
+
+      assert_eq!(values(&x), xs);
+      assert_eq!(x.count(), xs.len() as u128);
+      assert_eq!(x.min(), xs.first().copied());
+      assert_eq!(x.max(), xs.last().copied());
+      assert!(all.iter().all(|&v| x.contains(v) == xs.contains(&v)));
+      assert_eq!(values(&x.union(&y)), &xs | &ys);
+      assert_eq!(values(&x.intersection(&y)), &xs & &ys);
+      assert_eq!(values(&x.difference(&y)), &xs - &ys);
+      assert_eq!(values(&x.complement(i8::MIN..=i8::MAX)), &all - &xs);
+    }
+  }
+
+  #[test]
+  fn touching_intervals_merge_at_the_bounds() {
+    let set = IntervalSet::from_iter([0..=9, u32::MAX - 5..=u32::MAX, 10..=20, 100..=u32::MAX - 6]);
+    assert_eq!(set.to_string(), format!("{{0..=20, 100..={}}}", u32::MAX));
+    assert!(set.contains(u32::MAX));
+    assert_eq!(set.max(), Some(u32::MAX));
+  }
+
+  #[test]
+  fn full_ranges_count_without_overflow() {
+    assert_eq!(
+      IntervalSet::from_iter([u64::MIN..=u64::MAX]).count(),
+      1 << 64
+    );
+    assert_eq!(
+      IntervalSet::from_iter([i64::MIN..=i64::MAX]).count(),
+      1 << 64
+    );
+    assert_eq!(IntervalSet::from_iter([usize::MAX..=usize::MAX]).count(), 1);
+  }
+
+  #[test]
+  fn complement_reaches_both_ends() {
+    let set = IntervalSet::from_iter([-5..=5]);
+    assert_eq!(
+      set.complement(i64::MIN..=i64::MAX).to_string(),
+      format!("{{{}..=-6, 6..={}}}", i64::MIN, i64::MAX)
+    );
+    assert!(
+      IntervalSet::from_iter([i64::MIN..=i64::MAX])
+        .complement(0..=10)
+        .is_empty()
+    );
+    assert_eq!(IntervalSet::<u8>::new().complement(250..=255).count(), 6);
+  }
+
+  #[test]
+  fn shift_drops_values_past_the_bounds() {
+    let set = IntervalSet::from_iter([0..=10, 250..=255u8]);
+    assert_eq!(set.shift(3).to_string(), "{3..=13, 253..=255}");
+    assert_eq!(set.shift(-5).to_string(), "{0..=5, 245..=250}");
+    assert!(set.shift(300).is_empty());
+    assert_eq!(set.shift(-255).to_string(), "{0..=0}");
+  }
+
+  #[test]
+  fn range_maps_saturate_and_drop_at_the_bounds() {
+    let map = RangeMap::from_iter([(250..=255u8, 253), (0..=4, 0)]);
+    assert_eq!(map.map(255), u8::MAX);
+    assert_eq!(map.map(251), 254);
+    assert_eq!(
+      map.apply(&IntervalSet::from_iter([248..=255])).to_string(),
+      "{248..=249, 253..=255}"
+    );
+  }
+}
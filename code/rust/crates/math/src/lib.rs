@@ -0,0 +1,10 @@
+//! Number, range and sequence utilities shared by puzzle solutions.
+
+pub mod cuboid;
+pub mod cycle;
+pub mod interval;
+pub mod prelude;
+
+mod _prelude {
+  pub use crate::prelude::*;
+}
@@ -0,0 +1,26 @@
+pub use {
+  crate::{
+    cuboid::*,
+    cycle::*,
+    interval::*,
+  },
+  std::{
+    array,
+    collections::{
+      HashMap,
+      hash_map::Entry,
+    },
+    fmt::{
+      Debug,
+      Display,
+      Formatter,
+      Result as FmtResult,
+    },
+    hash::Hash,
+    mem,
+    ops::{
+      Range,
+      RangeInclusive,
+    },
+  },
+};
@@ -0,0 +1,92 @@
+use crate::_prelude::*;
+
+pub type Result<T> = StdResult<T, Error>;
+
+#[derive(Debug, ThisError, Diagnostic)]
+pub enum Error {
+  #[error("{line}:{column}: expected {expected}, found {found}")]
+  #[diagnostic(code(parse::mismatch))]
+  Mismatch {
+    expected: String,
+    found: String,
+    line: usize,
+    column: usize,
+    #[source_code]
+    source_code: String,
+    #[label("expected {expected}")]
+    span: SourceSpan,
+  },
+
+  #[error("{line}:{column}: invalid `{text}`: {reason}")]
+  #[diagnostic(code(parse::invalid))]
+  Invalid {
+    text: String,
+    reason: String,
+    line: usize,
+    column: usize,
+    #[source_code]
+    source_code: String,
+    #[label("{reason}")]
+    span: SourceSpan,
+  },
+
+  #[error("{line}:{column}: unexpected trailing input `{text}`")]
+  #[diagnostic(
+    code(parse::trailing),
+    help("The pattern matched, but did not cover the whole input.")
+  )]
+  Trailing {
+    text: String,
+    line: usize,
+    column: usize,
+    #[source_code]
+    source_code: String,
+    #[label("not covered by the pattern")]
+    span: SourceSpan,
+  },
+
+  #[error("invalid pattern `{pattern}`: {reason}")]
+  #[diagnostic(
+    code(parse::pattern),
+    help("Fields are written `{{}}`; literal braces are escaped as `{{{{` and `}}}}`.")
+  )]
+  Pattern { pattern: String, reason: String },
+}
+
+impl Error {
+  /// Shifts the reported line of a scan error so it refers to line `number`
+  /// of a larger input.
+  ///
+  /// Scanners number lines from 1 within the text they were given;
+  /// `parse::lines` uses this to report positions within the whole input.
+  #[must_use]
+  pub const fn at_line(mut self, number: usize) -> Self {
+    match &mut self {
+      Self::Mismatch { line, .. } | Self::Invalid { line, .. } | Self::Trailing { line, .. } => {
+        *line += number.saturating_sub(1);
+      }
+      Self::Pattern { .. } => {}
+    }
+    self
+  }
+
+  /// The 1-based line and column an error points at, if it points anywhere.
+  #[must_use]
+  pub const fn location(&self) -> Option<(usize, usize)> {
+    match self {
+      Self::Mismatch { line, column, .. }
+      | Self::Invalid { line, column, .. }
+      | Self::Trailing { line, column, .. } => Some((*line, *column)),
+      Self::Pattern { .. } => None,
+    }
+  }
+
+  /// Shorthand for a malformed pattern.
+  #[must_use]
+  pub fn pattern(pattern: &str, reason: &str) -> Self {
+    Self::Pattern {
+      pattern: pattern.to_owned(),
+      reason: reason.to_owned(),
+    }
+  }
+}
@@ -0,0 +1,49 @@
+//! Declarative scanning of puzzle input lines.
+//!
+//! Most puzzle inputs are lines of fixed text with numbers and names in
+//! between. Instead of chains of `split` and `parse`, `scan!` takes the line
+//! and a pattern with `{}` fields, and returns the typed fields:
+//!
+//! ```
+//! use parse::prelude::*;
+//!
+//! let (x, y, bx, by) = scan!(
+//!   "Sensor at x=2, y=18: closest beacon is at x=-2, y=15",
+//!   "Sensor at x={}, y={}: closest beacon is at x={}, y={}" => i64, i64, i64, i64
+//! )
+//! .unwrap();
+//! assert_eq!(x + y + bx + by, 33);
+//! ```
+//!
+//! Fields can also be lists with a separator, or lists of groups matching
+//! their own pattern; see `scan!` for the full syntax. Mismatches are
+//! reported as `Error`s carrying the line, column and a labelled source
+//! span.
+
+pub mod error;
+mod macros;
+mod pattern;
+pub mod prelude;
+mod scanner;
+mod values;
+
+pub use {
+  error::{
+    Error,
+    Result,
+  },
+  macros::lines,
+  pattern::*,
+  scanner::*,
+  values::*,
+};
+
+mod _prelude {
+  pub use crate::{
+    error::{
+      Error,
+      Result,
+    },
+    prelude::*,
+  };
+}
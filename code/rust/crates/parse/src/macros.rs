@@ -0,0 +1,235 @@
+//! The `scan!` and `scan_lines!` macros.
+
+use crate::_prelude::*;
+
+/// Matches text against a pattern and reads its `{}` fields.
+///
+/// ```text
+/// scan!(input, "pattern" => field, field, ...)
+/// scan!(in scanner, stop, "pattern" => field, field, ...)
+/// ```
+///
+/// Each `field` describes one `{}` of the pattern, in order:
+///
+/// | Field                        | Reads                                     |
+/// |------------------------------|-------------------------------------------|
+/// | `T`                          | a `T: Scan`                               |
+/// | `[T; sep]`                   | `Vec<T>`, elements separated by `sep`     |
+/// | `[("pattern" => ...); sep]`  | `Vec` of tuples, each matching `pattern`  |
+///
+/// The result is a `parse::Result` of the single value, or of a tuple when
+/// there are several fields. The first form must consume all of `input`;
+/// the second continues from an existing scanner and is meant for `Scan`
+/// implementations.
+///
+/// # Examples
+///
+/// ```
+/// use parse::prelude::*;
+///
+/// let line = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
+/// let (sx, sy, bx, by) = scan!(
+///   line,
+///   "Sensor at x={}, y={}: closest beacon is at x={}, y={}" => i64, i64, i64, i64
+/// )
+/// .unwrap();
+/// assert_eq!((sx, sy, bx, by), (2, 18, -2, 15));
+///
+/// let error = scan!(line, "Sensor at x={}, y={}; closest" => i64, i64).unwrap_err();
+/// assert_eq!(error.to_string(), "1:20: expected `; closest`, found `:`");
+/// ```
+///
+/// Lists and repeated groups, with 2023/19's parts:
+///
+/// ```
+/// use parse::prelude::*;
+///
+/// let ratings = scan!(
+///   "{x=787,m=2655,a=1222,s=2876}",
+///   "{{{}}}" => [("{}={}" => char, u32); ","]
+/// )
+/// .unwrap();
+/// assert_eq!(ratings, [('x', 787), ('m', 2655), ('a', 1222), ('s', 2876)]);
+///
+/// let (id, winning, owned) = scan!(
+///   "Card  12: 41 48  6 | 83  6 17",
+///   "Card {}: {} | {}" => u32, [u8; " "], [u8; " "]
+/// )
+/// .unwrap();
+/// assert_eq!((id, winning, owned), (12, vec![41, 48, 6], vec![83, 6, 17]));
+/// ```
+///
+/// Lines with several shapes implement `Scan` with `Scanner::attempt`, as
+/// 2023/19's workflows do:
+///
+/// ```
+/// use parse::prelude::*;
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// enum Rule {
+///   If(char, char, u32, String),
+///   Else(String),
+/// }
+///
+/// impl<'a> Scan<'a> for Rule {
+///   fn scan(scanner: &mut Scanner<'a>, stop: Stop<'_>) -> ParseResult<Self> {
+///     let test = scanner.attempt(|scanner| {
+///       scan!(in scanner, stop, "{}{}{}:{}" => char, char, u32, String)
+///     });
+///     match test {
+///       Some((category, op, value, target)) => Ok(Self::If(category, op, value, target)),
+///       None => String::scan(scanner, stop).map(Self::Else),
+///     }
+///   }
+/// }
+///
+/// let (name, rules) = scan!("px{a<2006:qkq,m>2090:A,rfg}", "{}{{{}}}" => String, [Rule; ","])
+///   .unwrap();
+/// assert_eq!(name, "px");
+/// assert_eq!(rules, [
+///   Rule::If('a', '<', 2006, "qkq".to_owned()),
+///   Rule::If('m', '>', 2090, "A".to_owned()),
+///   Rule::Else("rfg".to_owned()),
+/// ]);
+/// ```
+#[macro_export]
+macro_rules! scan {
+  (@fields $fields:ident [$($done:expr),*]) => {
+    $crate::scan!(@value [$($done),*])
+  };
+  (@fields $fields:ident [$($done:expr),*]
+    [($pattern:literal => $($inner:tt)+); $separator:expr] $(, $($rest:tt)*)?
+  ) => {
+    $crate::scan!(@fields $fields [$($done,)* $fields.list($separator, |scanner, stop| {
+      $crate::scan!(in scanner, stop, $pattern => $($inner)+)
+    })?] $($($rest)*)?)
+  };
+  (@fields $fields:ident [$($done:expr),*] [$ty:ty; $separator:expr] $(, $($rest:tt)*)?) => {
+    $crate::scan!(@fields $fields [$($done,)* $fields.list($separator, |scanner, stop| {
+      <$ty as $crate::Scan>::scan(scanner, stop)
+    })?] $($($rest)*)?)
+  };
+  (@fields $fields:ident [$($done:expr),*] $ty:ty $(, $($rest:tt)*)?) => {
+    $crate::scan!(@fields $fields [$($done,)* $fields.value::<$ty>()?] $($($rest)*)?)
+  };
+  (@value [$value:expr]) => {
+    $crate::Result::Ok($value)
+  };
+  (@value [$($value:expr),*]) => {
+    $crate::Result::Ok(($($value),*))
+  };
+  (in $scanner:expr, $stop:expr, $pattern:literal => $($fields:tt)+) => {
+    $crate::Pattern::new($pattern).and_then(|pattern| {
+      pattern.scan($scanner, $stop, |fields| $crate::scan!(@fields fields [] $($fields)+))
+    })
+  };
+  ($input:expr, $pattern:literal => $($fields:tt)+) => {{
+    let mut scanner = $crate::Scanner::new($input);
+    $crate::scan!(in &mut scanner, $crate::Stop::END, $pattern => $($fields)+)
+      .and_then(|value| scanner.finish().map(|()| value))
+  }};
+}
+
+/// Applies `scan!` to every line of an input.
+///
+/// Errors report their line within the whole input.
+///
+/// # Examples
+///
+/// ```
+/// use parse::prelude::*;
+///
+/// let input = "p=0,4 v=3,-3\np=6,3 v=-1,-3\n";
+/// let robots = scan_lines!(input, "p={},{} v={},{}" => i32, i32, i32, i32).unwrap();
+/// assert_eq!(robots, [(0, 4, 3, -3), (6, 3, -1, -3)]);
+///
+/// let error = scan_lines!("1-3 a\n1-3\n", "{}-{} {}" => u8, u8, char).unwrap_err();
+/// assert_eq!(error.location(), Some((2, 4)));
+/// ```
+#[macro_export]
+macro_rules! scan_lines {
+  ($input:expr, $pattern:literal => $($fields:tt)+) => {
+    $crate::lines($input, |line| $crate::scan!(line, $pattern => $($fields)+))
+  };
+}
+
+/// Scans every line of `input` with `scan`, collecting the results.
+///
+/// # Errors
+/// Returns the first error, with its line shifted to the line's position in
+/// `input`.
+pub fn lines<T>(input: &str, mut scan: impl FnMut(&str) -> Result<T>) -> Result<Vec<T>> {
+  input
+    .lines()
+    .enumerate()
+    .map(|(index, line)| scan(line).map_err(|error| error.at_line(index + 1)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::_prelude::*;
+
+  fn location<T>(result: Result<T>) -> Option<(usize, usize)> {
+    result.err().and_then(|error| error.location())
+  }
+
+  #[test]
+  fn mismatch_points_at_the_offending_character() {
+    let error = scan!("x=a", "x={}" => i32).unwrap_err();
+    assert!(matches!(error, Error::Mismatch { .. }));
+    assert_eq!(error.location(), Some((1, 3)));
+
+    assert_eq!(
+      location(scan!("x=1 y=2", "x={}, y={}" => i32, i32)),
+      Some((1, 4))
+    );
+  }
+
+  #[test]
+  fn end_of_input_points_past_the_last_character() {
+    let error = scan!("a=", "a={}" => u8).unwrap_err();
+    assert_eq!(error.location(), Some((1, 3)));
+    assert_eq!(
+      error.to_string(),
+      "1:3: expected an integer, found end of input"
+    );
+  }
+
+  #[test]
+  fn invalid_points_at_the_start_of_the_value() {
+    let error = scan!("n=-300", "n={}" => i8).unwrap_err();
+    assert!(matches!(error, Error::Invalid { .. }));
+    assert_eq!(error.location(), Some((1, 3)));
+  }
+
+  #[test]
+  fn trailing_points_at_the_first_unconsumed_character() {
+    let error = scan!("1,2,3", "{},{}" => u8, u8).unwrap_err();
+    assert!(matches!(error, Error::Trailing { .. }));
+    assert_eq!(error.location(), Some((1, 4)));
+  }
+
+  #[test]
+  fn columns_count_characters_not_bytes() {
+    assert_eq!(location(scan!("é→→x", "é→→{}" => u8)), Some((1, 4)));
+  }
+
+  #[test]
+  fn errors_inside_lists_and_groups_keep_their_column() {
+    assert_eq!(location(scan!("1,2,x", "{}" => [u8; ","])), Some((1, 5)));
+    assert_eq!(
+      location(scan!("{x=787,m=b}", "{{{}}}" => [("{}={}" => char, u32); ","])),
+      Some((1, 10))
+    );
+  }
+
+  #[test]
+  fn scan_lines_reports_the_line_within_the_input() {
+    let input = "p=0,4\np=6,3\np=1,?\n";
+    assert_eq!(
+      location(scan_lines!(input, "p={},{}" => i32, i32)),
+      Some((3, 5))
+    );
+  }
+}
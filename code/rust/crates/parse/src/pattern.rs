@@ -0,0 +1,160 @@
+//! Runtime side of `scan!` patterns.
+//!
+//! A pattern is literal text with `{}` fields, using the brace escapes of
+//! `format!`. `scan!` expands into a `Pattern::scan` call whose closure reads
+//! one value per field through `Fields`, in order; literals are matched
+//! between fields automatically.
+
+use crate::_prelude::*;
+
+/// A parsed `scan!` pattern: the literals around its fields.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern<'p> {
+  text: &'p str,
+  literals: Vec<String>,
+}
+
+impl<'p> Pattern<'p> {
+  /// Splits a pattern into literals.
+  ///
+  /// # Errors
+  /// Returns `Error::Pattern` for an unescaped brace that is not part of a
+  /// `{}` field.
+  pub fn new(text: &'p str) -> Result<Self> {
+    let mut literals = vec![String::new()];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+      match (c, chars.peek()) {
+        ('{', Some('{')) | ('}', Some('}')) => {
+          chars.next();
+          literals
+            .last_mut()
+            .unwrap_or_else(|| unreachable!())
+            .push(c);
+        }
+        ('{', Some('}')) => {
+          chars.next();
+          literals.push(String::new());
+        }
+        ('{' | '}', _) => return Err(Error::pattern(text, "unmatched brace")),
+        _ => literals
+          .last_mut()
+          .unwrap_or_else(|| unreachable!())
+          .push(c),
+      }
+    }
+    Ok(Self { text, literals })
+  }
+
+  /// Number of `{}` fields.
+  #[must_use]
+  pub const fn len(&self) -> usize {
+    self.literals.len() - 1
+  }
+
+  /// Whether the pattern is a plain literal.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Matches the pattern at the scanner's cursor, reading the fields with
+  /// `read`.
+  ///
+  /// `stop` is where the pattern's last field may end at the latest; it is
+  /// `Stop::END` for a whole line.
+  ///
+  /// # Errors
+  /// Returns the first error of a literal or field, or `Error::Pattern` if
+  /// `read` does not consume exactly one value per field.
+  pub fn scan<'a, T>(
+    &self,
+    scanner: &mut Scanner<'a>,
+    stop: Stop<'_>,
+    read: impl FnOnce(&mut Fields<'_, 'a>) -> Result<T>,
+  ) -> Result<T> {
+    let mut fields = Fields {
+      pattern: self,
+      scanner,
+      stop,
+      next: 0,
+    };
+    let value = read(&mut fields)?;
+    if fields.next != self.len() {
+      return Err(Error::pattern(
+        self.text,
+        &format!(
+          "{} field(s), but {} value(s) were read",
+          self.len(),
+          fields.next
+        ),
+      ));
+    }
+    fields.scanner.expect(&self.literals[fields.next])?;
+    Ok(value)
+  }
+}
+
+/// The fields of a pattern being matched, read left to right.
+#[derive(Debug)]
+pub struct Fields<'s, 'a> {
+  pattern: &'s Pattern<'s>,
+  scanner: &'s mut Scanner<'a>,
+  stop: Stop<'s>,
+  next: usize,
+}
+
+impl<'a> Fields<'_, 'a> {
+  /// Reads the next field as a `T`.
+  ///
+  /// # Errors
+  /// Returns the error of the literal before the field or of `T::scan`.
+  pub fn value<T: Scan<'a>>(&mut self) -> Result<T> {
+    let (scanner, stop) = self.enter()?;
+    T::scan(scanner, stop)
+  }
+
+  /// Reads the next field as a possibly empty list of `element`s separated
+  /// by `separator`.
+  ///
+  /// The list ends where the field's stop matches (so a separator that also
+  /// starts the following literal is not mistaken for another element), or
+  /// where no separator follows.
+  ///
+  /// # Errors
+  /// Returns the error of the literal before the field or of an element.
+  pub fn list<T>(
+    &mut self,
+    separator: &str,
+    mut element: impl FnMut(&mut Scanner<'a>, Stop<'_>) -> Result<T>,
+  ) -> Result<Vec<T>> {
+    let (scanner, stop) = self.enter()?;
+    let mut items = Vec::new();
+    if stop.matches(scanner.rest()) {
+      return Ok(items);
+    }
+    let item = Stop::at(separator, &stop);
+    loop {
+      items.push(element(scanner, item)?);
+      if stop.matches(scanner.rest()) || !scanner.eat(separator) {
+        return Ok(items);
+      }
+    }
+  }
+
+  /// Consumes the literal before the next field and returns where the field
+  /// ends.
+  fn enter(&mut self) -> Result<(&mut Scanner<'a>, Stop<'_>)> {
+    let index = self.next;
+    if index >= self.pattern.len() {
+      return Err(Error::pattern(
+        self.pattern.text,
+        &format!("{} field(s), but more values were read", self.pattern.len()),
+      ));
+    }
+    self.next += 1;
+    self.scanner.expect(&self.pattern.literals[index])?;
+    let stop = Stop::at(&self.pattern.literals[index + 1], &self.stop);
+    Ok((self.scanner, stop))
+  }
+}
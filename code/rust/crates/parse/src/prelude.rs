@@ -0,0 +1,26 @@
+pub use {
+  crate::{
+    error::{
+      Error as ParseError,
+      Result as ParseResult,
+    },
+    pattern::*,
+    scan,
+    scan_lines,
+    scanner::*,
+    values::*,
+  },
+  miette::{
+    Diagnostic,
+    SourceSpan,
+  },
+  std::{
+    fmt::{
+      Debug,
+      Display,
+    },
+    result::Result as StdResult,
+    str::FromStr,
+  },
+  thiserror::Error as ThisError,
+};
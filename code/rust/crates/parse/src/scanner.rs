@@ -0,0 +1,293 @@
+//! A cursor over the text being scanned.
+//!
+//! `Scanner` is what `scan!` drives and what `Scan` implementations
+//! consume from. It only ever moves forward (except through
+//! `Scanner::attempt`) and knows enough about its position to build errors
+//! that point at the offending line and column.
+//!
+//! Whitespace in literals is flexible: any run of whitespace in a literal
+//! matches one or more whitespace characters of the input, so the aligned
+//! columns of inputs like 2023/4 need no special care.
+//!
+//! # Examples
+//!
+//! ```
+//! use parse::prelude::*;
+//!
+//! let mut scanner = Scanner::new("Card   3:  1 21");
+//! assert!(scanner.eat("Card "));
+//! assert_eq!(scanner.integer::<u32>().unwrap(), 3);
+//! scanner.expect(": ").unwrap();
+//! assert_eq!(scanner.rest(), "1 21");
+//!
+//! let error = scanner.expect(",").unwrap_err();
+//! assert_eq!(error.location(), Some((1, 12)));
+//! assert_eq!(error.to_string(), "1:12: expected `,`, found `1`");
+//! ```
+
+use crate::_prelude::*;
+
+/// Where a free-form field ends.
+///
+/// Text fields (`String`, `&str`) have no natural end, so they run until the
+/// input ends or one of the stop literals starts: the literal following the
+/// field in its pattern, then any enclosing list separator or pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stop<'p> {
+  text: &'p str,
+  outer: Option<&'p Self>,
+}
+
+impl<'p> Stop<'p> {
+  /// Stops only at the end of the input.
+  pub const END: Stop<'static> = Stop {
+    text: "",
+    outer: None,
+  };
+
+  /// Stops at `text`, or wherever `outer` stops.
+  #[must_use]
+  pub const fn at(text: &'p str, outer: &'p Self) -> Self {
+    Self {
+      text,
+      outer: Some(outer),
+    }
+  }
+
+  /// Whether a field should end right before `rest`.
+  #[must_use]
+  pub fn matches(&self, rest: &str) -> bool {
+    rest.is_empty()
+      || (!self.text.is_empty() && literal_len(rest, self.text).is_some())
+      || self.outer.is_some_and(|outer| outer.matches(rest))
+  }
+}
+
+/// Length of the prefix of `rest` matched by `literal`, if it matches.
+fn literal_len(rest: &str, literal: &str) -> Option<usize> {
+  let mut input = rest.char_indices().peekable();
+  let mut expected = literal.chars().peekable();
+  while let Some(wanted) = expected.next() {
+    if wanted.is_whitespace() {
+      while expected.next_if(|c| c.is_whitespace()).is_some() {}
+      input.next_if(|(_, c)| c.is_whitespace())?;
+      while input.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    } else {
+      input.next_if(|&(_, c)| c == wanted)?;
+    }
+  }
+  Some(input.peek().map_or(rest.len(), |&(index, _)| index))
+}
+
+/// A forward-only cursor over a piece of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scanner<'a> {
+  source: &'a str,
+  position: usize,
+}
+
+impl<'a> Scanner<'a> {
+  /// Starts scanning at the beginning of `source`.
+  #[must_use]
+  pub const fn new(source: &'a str) -> Self {
+    Self {
+      source,
+      position: 0,
+    }
+  }
+
+  /// The text that has not been consumed yet.
+  #[must_use]
+  pub fn rest(&self) -> &'a str {
+    &self.source[self.position..]
+  }
+
+  /// Byte offset of the cursor within the source.
+  #[must_use]
+  pub const fn position(&self) -> usize {
+    self.position
+  }
+
+  /// Whether everything has been consumed.
+  #[must_use]
+  pub const fn is_empty(&self) -> bool {
+    self.position == self.source.len()
+  }
+
+  /// Consumes `literal` if the input starts with it.
+  pub fn eat(&mut self, literal: &str) -> bool {
+    literal_len(self.rest(), literal).is_some_and(|len| {
+      self.position += len;
+      true
+    })
+  }
+
+  /// Consumes `literal`.
+  ///
+  /// # Errors
+  /// Returns `Error::Mismatch` if the input does not start with `literal`.
+  pub fn expect(&mut self, literal: &str) -> Result<()> {
+    if self.eat(literal) {
+      Ok(())
+    } else {
+      Err(self.mismatch(&format!("`{literal}`")))
+    }
+  }
+
+  /// Consumes characters while `predicate` holds.
+  pub fn take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+    let rest = self.rest();
+    let len = rest
+      .char_indices()
+      .find(|&(_, c)| !predicate(c))
+      .map_or(rest.len(), |(index, _)| index);
+    self.position += len;
+    &rest[..len]
+  }
+
+  /// Consumes everything up to where `stop` matches.
+  pub fn until(&mut self, stop: Stop<'_>) -> &'a str {
+    let rest = self.rest();
+    let len = rest
+      .char_indices()
+      .map(|(index, _)| index)
+      .find(|&index| stop.matches(&rest[index..]))
+      .unwrap_or(rest.len());
+    self.position += len;
+    &rest[..len]
+  }
+
+  /// Consumes a single character.
+  ///
+  /// # Errors
+  /// Returns `Error::Mismatch` at the end of the input.
+  pub fn char(&mut self) -> Result<char> {
+    let c = self
+      .rest()
+      .chars()
+      .next()
+      .ok_or_else(|| self.mismatch("a character"))?;
+    self.position += c.len_utf8();
+    Ok(c)
+  }
+
+  /// Consumes an optionally signed decimal number.
+  ///
+  /// # Errors
+  /// Returns `Error::Mismatch` if there are no digits, or `Error::Invalid` if
+  /// the number does not fit `T`.
+  pub fn integer<T>(&mut self) -> Result<T>
+  where
+    T: FromStr,
+    T::Err: Display,
+  {
+    let start = self.position;
+    let rest = self.rest();
+    let sign = usize::from(rest.starts_with(['-', '+']));
+    let digits = rest[sign..].bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+      return Err(self.mismatch("an integer"));
+    }
+    self.position += sign + digits;
+    self.parsed(start, "integer")
+  }
+
+  /// Parses the text consumed since `start` with `FromStr`.
+  ///
+  /// This is the building block for `Scan` implementations of types that
+  /// already know how to parse themselves.
+  ///
+  /// # Errors
+  /// Returns `Error::Invalid` if parsing fails; the cursor is left after the
+  /// consumed text either way.
+  pub fn parsed<T>(&self, start: usize, kind: &str) -> Result<T>
+  where
+    T: FromStr,
+    T::Err: Display,
+  {
+    let text = &self.source[start..self.position];
+    text
+      .parse()
+      .map_err(|error: T::Err| self.invalid(start, kind, &error.to_string()))
+  }
+
+  /// Runs `scan`, rewinding the cursor if it fails.
+  ///
+  /// Useful for inputs whose lines come in several shapes.
+  pub fn attempt<T>(&mut self, scan: impl FnOnce(&mut Self) -> Result<T>) -> Option<T> {
+    let position = self.position;
+    scan(self).inspect_err(|_| self.position = position).ok()
+  }
+
+  /// Checks that everything has been consumed.
+  ///
+  /// # Errors
+  /// Returns `Error::Trailing` otherwise.
+  pub fn finish(&self) -> Result<()> {
+    if self.is_empty() {
+      return Ok(());
+    }
+    let (line, column) = self.line_column(self.position);
+    Err(Error::Trailing {
+      text: self.rest().to_owned(),
+      line,
+      column,
+      source_code: self.source.to_owned(),
+      span: (self.position, self.rest().len()).into(),
+    })
+  }
+
+  /// An error for input that does not look like `expected` at the cursor.
+  #[must_use]
+  pub fn mismatch(&self, expected: &str) -> Error {
+    let (line, column) = self.line_column(self.position);
+    let rest = self.rest();
+    let token = rest
+      .char_indices()
+      .take_while(|&(_, c)| !c.is_whitespace())
+      .take(12)
+      .last()
+      .map_or(0, |(index, c)| index + c.len_utf8());
+    let found = match rest.chars().next() {
+      None => "end of input".to_owned(),
+      Some(c) if token == 0 => format!("{c:?}"),
+      Some(_) => format!("`{}`", &rest[..token]),
+    };
+    Error::Mismatch {
+      expected: expected.to_owned(),
+      found,
+      line,
+      column,
+      source_code: self.source.to_owned(),
+      span: (self.position, token).into(),
+    }
+  }
+
+  /// An error for the text consumed since `start`, which looked like a
+  /// `kind` but could not be converted.
+  #[must_use]
+  pub fn invalid(&self, start: usize, kind: &str, reason: &str) -> Error {
+    let (line, column) = self.line_column(start);
+    Error::Invalid {
+      text: self.source[start..self.position].to_owned(),
+      reason: format!("{reason} (expected {kind})"),
+      line,
+      column,
+      source_code: self.source.to_owned(),
+      span: (start, self.position - start).into(),
+    }
+  }
+
+  /// 1-based line and column of a byte offset.
+  fn line_column(&self, offset: usize) -> (usize, usize) {
+    let before = &self.source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+      .rfind('\n')
+      .map_or(before, |newline| &before[newline + 1..])
+      .chars()
+      .count()
+      + 1;
+    (line, column)
+  }
+}
@@ -0,0 +1,85 @@
+//! Types that can be read out of a pattern field.
+//!
+//! Integers read an optionally signed decimal number, `char` reads a single
+//! character, and `String`/`&str` read free-form text up to the field's
+//! `Stop`. Puzzle-specific types implement `Scan` themselves, usually with
+//! a few `Scanner` calls or a nested `scan!`.
+//!
+//! # Examples
+//!
+//! ```
+//! use parse::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, Eq)]
+//! enum Turn {
+//!   Left(u32),
+//!   Right(u32),
+//! }
+//!
+//! impl Scan<'_> for Turn {
+//!   fn scan(scanner: &mut Scanner<'_>, _: Stop<'_>) -> ParseResult<Self> {
+//!     match scanner.char()? {
+//!       'L' => Ok(Self::Left(scanner.integer()?)),
+//!       'R' => Ok(Self::Right(scanner.integer()?)),
+//!       _ => Err(scanner.mismatch("`L` or `R`")),
+//!     }
+//!   }
+//! }
+//!
+//! let turns = scan!("R5, L12, R3", "{}" => [Turn; ", "]).unwrap();
+//! assert_eq!(turns, [Turn::Right(5), Turn::Left(12), Turn::Right(3)]);
+//! ```
+
+use crate::_prelude::*;
+
+/// A value that can fill a `{}` field of a `scan!` pattern.
+pub trait Scan<'a>: Sized {
+  /// Reads a value from the scanner.
+  ///
+  /// Implementations for self-delimiting values (numbers, single
+  /// characters) may ignore `stop`; free-form ones must not consume input
+  /// where it matches.
+  ///
+  /// # Errors
+  /// Returns an error pointing at the input that could not be read.
+  fn scan(scanner: &mut Scanner<'a>, stop: Stop<'_>) -> Result<Self>;
+}
+
+macro_rules! integers {
+  ($($int:ty),*) => {
+    $(
+      impl Scan<'_> for $int {
+        fn scan(scanner: &mut Scanner<'_>, _: Stop<'_>) -> Result<Self> {
+          scanner.integer()
+        }
+      }
+    )*
+  };
+}
+
+integers!(
+  i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl Scan<'_> for char {
+  fn scan(scanner: &mut Scanner<'_>, _: Stop<'_>) -> Result<Self> {
+    scanner.char()
+  }
+}
+
+impl<'a> Scan<'a> for &'a str {
+  fn scan(scanner: &mut Scanner<'a>, stop: Stop<'_>) -> Result<Self> {
+    let text = scanner.until(stop);
+    if text.is_empty() {
+      Err(scanner.mismatch("text"))
+    } else {
+      Ok(text)
+    }
+  }
+}
+
+impl Scan<'_> for String {
+  fn scan(scanner: &mut Scanner<'_>, stop: Stop<'_>) -> Result<Self> {
+    <&str>::scan(scanner, stop).map(ToOwned::to_owned)
+  }
+}
@@ -10,6 +10,7 @@ use {
       get_env_value,
     },
   },
+  ::env::secret::Secret,
   dotenv_rs::var,
   once_cell::sync::Lazy,
   std::{
@@ -60,7 +61,7 @@ pub fn get_aoc_token() -> String {
       if value.is_empty() {
         Err(AdminError::unset_session_token(key))
       } else if value.len() != expected_length || !value.chars().all(|c| c.is_ascii_hexdigit()) {
-        Err(AdminError::InvalidSessionToken(value))
+        Err(AdminError::InvalidSessionToken(Secret::new(value)))
       } else {
         Ok(value)
       }
@@ -1,3 +1,4 @@
+use ::env::secret::Secret;
 use std::{
   borrow::Cow,
   error::{
@@ -19,7 +20,7 @@ pub enum AdminError {
   VariableNotSet(Cow<'static, str>),
 
   #[error("The session token {0} is invalid. Please refer to the README for more information.")]
-  InvalidSessionToken(String),
+  InvalidSessionToken(Secret<String>),
 
   #[error(
     "The session token is necessary to interact with the API.\nPlease refer to the README for more information."
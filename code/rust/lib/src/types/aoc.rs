@@ -1,4 +1,5 @@
 use {
+  ::env::secret::Secret,
   crate::{
     AdminError,
     AoC,
@@ -35,7 +36,7 @@ impl AoC {
   }
 
   pub fn with_session_token(mut self, token: &str) -> Self {
-    self.token = token.to_string();
+    self.token = Secret::new(token.to_string()).register();
     self
   }
 
@@ -49,8 +50,8 @@ impl AoC {
     self
   }
 
-  fn default_session_token() -> String {
-    get_dotenv("AOC_SESSION_TOKEN").unwrap_or_default()
+  fn default_session_token() -> Secret<String> {
+    Secret::new(get_dotenv("AOC_SESSION_TOKEN").unwrap_or_default()).register()
   }
 
   fn default_session_year() -> u16 {
@@ -80,7 +81,7 @@ impl AoC {
   }
 
   fn get_token(&self) -> Result<String, AdminError> {
-    Ok(self.token.clone())
+    Ok(self.token.expose().clone())
     // const EXPECTED_TOKEN_LENGTH: usize = 128;
 
     // if self.token.is_empty() {
@@ -1,5 +1,6 @@
 use {
   crate::GlobalEnvironment,
+  ::env::secret::Secret,
   cargo_metadata::{
     Metadata,
     MetadataCommand,
@@ -13,7 +14,7 @@ impl Default for GlobalEnvironment {
       cargo_meta: get_metadata(),
       workspace: PathBuf::new(),
       aoc_home: PathBuf::new(),
-      aoc_session_token: String::new(),
+      aoc_session_token: Secret::default(),
       establish_environment: (),
     }
   }
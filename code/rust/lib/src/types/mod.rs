@@ -2,6 +2,8 @@ pub mod aoc;
 mod environment;
 pub mod package;
 
+use ::env::secret::Secret;
+
 /// Struct for formatting an incremental package name with leading zeros.
 #[derive(Debug, PartialEq)]
 pub struct Package {
@@ -19,7 +21,7 @@ pub struct Package {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct AoC {
-  pub token: String,
+  pub token: Secret<String>,
   pub day: u8,
   pub year: u16,
 }
@@ -28,6 +30,6 @@ pub struct GlobalEnvironment {
   pub cargo_meta: cargo_metadata::Metadata,
   pub workspace: std::path::PathBuf,
   pub aoc_home: std::path::PathBuf,
-  pub aoc_session_token: String,
+  pub aoc_session_token: Secret<String>,
   pub establish_environment: (),
 }
@@ -1,4 +1,5 @@
 use {
+  ::env::secret::Secret,
   crate::{
     AdminError,
     AoC,
@@ -79,7 +80,7 @@ impl Package {
   }
 
   pub fn with_aoc_token(mut self, token: &str) -> Self {
-    self.aoc.token = token.to_string();
+    self.aoc.token = Secret::new(token.to_string()).register();
     self.is_aoc = true;
     self
   }
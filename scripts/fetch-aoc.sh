@@ -16,6 +16,7 @@ SCRIPT_HEAD="Advent of Code - Asset Fetcher"
 PRJ_ROOT="${PRJ_ROOT:-$("${SCRIPT_DIR}/locate-root.sh")}"
 DATA_DIR="${PRJ_ROOT}/assets/data"
 SESSION_KEY_FILE="${PRJ_ROOT}/.env/session.key"
+SESSION_STAMP_FILE="${PRJ_ROOT}/.cache/session.validated"
 AOC_BASE_URL="https://adventofcode.com"
 FIRST_YEAR=2015
 msg=""
@@ -605,6 +606,9 @@ fetch_with_curl() {
 			# Verify we actually got content
 			if [ -s "${output_file}" ]; then
 				log_info "Successfully saved to: ${output_file}"
+				#> Record when the session key last worked, for `aoc doctor`
+				mkdir -p "$(dirname "${SESSION_STAMP_FILE}")"
+				: >"${SESSION_STAMP_FILE}"
 				return 0
 			else
 				# Empty response with HTTP 200 is a sign of rate limiting
@@ -678,6 +682,7 @@ fetch_with_curl() {
 		401 | 403)
 			# Authentication errors - don't retry
 			printf "HTTP %s: Invalid or expired session key\n" "${http_code}" >&2
+			rm -f "${SESSION_STAMP_FILE}"
 			return 1
 			;;
 		000)